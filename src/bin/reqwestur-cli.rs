@@ -0,0 +1,251 @@
+//! A command-line runner for the requests built in Reqwestur
+//!
+//! Replays the saved requests, the history or an exported file without launching the UI.
+
+use std::process::ExitCode;
+
+use reqwestur::utils::{
+    engine,
+    exports::{self, ExportType},
+    oauth2::TokenCache,
+    request::Request,
+    reqwestur::Reqwestur,
+    traits::ToStringForeign,
+};
+
+const USAGE: &str = "Usage: reqwestur-cli [OPTIONS] [FILE]
+
+Sends the requests saved in Reqwestur, or those in an exported JSON, HTTP, Postman, HAR or OpenAPI FILE.
+
+Options:
+      --history          Use the request history instead of the saved requests
+  -e, --env <NAME>       Fill in the variables of the environment called NAME
+  -r, --request <INDEX>  Only send the request at INDEX (starting at 1)
+  -l, --list             List the requests without sending them
+  -q, --quiet            Only print the status line of each response
+  -h, --help             Print this message
+
+Environment:
+  REQWESTUR_VAULT_PASSWORD  The master password used to unlock secret variables and certificate passphrases";
+
+/// The environment variable holding the vault's master password
+const VAULT_PASSWORD: &str = "REQWESTUR_VAULT_PASSWORD";
+
+/// Where the requests are loaded from
+enum Source {
+    Saved,
+    History,
+    File(std::path::PathBuf),
+}
+
+/// The parsed command-line arguments
+struct Arguments {
+    source: Source,
+    environment: Option<String>,
+    index: Option<usize>,
+    list: bool,
+    quiet: bool,
+}
+
+impl Arguments {
+    /// Parse the arguments, returning `None` if the usage should be printed
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut arguments = Self {
+            source: Source::Saved,
+            environment: None,
+            index: None,
+            list: false,
+            quiet: false,
+        };
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--history" => arguments.source = Source::History,
+                "-e" | "--env" => {
+                    let value = args.next().ok_or("Missing a value for --env.")?;
+                    arguments.environment = Some(value);
+                }
+                "-l" | "--list" => arguments.list = true,
+                "-q" | "--quiet" => arguments.quiet = true,
+                "-r" | "--request" => {
+                    let value = args.next().ok_or("Missing a value for --request.")?;
+                    match value.parse::<usize>() {
+                        Ok(index) if index > 0 => arguments.index = Some(index),
+                        _ => return Err(format!("Invalid request index: {value}")),
+                    }
+                }
+                flag if flag.starts_with('-') => return Err(format!("Unknown option: {flag}")),
+                file => arguments.source = Source::File(file.into()),
+            }
+        }
+
+        Ok(Some(arguments))
+    }
+}
+
+/// Load the requests from the selected source, and the state their clients are built from
+fn load(source: &Source, environment: Option<&str>) -> Result<(Vec<Request>, Reqwestur), String> {
+    let mut app = Reqwestur::from_storage();
+    if let (Ok(app), Ok(password)) = (&mut app, std::env::var(VAULT_PASSWORD)) {
+        // Unlocking creates a vault that doesn't exist, which is only done from the application
+        if !app.vault.exists() {
+            return Err(format!(
+                "Could not unlock the vault - there's no vault to unlock, unset {VAULT_PASSWORD} or create one in the application."
+            ));
+        }
+        app.unlock_vault(&password)
+            .map_err(|error| format!("Could not unlock the vault - {error}"))?;
+    }
+
+    let mut requests = match source {
+        Source::Saved => app.clone()?.saved_requests,
+        Source::History => app.clone()?.history.lock().unwrap().clone(),
+        Source::File(file_path) => {
+            let contents = std::fs::read_to_string(file_path)
+                .map_err(|error| format!("Could not read {} - {error}", file_path.display()))?;
+            let export_type = ExportType::from_extension(file_path).unwrap_or(ExportType::JSON);
+            let parsed = exports::parse(&export_type, &contents)?;
+            for warning in parsed.warnings {
+                eprintln!("Ignored {warning}");
+            }
+            for error in parsed.errors {
+                eprintln!("Skipped {error}");
+            }
+            parsed.requests
+        }
+    };
+
+    // The environment's variables are filled in and its cookie jar is used, if it keeps cookies
+    if let Some(name) = environment {
+        let app = app.as_mut().map_err(|error| error.clone())?;
+        let index = app
+            .environments
+            .iter()
+            .position(|environment| environment.name == name)
+            .ok_or(format!("No environment is called {name}."))?;
+        app.active_environment = Some(index);
+
+        requests = requests
+            .iter()
+            .map(|request| app.environments[index].apply(request))
+            .collect();
+    }
+
+    // An exported file may be used without any saved state, so certificates are optional
+    Ok((requests, app.unwrap_or_default()))
+}
+
+/// Send a single request and print the response, returning whether it succeeded
+fn run(
+    app: &mut Reqwestur,
+    tokens: &TokenCache,
+    position: usize,
+    request: &Request,
+    quiet: bool,
+) -> bool {
+    let name = format!(
+        "[{position}] {} {}",
+        request.method.to_string(),
+        request.address.uri
+    );
+
+    // Each request is sent with the certificate it selects, or the one for its host
+    let executor = match app.executor(request) {
+        Ok(executor) => executor,
+        Err(notification) => {
+            let message = notification
+                .message()
+                .unwrap_or("Could not load the certificate.");
+            eprintln!("{name} -> {message}");
+            return false;
+        }
+    };
+
+    // OAuth 2.0 tokens are shared by the requests, the authorization code grant needs a browser
+    let browser = |url: &str| eprintln!("Open {url} in a browser to authorise {name}.");
+    let sent = engine::runtime().block_on(async {
+        let mut request = request.clone();
+        executor.authorize(&mut request, tokens, &browser).await?;
+        executor.execute(&request).await
+    });
+
+    match sent {
+        Ok(response) => {
+            println!("{name} -> {}", response.status.to_string());
+
+            if !quiet {
+                for (header, value) in &response.headers {
+                    println!("{header}: {value}");
+                }
+                println!();
+                println!("{}", response.body);
+                println!();
+            }
+
+            response.status.0 < 400
+        }
+        Err(error) => {
+            eprintln!("{name} -> {error}");
+            false
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let arguments = match Arguments::parse(std::env::args().skip(1)) {
+        Ok(Some(arguments)) => arguments,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let (requests, mut app) = match load(&arguments.source, arguments.environment.as_deref()) {
+        Ok(loaded) => loaded,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let selected: Vec<(usize, &Request)> = requests
+        .iter()
+        .enumerate()
+        .map(|(index, request)| (index + 1, request))
+        .filter(|(position, _)| arguments.index.is_none_or(|index| index == *position))
+        .collect();
+
+    if selected.is_empty() {
+        eprintln!("No requests were found.");
+        return ExitCode::from(2);
+    }
+
+    if arguments.list {
+        for (position, request) in selected {
+            println!(
+                "[{position}] {} {}",
+                request.method.to_string(),
+                request.address.uri
+            );
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let tokens = TokenCache::default();
+    let failures = selected
+        .into_iter()
+        .filter(|(position, request)| !run(&mut app, &tokens, *position, request, arguments.quiet))
+        .count();
+
+    if failures > 0 {
+        eprintln!("{failures} request(s) failed.");
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
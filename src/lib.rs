@@ -0,0 +1,12 @@
+//! The Reqwestur library
+//!
+//! Contains the request engine and data types shared between the desktop application,
+//! any command-line tooling and the test suite.
+
+// Enum variants are written in uppercase to mirror the HTTP terms they represent
+#![allow(clippy::upper_case_acronyms)]
+// See `utils::request::Method`, using std::fmt::Display causes a stack overflow
+#![allow(clippy::to_string_trait_impl)]
+
+pub mod ui;
+pub mod utils;
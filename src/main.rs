@@ -3,10 +3,7 @@
 use eframe::egui;
 use std::sync::Arc;
 
-mod ui;
-mod utils;
-
-use crate::utils::reqwestur::Reqwestur;
+use reqwestur::utils::reqwestur::{APP_ID, Reqwestur};
 
 /// The main application runner
 ///
@@ -31,7 +28,7 @@ fn main() -> Result<(), eframe::Error> {
     };
 
     eframe::run_native(
-        "REQWESTUR",
+        APP_ID,
         options,
         Box::new(|cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
@@ -1,2 +1,2 @@
-pub(crate) mod widgets;
+pub mod widgets;
 pub(crate) mod window;
@@ -0,0 +1,288 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::{buttons::default_button, environments},
+    utils::{
+        self,
+        auth::{ApiKeyLocation, Auth, AuthKind},
+        oauth2::GrantType,
+        request::Request,
+        reqwestur::Reqwestur,
+    },
+};
+
+/// The authorisation editor window
+pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("auth_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Authorisation Editor")
+            .with_inner_size([500.0, 550.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                kind_picker(ui, &mut request.auth.kind);
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                let mut layouter = environments::layouter(app.variables());
+                let auth = &mut request.auth;
+
+                match auth.kind {
+                    AuthKind::NONE => {
+                        ui.label("The request is sent without credentials.");
+                    }
+                    AuthKind::BASIC | AuthKind::DIGEST => {
+                        credential(ui, &mut auth.username, "Username", &mut layouter);
+                        credential(ui, &mut auth.password, "Password", &mut layouter);
+
+                        if auth.kind == AuthKind::DIGEST {
+                            ui.label(
+                                "The credentials are sent in answer to the server's Digest challenge.",
+                            );
+                        }
+                    }
+                    AuthKind::BEARER => {
+                        credential(ui, &mut auth.token, "Token", &mut layouter);
+                    }
+                    AuthKind::APIKEY => api_key(ui, auth, &mut layouter),
+                    AuthKind::OAUTH2 => {
+                        oauth2(ui, auth, &mut layouter);
+                        token(app, request, ui);
+                    }
+                    AuthKind::AWSSIGV4 => {
+                        let sigv4 = &mut auth.sigv4;
+                        credential(ui, &mut sigv4.access_key, "Access Key ID", &mut layouter);
+                        credential(ui, &mut sigv4.secret_key, "Secret Access Key", &mut layouter);
+                        credential(
+                            ui,
+                            &mut sigv4.session_token,
+                            "Session Token (optional)",
+                            &mut layouter,
+                        );
+                        credential(ui, &mut sigv4.region, "Region, e.g. eu-west-2", &mut layouter);
+                        credential(
+                            ui,
+                            &mut sigv4.service,
+                            "Service, e.g. execute-api",
+                            &mut layouter,
+                        );
+                        ui.label("The request is signed, including its body, as it is sent.");
+                    }
+                }
+
+                ui.add_space(2.);
+                ui.label("Use {{variable}} to fill in a value from the active environment.");
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Done!",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.auth_editor_open = false;
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.auth_editor_open = false;
+            }
+        },
+    );
+}
+
+/// A dropdown to select how the request authenticates
+pub fn kind_picker(ui: &mut egui::Ui, kind: &mut AuthKind) -> egui::Response {
+    egui::ComboBox::from_id_salt("auth_kind")
+        .selected_text(kind.to_string())
+        .show_ui(ui, |ui| {
+            for option in AuthKind::values() {
+                let label = option.to_string();
+                ui.selectable_value(kind, option, label);
+            }
+        })
+        .response
+}
+
+/// The panel for an API key sent in a header or query parameter
+fn api_key(
+    ui: &mut egui::Ui,
+    auth: &mut Auth,
+    layouter: &mut impl FnMut(&egui::Ui, &dyn egui::TextBuffer, f32) -> std::sync::Arc<egui::Galley>,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Send As:");
+        egui::ComboBox::from_id_salt("api_key_location")
+            .selected_text(auth.key_location.to_string())
+            .show_ui(ui, |ui| {
+                for location in ApiKeyLocation::values() {
+                    let label = location.to_string();
+                    ui.selectable_value(&mut auth.key_location, location, label);
+                }
+            });
+    });
+
+    let name = match auth.key_location {
+        ApiKeyLocation::HEADER => "Header Name, e.g. X-API-Key",
+        ApiKeyLocation::QUERY => "Parameter Name, e.g. api_key",
+    };
+    credential(ui, &mut auth.key_name, name, layouter);
+    credential(ui, &mut auth.key_value, "Key", layouter);
+}
+
+/// The panel configuring how OAuth 2.0 access tokens are fetched
+fn oauth2(
+    ui: &mut egui::Ui,
+    auth: &mut Auth,
+    layouter: &mut impl FnMut(&egui::Ui, &dyn egui::TextBuffer, f32) -> std::sync::Arc<egui::Galley>,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Grant Type:");
+        egui::ComboBox::from_id_salt("oauth2_grant")
+            .selected_text(auth.oauth2.grant.to_string())
+            .show_ui(ui, |ui| {
+                for grant in GrantType::values() {
+                    let label = grant.to_string();
+                    ui.selectable_value(&mut auth.oauth2.grant, grant, label);
+                }
+            });
+    });
+
+    let oauth2 = &mut auth.oauth2;
+    credential(ui, &mut oauth2.token_url, "Token URL", layouter);
+
+    if oauth2.grant == GrantType::AUTHORIZATIONCODE {
+        credential(
+            ui,
+            &mut oauth2.authorization_url,
+            "Authorization URL",
+            layouter,
+        );
+
+        ui.horizontal(|ui| {
+            ui.label("Redirect Port:");
+            ui.add(egui::DragValue::new(&mut oauth2.redirect_port).range(0..=65535));
+            ui.label(format!(
+                "http://127.0.0.1:{}/callback",
+                if oauth2.redirect_port == 0 {
+                    "<any>".to_string()
+                } else {
+                    oauth2.redirect_port.to_string()
+                }
+            ));
+        });
+    }
+
+    credential(ui, &mut oauth2.client_id, "Client ID", layouter);
+    credential(ui, &mut oauth2.client_secret, "Client Secret", layouter);
+    credential(ui, &mut oauth2.scope, "Scope, e.g. read write", layouter);
+    ui.checkbox(
+        &mut oauth2.credentials_in_body,
+        "Send the client credentials in the body?",
+    );
+
+    match oauth2.grant {
+        GrantType::PASSWORD => {
+            credential(ui, &mut auth.username, "Username", layouter);
+            credential(ui, &mut auth.password, "Password", layouter);
+        }
+        GrantType::REFRESHTOKEN => {
+            credential(
+                ui,
+                &mut auth.oauth2.refresh_token,
+                "Refresh Token",
+                layouter,
+            );
+        }
+        GrantType::CLIENTCREDENTIALS | GrantType::AUTHORIZATIONCODE => {}
+    }
+}
+
+/// The cached access token, and the endpoint's response for debugging
+fn token(app: &mut Reqwestur, request: &Request, ui: &mut egui::Ui) {
+    ui.add_space(4.);
+    ui.label(egui::RichText::new("Access Token").size(14.));
+
+    let auth = utils::environments::resolve(request, &app.variables()).auth;
+    let tokens = app.token_cache();
+
+    ui.horizontal(|ui| {
+        let width = ui.available_width() / 2. - 4.;
+
+        if ui
+            .add(default_button(
+                None,
+                "Get New Token",
+                width,
+                ui.visuals().text_color(),
+            ))
+            .clicked()
+            && let Err(notification) = app.fetch_token(request)
+        {
+            *app.token_notification.lock().unwrap() = notification;
+        }
+
+        if ui
+            .add(default_button(
+                None,
+                "Clear Token",
+                width,
+                ui.visuals().text_color(),
+            ))
+            .clicked()
+        {
+            tokens.remove(&auth);
+        }
+    });
+
+    app.token_notification.lock().unwrap().display(ui);
+
+    match tokens.get(&auth) {
+        Some(token) => {
+            ui.label(format!(
+                "Expires: {}{}",
+                token.expiry(),
+                if token.is_expired() {
+                    ", it will be renewed when the request is sent"
+                } else {
+                    ""
+                }
+            ));
+
+            egui::CollapsingHeader::new("Token Response").show(ui, |ui| {
+                let mut response = token.response.as_str();
+                ui.add(
+                    egui::TextEdit::multiline(&mut response)
+                        .code_editor()
+                        .desired_rows(6)
+                        .desired_width(ui.available_width()),
+                );
+            });
+        }
+        None => {
+            ui.label("A token is fetched when the request is sent.");
+        }
+    }
+}
+
+/// A single line editor for a credential, colouring its placeholders
+pub(crate) fn credential(
+    ui: &mut egui::Ui,
+    value: &mut String,
+    hint: &str,
+    layouter: &mut impl FnMut(&egui::Ui, &dyn egui::TextBuffer, f32) -> std::sync::Arc<egui::Galley>,
+) {
+    ui.add(
+        egui::TextEdit::singleline(value)
+            .hint_text(hint)
+            .layouter(layouter)
+            .margin(5.)
+            .desired_width(ui.available_width()),
+    );
+}
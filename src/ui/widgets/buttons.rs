@@ -8,7 +8,8 @@ pub fn default_button<'image>(
 ) -> egui::Button<'image> {
     let image = image.into();
     let txt = egui::RichText::new(text).size(14.).color(colour);
-    let btn = if let Some(image) = image {
+
+    if let Some(image) = image {
         egui::Button::image_and_text(
             egui::Image::new(image).fit_to_exact_size(egui::vec2(16., 16.)),
             txt,
@@ -18,9 +19,7 @@ pub fn default_button<'image>(
         egui::Button::new(txt)
     }
     .min_size(egui::vec2(width, 32.))
-    .corner_radius(5.);
-
-    btn
+    .corner_radius(5.)
 }
 
 pub fn side_menu_button<'a>(
@@ -77,7 +76,7 @@ pub fn toggle_switch(on: &mut bool, text: &str) -> impl egui::Widget {
         }
 
         response.widget_info(|| {
-            egui::WidgetInfo::selected(egui::WidgetType::Checkbox, *on, false, &text)
+            egui::WidgetInfo::selected(egui::WidgetType::Checkbox, *on, false, text)
         });
 
         if ui.is_rect_visible(rect) {
@@ -120,11 +119,7 @@ pub fn minimiser(direction: MinimiserDirection, current_value: bool) -> impl egu
             MinimiserDirection::_RightToLeft => (chevron_left, chevron_right),
         };
 
-        let current_icon = if current_value.clone() {
-            minimise
-        } else {
-            expand
-        };
+        let current_icon = if current_value { minimise } else { expand };
 
         ui.ctx()
             .style_mut(|style| style.spacing.button_padding = egui::vec2(5., 5.));
@@ -6,144 +6,157 @@ use crate::{
         notification::{Notification, NotificationKind},
     },
     utils::{
-        certificates::{Certificate, CertificateStatus},
+        certificates::{Certificate, CertificateDetails, CertificateFormat, CertificateStatus},
         reqwestur::Reqwestur,
+        secrets::Vault,
     },
 };
 
-/// The certificate editor window
+/// The certificate library window, requests select a certificate by name or by the hosts it's sent to
 pub fn editor(app: &mut Reqwestur, ui: &mut egui::Ui) {
     ui.ctx().show_viewport_immediate(
         egui::ViewportId::from_hash_of("certificate_editor"),
         egui::ViewportBuilder::default()
-            .with_title("Certificate Editor")
-            .with_inner_size([500.0, 500.0])
+            .with_title("Certificate Library")
+            .with_inner_size([500.0, 700.0])
             .with_min_inner_size([500.0, 500.0]),
         |context, _class| {
             egui::CentralPanel::default().show(ui.ctx(), |ui| {
-                ui.vertical(|ui| {
-                    ui.group(|ui| {
-                        let file_name = match &app.certificate {
-                            Some(certificate) => {
-                                if let Some(file_name) = certificate.file_path.file_name() {
-                                    file_name.to_str().unwrap_or("Error reading file name.")
-                                } else {
-                                    "No file selected."
-                                }
-                            }
-                            None => "No file selected.",
-                        };
-                        ui.label(format!("Selected File: {}", file_name));
+                ui.horizontal(|ui| {
+                    picker(app, ui);
+
+                    let add_icon = egui::include_image!("../../assets/plus.svg");
+                    if ui
+                        .add(default_button(
+                            Some(add_icon),
+                            "New Certificate",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        let name = format!("Certificate {}", app.certificates.len() + 1);
+                        app.certificates.push(Certificate::new(name));
+                        app.selected_certificate = Some(app.certificates.len() - 1);
+                    }
+                });
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                let mut removed = false;
+                match app
+                    .selected_certificate
+                    .and_then(|index| app.certificates.get_mut(index))
+                {
+                    Some(certificate) => {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut certificate.name)
+                                .desired_width(ui.available_width())
+                                .hint_text("Certificate Name")
+                                .margin(5.),
+                        );
 
-                        let upload_icon = egui::include_image!("../../assets/upload.svg");
-                        if ui
+                        hosts(ui, &mut certificate.hosts);
+
+                        if files(ui, certificate, &mut app.vault) {
+                            app.vault_modal_open = true;
+                        }
+
+                        let bin_icon = egui::include_image!("../../assets/trash.svg");
+                        removed = ui
                             .add(default_button(
-                                Some(upload_icon),
-                                "Select a PFX.",
+                                Some(bin_icon),
+                                "Delete Certificate",
                                 ui.available_width(),
                                 ui.visuals().text_color(),
                             ))
-                            .clicked()
-                        {
-                            let mut certificate = Certificate::default();
-                            if let Some(file) = rfd::FileDialog::new()
-                                .add_filter("PFX", &["pfx"])
-                                .set_directory("/")
-                                .pick_file()
-                            {
-                                certificate.file_path = file;
-                            }
+                            .clicked();
 
-                            app.certificate = Some(certificate);
-                        }
-
-                        if let Some(certificate) = &mut app.certificate {
-                            if certificate.file_path.exists() {
-                                ui.group(|ui| {
-                                    ui.label("Certificate Passphrase:");
-                                    ui.add_sized(
-                                        egui::vec2(ui.available_width(), 20.),
-                                        egui::TextEdit::singleline(&mut certificate.passphrase)
-                                            .margin(5.)
-                                            .password(true),
-                                    );
+                        if !certificate.details.is_empty() {
+                            egui::ScrollArea::vertical()
+                                .max_height(200.)
+                                .auto_shrink([false, true])
+                                .show(ui, |ui| {
+                                    for (index, details) in certificate.details.iter().enumerate() {
+                                        ui.group(|ui| {
+                                            ui.set_width(ui.available_width());
+                                            show_details(ui, index, details);
+                                        });
+                                    }
                                 });
-                            }
+                        }
 
-                            let bin_icon = egui::include_image!("../../assets/trash.svg");
+                        ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+                            let save_icon = egui::include_image!("../../assets/floppy.svg");
                             if ui
-                                .add(default_button(
-                                    Some(bin_icon),
-                                    "Remove PFX.",
-                                    ui.available_width(),
-                                    ui.visuals().text_color(),
-                                ))
+                                .add_enabled(
+                                    certificate.status == CertificateStatus::OK,
+                                    default_button(
+                                        Some(save_icon.clone()),
+                                        "Confirm & Close!",
+                                        ui.available_width(),
+                                        ui.visuals().text_color(),
+                                    ),
+                                )
                                 .clicked()
                             {
-                                app.certificate = None;
+                                app.certificate_editor_open = false;
+                                certificate.notification.clear();
                             }
-                        }
-                    });
-                });
 
-                if let Some(certificate) = &mut app.certificate {
-                    ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
-                        let save_icon = egui::include_image!("../../assets/floppy.svg");
-                        if ui
-                            .add_enabled(
-                                certificate.status == CertificateStatus::OK,
-                                default_button(
-                                    Some(save_icon.clone()),
-                                    "Confirm & Close!",
-                                    ui.available_width(),
-                                    ui.visuals().text_color(),
-                                ),
-                            )
-                            .clicked()
-                        {
-                            app.certificate_editor_open = false;
-                            certificate.notification.clear();
-                        }
-
-                        if ui
-                            .add_enabled(
-                                !certificate.passphrase.is_empty()
-                                    && certificate.file_path.file_name().is_some(),
-                                default_button(
-                                    Some(save_icon),
-                                    "Validate Certificates",
-                                    ui.available_width(),
-                                    ui.visuals().text_color(),
-                                ),
-                            )
-                            .clicked()
-                        {
-                            match certificate.import() {
-                                Ok(identity) => {
-                                    certificate.status = CertificateStatus::OK;
-                                    certificate.notification = Notification::new(
-                                        "Certificate loaded successfully!",
-                                        NotificationKind::INFO,
-                                    );
-                                    certificate.identity = Some(identity);
-                                }
-                                Err(error) => {
-                                    certificate.status = CertificateStatus::ERROR;
-                                    certificate.notification =
-                                        Notification::new(error, NotificationKind::ERROR);
+                            if ui
+                                .add_enabled(
+                                    certificate.is_ready(),
+                                    default_button(
+                                        Some(save_icon),
+                                        "Validate Certificates",
+                                        ui.available_width(),
+                                        ui.visuals().text_color(),
+                                    ),
+                                )
+                                .clicked()
+                            {
+                                match certificate.import() {
+                                    Ok(identity) => {
+                                        certificate.status = CertificateStatus::OK;
+                                        certificate.notification = Notification::new(
+                                            "Certificate loaded successfully!",
+                                            NotificationKind::INFO,
+                                        );
+                                        certificate.identity = Some(identity);
+                                        certificate.details =
+                                            certificate.inspect().unwrap_or_default();
+                                    }
+                                    Err(error) => {
+                                        certificate.status = CertificateStatus::ERROR;
+                                        certificate.details.clear();
+                                        certificate.notification =
+                                            Notification::new(error, NotificationKind::ERROR);
+                                    }
                                 }
                             }
-                        }
 
-                        certificate.notification.display(ui);
+                            certificate.notification.display(ui);
 
-                        if certificate.status == CertificateStatus::UNCONFIRMED {
-                            ui.label("No certificates have been loaded.");
-                        }
-                    });
+                            if certificate.status == CertificateStatus::UNCONFIRMED {
+                                ui.label("No certificates have been loaded.");
+                            }
+                        });
+                    }
+                    None => {
+                        ui.label(
+                            "Create or select a certificate, requests send it when they select \
+                             it by name or are sent to one of its hosts.",
+                        );
+                    }
                 }
 
-                ui.add_space(5.);
+                if removed && let Some(index) = app.selected_certificate.take() {
+                    let certificate = app.certificates.remove(index);
+                    let _ = app.vault.remove(&certificate.passphrase);
+                }
             });
 
             if context.input(|i| i.viewport().close_requested()) {
@@ -152,3 +165,292 @@ pub fn editor(app: &mut Reqwestur, ui: &mut egui::Ui) {
         },
     );
 }
+
+/// A dropdown to select the certificate being edited
+fn picker(app: &mut Reqwestur, ui: &mut egui::Ui) -> egui::Response {
+    let selected = app
+        .selected_certificate
+        .and_then(|index| app.certificates.get(index))
+        .map(|certificate| certificate.name.clone())
+        .unwrap_or("No Certificate".to_string());
+    let names: Vec<String> = app
+        .certificates
+        .iter()
+        .map(|certificate| certificate.name.clone())
+        .collect();
+
+    egui::ComboBox::from_id_salt("selected_certificate")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            for (index, name) in names.into_iter().enumerate() {
+                ui.selectable_value(&mut app.selected_certificate, Some(index), name);
+            }
+        })
+        .response
+}
+
+/// The host patterns the certificate is sent to when a request doesn't select a certificate
+fn hosts(ui: &mut egui::Ui, hosts: &mut Vec<String>) {
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Sent to Hosts:");
+
+            let add_icon = egui::include_image!("../../assets/plus.svg");
+            if ui
+                .add(default_button(
+                    Some(add_icon),
+                    "New Host",
+                    ui.available_width(),
+                    ui.visuals().text_color(),
+                ))
+                .clicked()
+            {
+                hosts.push(String::new());
+            }
+        });
+
+        let mut deleted = None;
+        for (row, host) in hosts.iter_mut().enumerate() {
+            ui.push_id(row, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(host)
+                            .hint_text("api.example.com or *.example.com")
+                            .margin(5.)
+                            .vertical_align(egui::Align::Center)
+                            .desired_width(ui.available_width() - 40.),
+                    );
+
+                    let bin_icon = egui::include_image!("../../assets/trash.svg");
+                    if ui
+                        .add(
+                            egui::Button::image(
+                                egui::Image::new(bin_icon).fit_to_exact_size(egui::vec2(16., 16.)),
+                            )
+                            .image_tint_follows_text_color(true),
+                        )
+                        .on_hover_text("Remove Host")
+                        .clicked()
+                    {
+                        deleted = Some(row);
+                    }
+                });
+            });
+        }
+
+        if let Some(row) = deleted {
+            hosts.remove(row);
+        }
+    });
+}
+
+/// The certificate's format, files and passphrase, returning whether the vault should be unlocked
+fn files(ui: &mut egui::Ui, certificate: &mut Certificate, vault: &mut Vault) -> bool {
+    let mut unlock = false;
+
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Format:");
+
+            let mut selected = certificate.format.clone();
+            egui::ComboBox::from_id_salt("certificate_format")
+                .selected_text(selected.to_string())
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    for option in CertificateFormat::values() {
+                        let label = option.to_string();
+                        ui.selectable_value(&mut selected, option, label);
+                    }
+                });
+
+            // Changing the format starts a new selection
+            if selected != certificate.format {
+                let _ = vault.remove(&certificate.passphrase);
+                certificate.reset(selected, std::path::PathBuf::new());
+            }
+        });
+
+        ui.label(format!(
+            "Selected File: {}",
+            selected_file(Some(&certificate.file_path)).unwrap_or("No file selected.")
+        ));
+
+        let select_label = match certificate.format {
+            CertificateFormat::PKCS12 => "Select a PFX.",
+            CertificateFormat::PEM => "Select a Certificate.",
+        };
+
+        let upload_icon = egui::include_image!("../../assets/upload.svg");
+        if ui
+            .add(default_button(
+                Some(upload_icon),
+                select_label,
+                ui.available_width(),
+                ui.visuals().text_color(),
+            ))
+            .clicked()
+            && let Some(file) = pick_file(&certificate.format)
+        {
+            let _ = vault.remove(&certificate.passphrase);
+            certificate.reset(certificate.format.clone(), file);
+        }
+
+        if certificate.format == CertificateFormat::PEM && certificate.file_path.exists() {
+            ui.group(|ui| {
+                ui.label(format!(
+                    "Private Key: {}",
+                    selected_file(certificate.key_path.as_ref())
+                        .unwrap_or("In the certificate file.")
+                ));
+                pem_file_picker(ui, &mut certificate.key_path, "Select a Private Key.");
+
+                ui.label(format!(
+                    "Chain: {}",
+                    selected_file(certificate.chain_path.as_ref())
+                        .unwrap_or("In the certificate file.")
+                ));
+                pem_file_picker(ui, &mut certificate.chain_path, "Select a Chain.");
+            });
+        }
+
+        if certificate.file_path.exists() {
+            ui.group(|ui| {
+                ui.label(match certificate.format {
+                    CertificateFormat::PKCS12 => "Certificate Passphrase:",
+                    CertificateFormat::PEM => "Private Key Passphrase (if it's encrypted):",
+                });
+
+                // The passphrase is only stored in the vault
+                let unlocked = vault.is_unlocked();
+                if ui
+                    .add_enabled_ui(unlocked, |ui| {
+                        ui.add_sized(
+                            egui::vec2(ui.available_width(), 20.),
+                            egui::TextEdit::singleline(&mut certificate.passphrase.value)
+                                .margin(5.)
+                                .password(true),
+                        )
+                    })
+                    .inner
+                    .changed()
+                    && let Err(error) = vault.set(&certificate.passphrase)
+                {
+                    certificate.notification = Notification::new(error, NotificationKind::ERROR);
+                }
+
+                unlock = !unlocked
+                    && ui
+                        .add(default_button(
+                            None,
+                            "Unlock the vault to enter the passphrase.",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked();
+            });
+        }
+    });
+
+    unlock
+}
+
+/// The name of the selected file, if there is one
+fn selected_file(file_path: Option<&std::path::PathBuf>) -> Option<&str> {
+    file_path?.file_name()?.to_str()
+}
+
+/// Ask for a certificate file of the given format
+fn pick_file(format: &CertificateFormat) -> Option<std::path::PathBuf> {
+    let dialog = rfd::FileDialog::new().set_directory("/");
+    match format {
+        CertificateFormat::PKCS12 => dialog.add_filter("PFX", &["pfx", "p12"]),
+        CertificateFormat::PEM => dialog.add_filter("PEM", &["pem", "crt", "cer", "key"]),
+    }
+    .pick_file()
+}
+
+/// The buttons to select or clear an optional PEM file
+fn pem_file_picker(ui: &mut egui::Ui, file_path: &mut Option<std::path::PathBuf>, text: &str) {
+    ui.horizontal(|ui| {
+        let upload_icon = egui::include_image!("../../assets/upload.svg");
+        if ui
+            .add(default_button(
+                Some(upload_icon),
+                text,
+                ui.available_width() - 40.,
+                ui.visuals().text_color(),
+            ))
+            .clicked()
+            && let Some(file) = pick_file(&CertificateFormat::PEM)
+        {
+            *file_path = Some(file);
+        }
+
+        let bin_icon = egui::include_image!("../../assets/trash.svg");
+        if ui
+            .add_enabled(
+                file_path.is_some(),
+                egui::Button::image(
+                    egui::Image::new(bin_icon).fit_to_exact_size(egui::vec2(16., 16.)),
+                )
+                .image_tint_follows_text_color(true),
+            )
+            .on_hover_text("Use the certificate file")
+            .clicked()
+        {
+            *file_path = None;
+        }
+    });
+}
+
+/// The subject, issuer, names, validity and fingerprint of a certificate
+pub(crate) fn show_details(ui: &mut egui::Ui, index: usize, details: &CertificateDetails) {
+    let title = if index == 0 {
+        "Certificate"
+    } else {
+        "Chain Certificate"
+    };
+    ui.label(egui::RichText::new(title).size(14.));
+
+    egui::Grid::new(("certificate_details", index))
+        .num_columns(2)
+        .spacing([10., 4.])
+        .show(ui, |ui| {
+            ui.label("Subject:");
+            ui.label(&details.subject);
+            ui.end_row();
+
+            ui.label("Issuer:");
+            ui.label(&details.issuer);
+            ui.end_row();
+
+            if !details.subject_alt_names.is_empty() {
+                ui.label("Alternative Names:");
+                ui.label(details.subject_alt_names.join(", "));
+                ui.end_row();
+            }
+
+            ui.label("Valid From:");
+            ui.label(details.valid_from());
+            ui.end_row();
+
+            ui.label("Expires:");
+            if details.is_expired() {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("{} (Expired)", details.expiry()),
+                );
+            } else {
+                ui.label(details.expiry());
+            }
+            ui.end_row();
+
+            if !details.fingerprint.is_empty() {
+                ui.label("SHA-256 Fingerprint:");
+                ui.add(
+                    egui::Label::new(egui::RichText::new(&details.fingerprint).monospace()).wrap(),
+                );
+                ui.end_row();
+            }
+        });
+}
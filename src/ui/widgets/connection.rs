@@ -0,0 +1,142 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::{buttons::default_button, certificates},
+    utils::connection::{Connection, Handshake},
+};
+
+/// The connection a response came over and why it failed, `inspect` is set when the chain should be read
+pub fn viewer<'a>(connection: &'a Connection, inspect: &'a mut bool) -> impl egui::Widget + 'a {
+    move |ui: &mut egui::Ui| {
+        egui::ScrollArea::vertical()
+            .id_salt("response_connection")
+            .animated(true)
+            .auto_shrink(false)
+            .show(ui, |ui| {
+                if let Some(failure) = &connection.failure {
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            egui::RichText::new(failure.to_string()).size(14.),
+                        );
+                        ui.label(failure.explanation());
+                    });
+                    ui.add_space(4.);
+                }
+
+                egui::Grid::new("response_connection_table")
+                    .num_columns(2)
+                    .spacing([10., 4.])
+                    .show(ui, |ui| {
+                        if let Some(remote_address) = &connection.remote_address {
+                            ui.label("Remote Address:");
+                            ui.label(remote_address);
+                            ui.end_row();
+                        }
+
+                        if let Some(http_version) = &connection.http_version {
+                            ui.label("HTTP Version:");
+                            ui.label(http_version);
+                            ui.end_row();
+
+                            // HTTP/2 is only used over TLS when it's chosen with ALPN
+                            if connection.certificate.is_some() {
+                                ui.label("ALPN:");
+                                ui.label(if http_version == "HTTP/2.0" {
+                                    "h2"
+                                } else {
+                                    "http/1.1 or not negotiated"
+                                });
+                                ui.end_row();
+                            }
+                        }
+                    });
+
+                if let Some(details) = &connection.certificate {
+                    ui.add_space(4.);
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        certificates::show_details(ui, 0, details);
+                    });
+                }
+
+                if !connection.is_tls() {
+                    if connection.failure.is_none() {
+                        ui.add_space(4.);
+                        ui.label("The connection isn't encrypted with TLS.");
+                    }
+                    return ui.response();
+                }
+
+                ui.add_space(8.);
+                ui.label(egui::RichText::new("TLS Handshake").size(14.));
+                ui.label(
+                    "Read by a separate connection which accepts any certificate, without the \
+                     proxy or client certificate, so it may differ from the request's. Servers \
+                     only offering TLS 1.0 or 1.1 can't be read.",
+                );
+                ui.add_space(4.);
+
+                match &connection.handshake {
+                    _ if connection.inspecting => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Reading the certificate chain...");
+                        });
+                    }
+                    Some(Ok(handshake)) => self::handshake(ui, handshake),
+                    Some(Err(error)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    None => {}
+                }
+
+                if !connection.inspecting
+                    && ui
+                        .add(default_button(
+                            None,
+                            "Read the Certificate Chain",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                {
+                    *inspect = true;
+                }
+
+                ui.response()
+            })
+            .inner
+    }
+}
+
+/// The negotiated TLS version, application protocol and cipher suite, followed by the chain
+fn handshake(ui: &mut egui::Ui, handshake: &Handshake) {
+    egui::Grid::new("response_handshake_table")
+        .num_columns(2)
+        .spacing([10., 4.])
+        .show(ui, |ui| {
+            ui.label("TLS Version:");
+            ui.label(&handshake.version);
+            ui.end_row();
+
+            ui.label("ALPN:");
+            ui.label(handshake.alpn.as_deref().unwrap_or("None"));
+            ui.end_row();
+
+            ui.label("Cipher Suite:");
+            ui.label(&handshake.cipher_suite);
+            ui.end_row();
+        });
+
+    for (index, details) in handshake.chain.iter().enumerate() {
+        ui.add_space(4.);
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.push_id(("handshake_chain", index), |ui| {
+                certificates::show_details(ui, index, details);
+            });
+        });
+    }
+}
@@ -0,0 +1,307 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::{
+        buttons::default_button,
+        environments,
+        notification::{Notification, NotificationKind},
+    },
+    utils::{
+        cookies::{self, Cookie, CookieJar},
+        reqwestur::Reqwestur,
+    },
+};
+
+/// The cookie manager window, editing the cookies kept by the selected environment
+pub fn editor(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("cookie_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Cookie Manager")
+            .with_inner_size([600.0, 500.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                ui.horizontal(|ui| {
+                    let selected = app.active_environment;
+                    environments::picker(app, ui);
+                    if app.active_environment != selected {
+                        app.cookies = app
+                            .cookie_jar()
+                            .map(|jar| jar.records())
+                            .unwrap_or_default();
+                    }
+
+                    domain_picker(app, ui);
+                });
+
+                let Some(jar) = app.cookie_jar() else {
+                    ui.add_space(4.);
+                    ui.label("Select an environment which keeps cookies to manage them.");
+                    done_button(app, ui);
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    let width = ui.available_width() / 3. - 6.;
+
+                    let add_icon = egui::include_image!("../../assets/plus.svg");
+                    if ui
+                        .add_enabled(
+                            !app.cookie_domain.is_empty(),
+                            default_button(
+                                Some(add_icon),
+                                "New Cookie",
+                                width,
+                                ui.visuals().text_color(),
+                            ),
+                        )
+                        .on_disabled_hover_text("Enter the domain the cookie is sent to.")
+                        .clicked()
+                    {
+                        app.cookies.push(Cookie {
+                            domain: app.cookie_domain.clone(),
+                            path: "/".to_string(),
+                            ..Default::default()
+                        });
+                    }
+
+                    let open_icon = egui::include_image!("../../assets/folder_open.svg");
+                    if ui
+                        .add(default_button(
+                            Some(open_icon),
+                            "Import cookies.txt",
+                            width,
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.notification = import(&jar);
+                        app.cookies = jar.records();
+                    }
+
+                    let save_icon = egui::include_image!("../../assets/upload.svg");
+                    if ui
+                        .add(default_button(
+                            Some(save_icon),
+                            "Export cookies.txt",
+                            width,
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.notification = export(&jar);
+                    }
+                });
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                let mut deleted = None;
+                egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .max_height(ui.available_height() - 34.)
+                    .max_width(ui.available_width())
+                    .show(ui, |ui| {
+                        for (row, cookie) in app.cookies.iter_mut().enumerate() {
+                            // Only the selected domain's cookies are listed, all of them if none is selected
+                            if !app.cookie_domain.is_empty() && cookie.domain != app.cookie_domain {
+                                continue;
+                            }
+
+                            let previous = cookie.clone();
+                            let (changed, delete) =
+                                ui.push_id(row, |ui| cookie_row(ui, cookie)).inner;
+
+                            // Edits are written to the jar straight away, replacing the previous cookie
+                            if changed {
+                                jar.remove(&previous);
+                                if let Err(error) = jar.insert(cookie) {
+                                    app.notification =
+                                        Notification::new(error, NotificationKind::WARN);
+                                }
+                            }
+
+                            if delete {
+                                deleted = Some(row);
+                            }
+                        }
+                    });
+
+                if let Some(row) = deleted {
+                    jar.remove(&app.cookies.remove(row));
+                }
+
+                done_button(app, ui);
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.cookie_editor_open = false;
+            }
+        },
+    );
+}
+
+/// A dropdown to select the domain whose cookies are listed, the domain can also be typed in
+fn domain_picker(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    let mut domains: Vec<String> = app
+        .cookies
+        .iter()
+        .map(|cookie| cookie.domain.clone())
+        .collect();
+    domains.sort();
+    domains.dedup();
+
+    ui.add(
+        egui::TextEdit::singleline(&mut app.cookie_domain)
+            .hint_text("All Domains")
+            .margin(5.)
+            .desired_width(ui.available_width() - 40.),
+    );
+
+    egui::ComboBox::from_id_salt("cookie_domain")
+        .selected_text("")
+        .width(20.)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut app.cookie_domain, String::new(), "All Domains");
+            for domain in domains {
+                ui.selectable_value(&mut app.cookie_domain, domain.clone(), domain);
+            }
+        });
+}
+
+/// A row to edit a cookie, returning whether it changed and whether it should be removed
+fn cookie_row(ui: &mut egui::Ui, cookie: &mut Cookie) -> (bool, bool) {
+    ui.group(|ui| {
+        let mut changed = false;
+
+        let removed = ui
+            .horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut cookie.name)
+                            .hint_text("Cookie Name")
+                            .margin(5.)
+                            .vertical_align(egui::Align::Center)
+                            .desired_width(ui.available_width() / 3.),
+                    )
+                    .changed();
+
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut cookie.value)
+                            .hint_text("Cookie Value")
+                            .margin(5.)
+                            .vertical_align(egui::Align::Center)
+                            .desired_width(ui.available_width() - 40.),
+                    )
+                    .changed();
+
+                let bin_icon = egui::include_image!("../../assets/trash.svg");
+                ui.add(
+                    egui::Button::image(
+                        egui::Image::new(bin_icon).fit_to_exact_size(egui::vec2(16., 16.)),
+                    )
+                    .image_tint_follows_text_color(true),
+                )
+                .on_hover_text("Remove Cookie")
+                .clicked()
+            })
+            .inner;
+
+        ui.horizontal(|ui| {
+            ui.label(&cookie.domain);
+
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut cookie.path)
+                        .hint_text("Path")
+                        .margin(5.)
+                        .desired_width(80.),
+                )
+                .changed();
+
+            changed |= ui.checkbox(&mut cookie.secure, "Secure").changed();
+            changed |= ui.checkbox(&mut cookie.http_only, "HttpOnly").changed();
+            changed |= ui
+                .checkbox(&mut cookie.include_subdomains, "Subdomains")
+                .changed();
+
+            ui.label(format!("Expires: {}", cookie.expiry()));
+        });
+
+        (changed, removed)
+    })
+    .inner
+}
+
+/// The button closing the window
+fn done_button(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+        if ui
+            .add(default_button(
+                None,
+                "Done!",
+                ui.available_width(),
+                ui.visuals().text_color(),
+            ))
+            .clicked()
+        {
+            app.cookie_editor_open = false;
+        }
+    });
+}
+
+/// Ask for a Netscape `cookies.txt` file and add its cookies to the jar
+fn import(jar: &CookieJar) -> Notification {
+    let Some(file_path) = rfd::FileDialog::new()
+        .add_filter("cookies.txt", &["txt"])
+        .pick_file()
+    else {
+        return Notification::default();
+    };
+
+    let cookies = match std::fs::read_to_string(&file_path)
+        .map_err(|error| format!("Could not read {} - {error}", file_path.display()))
+        .and_then(|contents| cookies::from_netscape(&contents))
+    {
+        Ok(cookies) => cookies,
+        Err(error) => return Notification::new(error, NotificationKind::ERROR),
+    };
+
+    // Expired cookies aren't kept
+    let imported = cookies
+        .iter()
+        .filter(|cookie| jar.insert(cookie).is_ok())
+        .count();
+
+    Notification::new(
+        format!(
+            "Imported {imported} of {} cookies from {}.",
+            cookies.len(),
+            file_path.display()
+        ),
+        NotificationKind::INFO,
+    )
+}
+
+/// Ask where to save the jar's cookies as a Netscape `cookies.txt` file
+fn export(jar: &CookieJar) -> Notification {
+    let Some(file_path) = rfd::FileDialog::new()
+        .set_file_name("cookies.txt")
+        .save_file()
+    else {
+        return Notification::default();
+    };
+
+    match std::fs::write(&file_path, cookies::to_netscape(&jar.records())) {
+        Ok(()) => Notification::new(
+            format!("Exported the cookies to {}.", file_path.display()),
+            NotificationKind::INFO,
+        ),
+        Err(error) => Notification::new(
+            format!("Could not write {} - {error}", file_path.display()),
+            NotificationKind::ERROR,
+        ),
+    }
+}
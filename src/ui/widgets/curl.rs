@@ -0,0 +1,79 @@
+use eframe::egui::{self};
+
+use crate::{
+    ui::widgets::buttons::default_button,
+    utils::{formats::curl, request::Request, reqwestur::Reqwestur},
+};
+
+/// The window to paste a curl command into, replacing the current request
+pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("curl_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Import cURL")
+            .with_inner_size([500.0, 400.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                ui.label("Paste a curl command to replace the current request.");
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.)
+                    .show(ui, |ui| {
+                        ui.add_sized(
+                            ui.available_size(),
+                            egui::TextEdit::multiline(&mut app.curl_command)
+                                .code_editor()
+                                .hint_text("curl -X POST https://example.com -d 'name=value'")
+                                .margin(5.),
+                        );
+                    });
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Import",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        let parsed = curl::parse(&app.curl_command);
+                        let notification = parsed.notification();
+
+                        match parsed.requests.into_iter().next() {
+                            Some(imported) => {
+                                *request = Request {
+                                    name: request.name.clone(),
+                                    ..imported
+                                };
+                                request.notification(&notification);
+                                app.curl_command.clear();
+                                app.curl_editor_open = false;
+                            }
+                            None => app.notification = notification,
+                        }
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.curl_editor_open = false;
+            }
+        },
+    );
+}
+
+/// Offer to copy the request as a curl command when the response is right-clicked
+pub fn context_menu(response: &egui::Response, request: &Request) {
+    response.context_menu(|ui| {
+        if ui.button("Copy as cURL").clicked() {
+            ui.ctx().copy_text(curl::serialize(request));
+            ui.close();
+        }
+    });
+}
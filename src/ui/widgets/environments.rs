@@ -0,0 +1,393 @@
+use std::sync::Arc;
+
+use eframe::egui::{self, text::LayoutJob};
+
+use crate::{
+    ui::widgets::{
+        buttons::default_button,
+        notification::{Notification, NotificationKind},
+    },
+    utils::{
+        environments::{Environment, placeholders},
+        reqwestur::Reqwestur,
+        secrets::Secret,
+    },
+};
+
+/// The environment editor window
+pub fn editor(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("environment_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Environment Editor")
+            .with_inner_size([500.0, 500.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                ui.horizontal(|ui| {
+                    picker(app, ui);
+
+                    let add_icon = egui::include_image!("../../assets/plus.svg");
+                    if ui
+                        .add(default_button(
+                            Some(add_icon),
+                            "New Environment",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        let name = format!("Environment {}", app.environments.len() + 1);
+                        app.environments.push(Environment::new(name));
+                        app.active_environment = Some(app.environments.len() - 1);
+                    }
+                });
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                let mut removed = false;
+                match app
+                    .active_environment
+                    .and_then(|index| app.environments.get_mut(index))
+                {
+                    Some(environment) => {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut environment.name)
+                                .desired_width(ui.available_width())
+                                .hint_text("Environment Name")
+                                .margin(5.),
+                        );
+
+                        ui.horizontal(|ui| {
+                            ui.checkbox(
+                                &mut environment.keep_cookies,
+                                "Keep cookies between requests?",
+                            );
+
+                            let cookies = environment.cookies.len();
+                            if environment.keep_cookies
+                                && ui
+                                    .add_enabled(
+                                        cookies > 0,
+                                        egui::Button::new(format!("Clear Cookies ({cookies})")),
+                                    )
+                                    .clicked()
+                            {
+                                environment.cookies.clear();
+                            }
+
+                            if environment.keep_cookies && ui.button("Manage Cookies").clicked() {
+                                app.cookies = environment.cookies.records();
+                                app.cookie_editor_open = true;
+                            }
+                        });
+
+                        // Only meant for test servers, a banner is shown while either is ticked
+                        ui.horizontal(|ui| {
+                            ui.checkbox(
+                                &mut environment.accept_invalid_certificates,
+                                "Accept invalid certificates?",
+                            );
+                            ui.checkbox(
+                                &mut environment.accept_invalid_hostnames,
+                                "Accept invalid hostnames?",
+                            );
+                        });
+
+                        ui.horizontal(|ui| {
+                            let width = ui.available_width() / 3. - 6.;
+
+                            let add_icon = egui::include_image!("../../assets/plus.svg");
+                            if ui
+                                .add(default_button(
+                                    Some(add_icon.clone()),
+                                    "New Variable",
+                                    width,
+                                    ui.visuals().text_color(),
+                                ))
+                                .clicked()
+                            {
+                                environment
+                                    .variables
+                                    .push((String::default(), String::default()));
+                            }
+
+                            if ui
+                                .add(default_button(
+                                    Some(add_icon),
+                                    "New Secret",
+                                    width,
+                                    ui.visuals().text_color(),
+                                ))
+                                .clicked()
+                            {
+                                environment
+                                    .secrets
+                                    .push((String::default(), Secret::default()));
+                            }
+
+                            let bin_icon = egui::include_image!("../../assets/trash.svg");
+                            removed = ui
+                                .add(default_button(
+                                    Some(bin_icon),
+                                    "Delete Environment",
+                                    width,
+                                    ui.visuals().text_color(),
+                                ))
+                                .clicked();
+                        });
+
+                        ui.add_space(2.);
+
+                        let unlocked = app.vault.is_unlocked();
+                        let (mut deleted, mut deleted_secret) = (None, None);
+                        egui::ScrollArea::vertical()
+                            .auto_shrink(false)
+                            .max_height(ui.available_height() - 34.)
+                            .max_width(ui.available_width())
+                            .show(ui, |ui| {
+                                for (row, (name, value)) in
+                                    environment.variables.iter_mut().enumerate()
+                                {
+                                    if variable_row(ui, name, value, false, true).1 {
+                                        deleted = Some(row);
+                                    }
+                                }
+
+                                if environment.secrets.is_empty() {
+                                    return;
+                                }
+
+                                ui.add_space(4.);
+                                ui.label(egui::RichText::new("Secrets").size(14.));
+
+                                if !unlocked
+                                    && ui
+                                        .add(default_button(
+                                            None,
+                                            "Unlock the vault to edit secret values.",
+                                            ui.available_width(),
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
+                                {
+                                    app.vault_modal_open = true;
+                                }
+
+                                for (row, (name, secret)) in
+                                    environment.secrets.iter_mut().enumerate()
+                                {
+                                    let (changed, delete) =
+                                        variable_row(ui, name, &mut secret.value, true, unlocked);
+
+                                    if changed && let Err(error) = app.vault.set(secret) {
+                                        app.notification =
+                                            Notification::new(error, NotificationKind::ERROR);
+                                    }
+
+                                    if delete {
+                                        deleted_secret = Some(row);
+                                    }
+                                }
+                            });
+
+                        if let Some(row) = deleted {
+                            environment.variables.remove(row);
+                        }
+
+                        if let Some(row) = deleted_secret {
+                            let (_, secret) = environment.secrets.remove(row);
+                            let _ = app.vault.remove(&secret);
+                        }
+                    }
+                    None => {
+                        ui.label(
+                            "Create or select an environment to fill its variables into requests, \
+                             e.g. {{baseUrl}}/users.",
+                        );
+                    }
+                }
+
+                if removed && let Some(index) = app.active_environment.take() {
+                    let environment = app.environments.remove(index);
+                    for (_, secret) in &environment.secrets {
+                        let _ = app.vault.remove(secret);
+                    }
+                }
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Done!",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.environment_editor_open = false;
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.environment_editor_open = false;
+            }
+        },
+    );
+}
+
+/// A row to edit a variable, returning whether its value changed and whether it should be removed
+fn variable_row(
+    ui: &mut egui::Ui,
+    name: &mut String,
+    value: &mut String,
+    secret: bool,
+    enabled: bool,
+) -> (bool, bool) {
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(name)
+                    .hint_text("Variable Name")
+                    .margin(5.)
+                    .vertical_align(egui::Align::Center)
+                    .desired_width(ui.available_width() / 2. - 50.),
+            );
+
+            // Secret values are masked and can only be edited once the vault is unlocked
+            let changed = ui
+                .add_enabled(
+                    enabled,
+                    egui::TextEdit::singleline(value)
+                        .hint_text(if secret {
+                            "Secret Value"
+                        } else {
+                            "Variable Value"
+                        })
+                        .password(secret)
+                        .margin(5.)
+                        .vertical_align(egui::Align::Center)
+                        .desired_width(ui.available_width() - 40.),
+                )
+                .changed();
+
+            let bin_icon = egui::include_image!("../../assets/trash.svg");
+            let removed = ui
+                .add(
+                    egui::Button::image(
+                        egui::Image::new(bin_icon).fit_to_exact_size(egui::vec2(16., 16.)),
+                    )
+                    .image_tint_follows_text_color(true),
+                )
+                .on_hover_text("Remove Variable")
+                .clicked();
+
+            (changed, removed)
+        })
+        .inner
+    })
+    .inner
+}
+
+/// A dropdown to select the environment filled into requests
+pub fn picker(app: &mut Reqwestur, ui: &mut egui::Ui) -> egui::Response {
+    let selected = app
+        .environment()
+        .map(|environment| environment.name.clone())
+        .unwrap_or("No Environment".to_string());
+    let names: Vec<String> = app
+        .environments
+        .iter()
+        .map(|environment| environment.name.clone())
+        .collect();
+
+    egui::ComboBox::from_id_salt("active_environment")
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut app.active_environment, None, "No Environment");
+            for (index, name) in names.into_iter().enumerate() {
+                ui.selectable_value(&mut app.active_environment, Some(index), name);
+            }
+        })
+        .response
+}
+
+/// A layouter for single line editors, colouring the `{{variable}}` placeholders
+pub fn layouter(
+    variables: Vec<(String, String)>,
+) -> impl FnMut(&egui::Ui, &dyn egui::TextBuffer, f32) -> Arc<egui::Galley> {
+    move |ui: &egui::Ui, buffer: &dyn egui::TextBuffer, wrap_width: f32| {
+        let job = LayoutJob::simple(
+            buffer.as_str().to_string(),
+            egui::FontSelection::default().resolve(ui.style()),
+            ui.visuals().text_color(),
+            wrap_width,
+        );
+
+        ui.fonts(|fonts| fonts.layout_job(highlight(ui, job, &variables)))
+    }
+}
+
+/// Colour the placeholders in a laid out text, those without a variable are underlined as errors
+pub fn highlight(ui: &egui::Ui, mut job: LayoutJob, variables: &[(String, String)]) -> LayoutJob {
+    let marks: Vec<_> = placeholders(&job.text)
+        .into_iter()
+        .map(|placeholder| {
+            let mut format = egui::TextFormat::default();
+            if variables.iter().any(|(name, _)| *name == placeholder.name) {
+                format.color = ui.visuals().hyperlink_color;
+            } else {
+                format.color = ui.visuals().error_fg_color;
+                format.underline = egui::Stroke::new(1., ui.visuals().error_fg_color);
+            }
+            (placeholder.range, format)
+        })
+        .collect();
+
+    if marks.is_empty() {
+        return job;
+    }
+
+    let mut sections = Vec::with_capacity(job.sections.len() + marks.len() * 2);
+    for section in job.sections.drain(..) {
+        let (mut start, end) = (section.byte_range.start, section.byte_range.end);
+        let mut leading_space = section.leading_space;
+        let mut push = |range: std::ops::Range<usize>, format: egui::TextFormat| {
+            sections.push(egui::text::LayoutSection {
+                leading_space: std::mem::take(&mut leading_space),
+                byte_range: range,
+                format,
+            });
+        };
+
+        for (range, marked) in &marks {
+            if range.end <= start || range.start >= end {
+                continue;
+            }
+
+            if range.start > start {
+                push(start..range.start, section.format.clone());
+            }
+
+            let stop = range.end.min(end);
+            push(
+                start.max(range.start)..stop,
+                egui::TextFormat {
+                    color: marked.color,
+                    underline: marked.underline,
+                    ..section.format.clone()
+                },
+            );
+            start = stop;
+        }
+
+        if start < end {
+            push(start..end, section.format);
+        }
+    }
+
+    job.sections = sections;
+    job
+}
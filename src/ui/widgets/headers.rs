@@ -1,8 +1,8 @@
 use eframe::egui::{self};
 
 use crate::{
-    ui::widgets::buttons::default_button,
-    utils::{request::Request, reqwestur::Reqwestur},
+    ui::widgets::{buttons::default_button, environments},
+    utils::{cookies::Cookie, request::Request, reqwestur::Reqwestur},
 };
 
 /// The header editor window
@@ -14,6 +14,8 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
             .with_inner_size([500.0, 500.0]),
         |context, _class| {
             egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                let mut layouter = environments::layouter(app.variables());
+
                 let add_icon = egui::include_image!("../../assets/plus.svg");
                 if ui
                     .add(default_button(
@@ -49,6 +51,7 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
 
                                         let value_editor = egui::TextEdit::singleline(value)
                                             .hint_text("Header Value")
+                                            .layouter(&mut layouter)
                                             .margin(5.)
                                             .vertical_align(egui::Align::Center)
                                             .desired_width(ui.available_width());
@@ -100,6 +103,23 @@ impl StringToVec for Vec<String> {
     }
 }
 
+impl StringToVec for Vec<Cookie> {
+    fn convert(&self) -> Vec<Vec<String>> {
+        self.iter()
+            .map(|cookie| {
+                vec![
+                    cookie.name.to_string(),
+                    cookie.value.to_string(),
+                    cookie.domain.to_string(),
+                    cookie.path.to_string(),
+                    cookie.expiry(),
+                    cookie.flags(),
+                ]
+            })
+            .collect()
+    }
+}
+
 /// The viewer that displays the request's headers in a table-like grid
 pub fn viewer(content: Vec<Vec<String>>) -> impl egui::Widget {
     move |ui: &mut egui::Ui| {
@@ -1,7 +1,7 @@
 use eframe::egui::{self};
 
 use crate::{
-    ui::widgets::{buttons::default_button, chip::Chip},
+    ui::widgets::{buttons::default_button, chip::Chip, curl},
     utils::{
         request::Request,
         reqwestur::{AppView, Reqwestur},
@@ -16,7 +16,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
         egui::CentralPanel::default()
             .show(ui.ctx(), |ui| {
                 ui.vertical(|ui| {
-                    if history.len() == 0 {
+                    if history.is_empty() {
                         ui.label("You haven't made any requests yet!");
                     } else {
                         let bin_icon = egui::include_image!("../../assets/trash.svg");
@@ -88,25 +88,35 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                             .clicked()
                                                         {
                                                             let Request {
+                                                                name,
                                                                 method,
                                                                 headers,
+                                                                auth,
                                                                 address,
                                                                 timestamp: _,
                                                                 content_type,
                                                                 body,
                                                                 params,
+                                                                timeout,
+                                                                certificate,
+                                                                proxy,
                                                                 response: _,
                                                                 notification: _,
                                                                 event: _,
                                                             } = row_data.clone();
 
                                                             app.saved_requests.push(Request {
+                                                                name,
                                                                 method,
                                                                 headers,
+                                                                auth,
                                                                 address,
                                                                 content_type,
                                                                 body,
                                                                 params,
+                                                                timeout,
+                                                                certificate,
+                                                                proxy,
                                                                 ..Default::default()
                                                             });
                                                         }
@@ -115,7 +125,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                     ui.vertical(|ui| {
                                                         ui.horizontal(|ui| {
                                                             Chip::new(
-                                                                &row_data.method.to_string(),
+                                                                row_data.method.to_string(),
                                                                 row_data.method.to_colour(
                                                                     ui.visuals().dark_mode,
                                                                 ),
@@ -123,7 +133,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                             .show(ui);
 
                                                             Chip::new(
-                                                                &row_data
+                                                                row_data
                                                                     .response
                                                                     .status
                                                                     .to_string(),
@@ -139,7 +149,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                                 ),
                                                                 |ui| {
                                                                     Chip::new(
-                                                                        &row_data.timestamp.clone(),
+                                                                        row_data.timestamp.clone(),
                                                                         egui::Color32::ORANGE,
                                                                     )
                                                                     .show(ui);
@@ -147,15 +157,17 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                             );
                                                         });
 
-                                                        ui.add(
+                                                        let uri = ui.add(
                                                             egui::Label::new(
                                                                 egui::RichText::new(
                                                                     &row_data.address.uri,
                                                                 )
                                                                 .size(14.),
                                                             )
-                                                            .truncate(),
+                                                            .truncate()
+                                                            .sense(egui::Sense::click()),
                                                         );
+                                                        curl::context_menu(&uri, row_data);
                                                     });
                                                 });
                                             });
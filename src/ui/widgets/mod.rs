@@ -1,17 +1,26 @@
 pub(crate) mod about;
+pub(crate) mod auth;
 pub(crate) mod buttons;
 pub(crate) mod certificates;
 pub(crate) mod chip;
+pub(crate) mod connection;
+pub(crate) mod cookies;
+pub(crate) mod curl;
+pub(crate) mod environments;
 pub(crate) mod groups;
 pub(crate) mod headers;
 pub(crate) mod help;
 pub(crate) mod history;
 pub(crate) mod home;
 pub(crate) mod menu;
-pub(crate) mod notification;
+pub mod notification;
 pub(crate) mod payload;
+pub(crate) mod proxy;
+pub(crate) mod query;
 pub(crate) mod request;
 pub(crate) mod response;
 pub(crate) mod saved_requests;
 pub(crate) mod tabs;
 pub(crate) mod task_bar;
+pub(crate) mod tls;
+pub(crate) mod vault;
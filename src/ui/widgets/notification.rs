@@ -48,6 +48,18 @@ impl Notification {
         }
     }
 
+    /// Get the message of the current Notification, if there is one
+    pub fn message(&self) -> Option<&str> {
+        self.inner.as_ref().map(|inner| inner.message.as_str())
+    }
+
+    /// Whether the current Notification is an error
+    pub fn is_error(&self) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.kind == NotificationKind::ERROR)
+    }
+
     /// Clear the current Notification
     pub fn clear(&mut self) {
         self.inner = None;
@@ -64,7 +76,7 @@ impl Notification {
     fn display_widget(&self) -> impl egui::Widget {
         move |ui: &mut egui::Ui| {
             // Grab the current notification if it exists
-            let notification = self.inner.clone().unwrap_or(NotificationInner::default());
+            let notification = self.inner.clone().unwrap_or_default();
 
             let icon = match notification.kind {
                 NotificationKind::INFO => {
@@ -122,14 +134,21 @@ impl Notification {
     /// Display a banner notification
     pub fn banner(&mut self, ui: &mut egui::Ui) {
         if self.inner.is_some() {
-            ui.add(self.banner_widget());
+            ui.add(self.banner_widget("notification_banner", true));
         }
     }
 
-    fn banner_widget(&mut self) -> impl egui::Widget {
+    /// Display a banner which can't be closed, e.g. a warning that lasts as long as a setting
+    pub fn fixed_banner(&mut self, ui: &mut egui::Ui, id: &'static str) {
+        if self.inner.is_some() {
+            ui.add(self.banner_widget(id, false));
+        }
+    }
+
+    fn banner_widget(&mut self, id: &'static str, closable: bool) -> impl egui::Widget {
         move |ui: &mut egui::Ui| {
             // Grab the current notification if it exists
-            let notification = self.inner.clone().unwrap_or(NotificationInner::default());
+            let notification = self.inner.clone().unwrap_or_default();
 
             let icon = match notification.kind {
                 NotificationKind::INFO => {
@@ -153,7 +172,7 @@ impl Notification {
                 ..Default::default()
             };
 
-            egui::TopBottomPanel::top("notification_banner")
+            egui::TopBottomPanel::top(id)
                 .frame(frame)
                 .show(ui.ctx(), |ui| {
                     ui.horizontal(|ui| {
@@ -176,6 +195,10 @@ impl Notification {
                             .truncate(),
                         );
 
+                        if !closable {
+                            return;
+                        }
+
                         ui.with_layout(
                             egui::Layout::right_to_left(egui::Align::RIGHT)
                                 .with_main_align(egui::Align::RIGHT),
@@ -1,7 +1,7 @@
 use eframe::egui::{self};
 
 use crate::{
-    ui::widgets::buttons::default_button,
+    ui::widgets::{buttons::default_button, environments},
     utils::{
         request::{ContentType, Request},
         reqwestur::Reqwestur,
@@ -17,8 +17,10 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
             .with_inner_size([500.0, 500.0]),
         |context, _class| {
             egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                let variables = app.variables();
+
                 egui::ComboBox::new("body_type_dropdown", "Payload Type")
-                    .selected_text(&request.content_type.to_string())
+                    .selected_text(request.content_type.to_string())
                     .show_ui(ui, |ui| {
                         for body_type in ContentType::values() {
                             ui.selectable_value(
@@ -42,6 +44,8 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
                                     request.body = None;
                                 }
                                 ContentType::XWWWFORMURLENCODED => {
+                                    let mut layouter = environments::layouter(variables.clone());
+
                                     let add_icon = egui::include_image!("../../assets/plus.svg");
                                     if ui
                                         .add(default_button(
@@ -93,6 +97,7 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
                                                                         value,
                                                                     )
                                                                     .hint_text("Field Value")
+                                                                    .layouter(&mut layouter)
                                                                     .margin(5.)
                                                                     .vertical_align(
                                                                         egui::Align::Center,
@@ -131,6 +136,8 @@ pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
                                                         _ => "text",
                                                     },
                                                 );
+                                            let layout_job =
+                                                environments::highlight(ui, layout_job, &variables);
                                             ui.fonts(|f| f.layout_job(layout_job))
                                         };
 
@@ -0,0 +1,206 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::{
+        auth::credential,
+        buttons::default_button,
+        environments,
+        notification::{Notification, NotificationKind},
+        tls::remove_button,
+    },
+    utils::{proxy::ProxyKind, request::Request, reqwestur::Reqwestur},
+};
+
+/// The environment variables read when the system's proxy is used
+const PROXY_VARIABLES: [&str; 4] = ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "NO_PROXY"];
+
+/// The proxy settings window, editing the request's own proxy if it has one
+pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("proxy_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Proxy Settings")
+            .with_inner_size([500.0, 500.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                let mut layouter = environments::layouter(app.variables());
+
+                if request.proxy.is_some() {
+                    ui.label(
+                        "This request is sent through its own proxy, untick Custom Proxy? to use \
+                         the proxy of every request.",
+                    );
+                } else {
+                    ui.label("Used by every request without a custom proxy.");
+                }
+
+                let proxy = request.proxy.as_mut().unwrap_or(&mut app.proxy);
+                let mut unlock = false;
+
+                ui.horizontal(|ui| {
+                    ui.label("Proxy:");
+                    egui::ComboBox::from_id_salt("proxy_kind")
+                        .selected_text(proxy.kind.to_string())
+                        .show_ui(ui, |ui| {
+                            for kind in ProxyKind::values() {
+                                let label = kind.to_string();
+                                ui.selectable_value(&mut proxy.kind, kind, label);
+                            }
+                        });
+                });
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .max_height(ui.available_height() - 34.)
+                    .max_width(ui.available_width())
+                    .show(ui, |ui| match proxy.kind {
+                        ProxyKind::SYSTEM => {
+                            ui.label(
+                                "Requests are sent through the proxy in the environment \
+                                 variables, hosts in NO_PROXY are connected to directly.",
+                            );
+
+                            egui::Grid::new("proxy_variables")
+                                .num_columns(2)
+                                .spacing([10., 4.])
+                                .show(ui, |ui| {
+                                    for name in PROXY_VARIABLES {
+                                        ui.label(format!("{name}:"));
+                                        ui.label(
+                                            std::env::var(name)
+                                                .or(std::env::var(name.to_lowercase()))
+                                                .unwrap_or("Not Set".to_string()),
+                                        );
+                                        ui.end_row();
+                                    }
+                                });
+                        }
+                        ProxyKind::NONE => {
+                            ui.label("Requests connect to servers directly.");
+                        }
+                        ProxyKind::HTTP | ProxyKind::HTTPS | ProxyKind::SOCKS5 => {
+                            credential(
+                                ui,
+                                &mut proxy.address,
+                                "Host and Port, e.g. proxy.example.com:8080",
+                                &mut layouter,
+                            );
+
+                            ui.add_space(4.);
+                            ui.label(egui::RichText::new("Authentication").size(14.));
+                            ui.label("Leave the username empty if the proxy doesn't need one.");
+                            credential(ui, &mut proxy.username, "Username", &mut layouter);
+                            // The password is only stored in the vault
+                            let unlocked = app.vault.is_unlocked();
+                            if proxy.password.value.is_empty()
+                                && let Some(password) = app.vault.get(&proxy.password.id)
+                            {
+                                proxy.password.value = password.to_string();
+                            }
+                            if ui
+                                .add_enabled_ui(unlocked, |ui| {
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut proxy.password.value)
+                                            .hint_text("Password")
+                                            .margin(5.)
+                                            .desired_width(ui.available_width())
+                                            .password(true),
+                                    )
+                                })
+                                .inner
+                                .changed()
+                                && let Err(error) = app.vault.set(&proxy.password)
+                            {
+                                app.notification =
+                                    Notification::new(error, NotificationKind::ERROR);
+                            }
+
+                            unlock = !unlocked
+                                && ui
+                                    .add(default_button(
+                                        None,
+                                        "Unlock the vault to enter the password.",
+                                        ui.available_width(),
+                                        ui.visuals().text_color(),
+                                    ))
+                                    .clicked();
+
+                            ui.add_space(4.);
+                            ui.label(egui::RichText::new("Bypass").size(14.));
+                            ui.label(
+                                "Hosts connected to directly, as in NO_PROXY, e.g. localhost, \
+                                 .example.com or 10.0.0.0/8.",
+                            );
+
+                            let mut deleted = None;
+                            for (row, pattern) in proxy.bypass.iter_mut().enumerate() {
+                                ui.push_id(row, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(pattern)
+                                                .hint_text("Host, Domain or IP Range")
+                                                .margin(5.)
+                                                .vertical_align(egui::Align::Center)
+                                                .desired_width(ui.available_width() - 40.),
+                                        );
+
+                                        if remove_button(ui, "Remove Pattern") {
+                                            deleted = Some(row);
+                                        }
+                                    });
+                                });
+                            }
+
+                            if let Some(row) = deleted {
+                                proxy.bypass.remove(row);
+                            }
+
+                            let add_icon = egui::include_image!("../../assets/plus.svg");
+                            if ui
+                                .add(default_button(
+                                    Some(add_icon),
+                                    "New Pattern",
+                                    ui.available_width(),
+                                    ui.visuals().text_color(),
+                                ))
+                                .clicked()
+                            {
+                                proxy.bypass.push(String::new());
+                            }
+
+                            ui.add_space(2.);
+                            ui.label(
+                                "Use {{variable}} to fill in a value from the active environment.",
+                            );
+                        }
+                    });
+
+                if unlock {
+                    app.vault_modal_open = true;
+                }
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Done!",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.proxy_editor_open = false;
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.proxy_editor_open = false;
+            }
+        },
+    );
+}
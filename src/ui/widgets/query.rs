@@ -0,0 +1,127 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::{buttons::default_button, environments},
+    utils::{
+        request::{QueryParam, Request},
+        reqwestur::Reqwestur,
+    },
+};
+
+/// The query parameter editor window, changes are written back to the URI as they're made
+pub fn editor(app: &mut Reqwestur, request: &mut Request, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("query_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("Query Parameter Editor")
+            .with_inner_size([500.0, 500.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                let mut layouter = environments::layouter(app.variables());
+                request.address.query_from_uri();
+
+                let add_icon = egui::include_image!("../../assets/plus.svg");
+                if ui
+                    .add(default_button(
+                        Some(add_icon),
+                        "New Parameter",
+                        ui.available_width(),
+                        ui.visuals().text_color(),
+                    ))
+                    .clicked()
+                {
+                    request.address.query.push(QueryParam::new("", ""));
+                }
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                let mut changed = false;
+                let mut deleted = None;
+                egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .max_height(ui.available_height() - 34.)
+                    .max_width(ui.available_width())
+                    .show(ui, |ui| {
+                        for (row, param) in request.address.query.iter_mut().enumerate() {
+                            ui.push_id(row, |ui| {
+                                ui.group(|ui| {
+                                    ui.horizontal(|ui| {
+                                        changed |= ui
+                                            .checkbox(&mut param.enabled, "")
+                                            .on_hover_text("Send Parameter?")
+                                            .changed();
+
+                                        changed |= ui
+                                            .add(
+                                                egui::TextEdit::singleline(&mut param.name)
+                                                    .hint_text("Parameter Name")
+                                                    .margin(5.)
+                                                    .vertical_align(egui::Align::Center)
+                                                    .desired_width(ui.available_width() / 2. - 30.),
+                                            )
+                                            .changed();
+
+                                        changed |= ui
+                                            .add(
+                                                egui::TextEdit::singleline(&mut param.value)
+                                                    .hint_text("Parameter Value")
+                                                    .layouter(&mut layouter)
+                                                    .margin(5.)
+                                                    .vertical_align(egui::Align::Center)
+                                                    .desired_width(ui.available_width() - 40.),
+                                            )
+                                            .changed();
+
+                                        let bin_icon =
+                                            egui::include_image!("../../assets/trash.svg");
+                                        if ui
+                                            .add(
+                                                egui::Button::image(
+                                                    egui::Image::new(bin_icon)
+                                                        .fit_to_exact_size(egui::vec2(16., 16.)),
+                                                )
+                                                .image_tint_follows_text_color(true),
+                                            )
+                                            .on_hover_text("Remove Parameter")
+                                            .clicked()
+                                        {
+                                            deleted = Some(row);
+                                        }
+                                    });
+                                });
+                            });
+                        }
+                    });
+
+                if let Some(row) = deleted {
+                    request.address.query.remove(row);
+                    changed = true;
+                }
+
+                if changed {
+                    request.address.uri_from_query();
+                }
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Done!",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.query_editor_open = false;
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.query_editor_open = false;
+            }
+        },
+    );
+}
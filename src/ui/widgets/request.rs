@@ -1,13 +1,15 @@
 use eframe::egui::{self, scroll_area::ScrollSource};
 
 use crate::{
-    ui::widgets::{
-        buttons::default_button,
-        notification::{Notification, NotificationKind},
-    },
+    ui::widgets::{auth, buttons::default_button, environments},
     utils::{
-        request::{ContentType, Method, Request},
+        auth::AuthKind,
+        engine::DEFAULT_TIMEOUT,
+        formats::curl,
+        proxy::ProxySettings,
+        request::{ContentType, Method, Request, RequestEvent},
         reqwestur::Reqwestur,
+        secrets::Secret,
     },
 };
 
@@ -37,6 +39,19 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                             ui.add_space(1.);
 
                             ui.group(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut request.name)
+                                        .desired_width(ui.available_width())
+                                        .hint_text("Request Name (optional)")
+                                        .margin(5.),
+                                );
+
+                                let variables = app.variables();
+                                let mut layouter = environments::layouter(variables.clone());
+
+                                // The URI may have been replaced, e.g. by loading a saved request
+                                request.address.query_from_uri();
+
                                 ui.horizontal(|ui| {
                                     egui::ComboBox::from_id_salt("request_method")
                                         .selected_text(request.method.to_string())
@@ -53,29 +68,52 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                             }
                                         });
 
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut request.address.uri)
+                                            .min_size(egui::vec2(ui.available_width(), 10.))
+                                            .hint_text("Request URL, e.g. https://test.com")
+                                            .layouter(&mut layouter)
+                                            .margin(5.),
+                                    );
+                                });
+
+                                // Checked every frame, as the environment's variables can change too
+                                request.address.validate(&variables);
+                                request.address.notification.display(ui);
+                            });
+
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new("cURL").size(14.));
+
+                                ui.horizontal(|ui| {
+                                    let width = ui.available_width() / 2. - 4.;
+
+                                    let import_icon =
+                                        egui::include_image!("../../assets/upload.svg");
                                     if ui
-                                        .add(
-                                            egui::TextEdit::singleline(&mut request.address.uri)
-                                                .min_size(egui::vec2(ui.available_width(), 10.))
-                                                .hint_text("Request URL, e.g. http://test.com")
-                                                .margin(5.),
-                                        )
-                                        .changed()
+                                        .add(default_button(
+                                            Some(import_icon),
+                                            "Import cURL",
+                                            width,
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
                                     {
-                                        if let Err(error) =
-                                            reqwest::Url::parse(&request.address.uri)
-                                        {
-                                            request.address.notification = Notification::new(
-                                                format!("URL cannot be parsed: {}!", error),
-                                                NotificationKind::ERROR,
-                                            );
-                                        } else {
-                                            request.address.notification.clear();
-                                        }
+                                        app.curl_editor_open = true;
                                     }
-                                });
 
-                                request.address.notification.display(ui);
+                                    if ui
+                                        .add(default_button(
+                                            None,
+                                            "Copy as cURL",
+                                            width,
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
+                                    {
+                                        ui.ctx().copy_text(curl::serialize(request));
+                                    }
+                                });
                             });
 
                             if [Method::PATCH, Method::POST, Method::PUT].contains(&request.method)
@@ -96,6 +134,23 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                 });
                             }
 
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new("Query Parameters").size(14.));
+
+                                let edit_icon = egui::include_image!("../../assets/pen.svg");
+                                if ui
+                                    .add(default_button(
+                                        Some(edit_icon),
+                                        "Parameter Management",
+                                        ui.available_width(),
+                                        ui.visuals().text_color(),
+                                    ))
+                                    .clicked()
+                                {
+                                    app.query_editor_open = true;
+                                }
+                            });
+
                             ui.group(|ui| {
                                 ui.label(egui::RichText::new("Request Headers").size(14.));
 
@@ -113,31 +168,142 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                 }
                             });
 
-                            let size = ui
-                                .group(|ui| {
-                                    ui.label(egui::RichText::new("Certificates").size(14.));
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new("Authorisation").size(14.));
 
-                                    ui.checkbox(
-                                        &mut app.use_certificate_authentication,
-                                        "Use Certificates?",
-                                    );
+                                ui.horizontal(|ui| {
+                                    auth::kind_picker(ui, &mut request.auth.kind);
 
-                                    if app.use_certificate_authentication {
-                                        let edit_icon =
-                                            egui::include_image!("../../assets/pen.svg");
-                                        if ui
-                                            .add(default_button(
+                                    let edit_icon = egui::include_image!("../../assets/pen.svg");
+                                    if ui
+                                        .add_enabled(
+                                            request.auth.kind != AuthKind::NONE,
+                                            default_button(
                                                 Some(edit_icon),
-                                                "Certificate Management",
+                                                "Credential Management",
                                                 ui.available_width(),
                                                 ui.visuals().text_color(),
-                                            ))
-                                            .clicked()
-                                        {
-                                            app.certificate_editor_open = true;
+                                            ),
+                                        )
+                                        .clicked()
+                                    {
+                                        app.auth_editor_open = true;
+                                    }
+                                });
+                            });
+
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new("Timeout").size(14.));
+
+                                ui.horizontal(|ui| {
+                                    let mut custom = request.timeout.is_some();
+                                    if ui.checkbox(&mut custom, "Custom Timeout?").changed() {
+                                        request.timeout = custom.then_some(DEFAULT_TIMEOUT);
+                                    }
+
+                                    match &mut request.timeout {
+                                        Some(seconds) => {
+                                            ui.add(
+                                                egui::DragValue::new(seconds)
+                                                    .range(1..=3600)
+                                                    .suffix(" seconds"),
+                                            );
                                         }
-                                    } else {
-                                        app.certificate = None;
+                                        None => {
+                                            ui.label(format!("{DEFAULT_TIMEOUT} seconds"));
+                                        }
+                                    }
+                                });
+                            });
+
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new("Proxy").size(14.));
+
+                                ui.horizontal(|ui| {
+                                    // A custom proxy starts from the proxy of every request, with its own password in the vault
+                                    let mut custom = request.proxy.is_some();
+                                    if ui.checkbox(&mut custom, "Custom Proxy?").changed() {
+                                        request.proxy = custom.then(|| ProxySettings {
+                                            password: Secret {
+                                                value: app.proxy.password.value.clone(),
+                                                ..Default::default()
+                                            },
+                                            ..app.proxy.clone()
+                                        });
+                                    }
+
+                                    let edit_icon = egui::include_image!("../../assets/pen.svg");
+                                    if ui
+                                        .add(default_button(
+                                            Some(edit_icon),
+                                            "Proxy Settings",
+                                            ui.available_width(),
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
+                                    {
+                                        app.proxy_editor_open = true;
+                                    }
+                                });
+                            });
+
+                            let size = ui
+                                .group(|ui| {
+                                    ui.label(egui::RichText::new("Certificates").size(14.));
+
+                                    // Without a selection the certificate sent to the host is used
+                                    let names: Vec<String> = app
+                                        .certificates
+                                        .iter()
+                                        .map(|certificate| certificate.name.clone())
+                                        .collect();
+                                    egui::ComboBox::from_id_salt("request_certificate")
+                                        .selected_text(
+                                            request
+                                                .certificate
+                                                .clone()
+                                                .unwrap_or("Chosen by Host".to_string()),
+                                        )
+                                        .width(ui.available_width())
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(
+                                                &mut request.certificate,
+                                                None,
+                                                "Chosen by Host",
+                                            );
+                                            for name in names {
+                                                ui.selectable_value(
+                                                    &mut request.certificate,
+                                                    Some(name.clone()),
+                                                    name,
+                                                );
+                                            }
+                                        });
+
+                                    let edit_icon = egui::include_image!("../../assets/pen.svg");
+                                    if ui
+                                        .add(default_button(
+                                            Some(edit_icon),
+                                            "Certificate Library",
+                                            ui.available_width(),
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
+                                    {
+                                        app.certificate_editor_open = true;
+                                    }
+
+                                    let edit_icon = egui::include_image!("../../assets/pen.svg");
+                                    if ui
+                                        .add(default_button(
+                                            Some(edit_icon),
+                                            "TLS Settings",
+                                            ui.available_width(),
+                                            ui.visuals().text_color(),
+                                        ))
+                                        .clicked()
+                                    {
+                                        app.tls_editor_open = true;
                                     }
                                 })
                                 .response
@@ -156,33 +322,42 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                 |ui: &mut egui::Ui| {
                                     ui.add_space(5.);
 
-                                    let send_icon =
-                                        egui::include_image!("../../assets/paper_plane.svg");
-                                    if ui
-                                        .add_enabled(
-                                            true,
-                                            default_button(
-                                                Some(send_icon),
-                                                "Send!",
+                                    if request.event == RequestEvent::PENDING {
+                                        let cancel_icon =
+                                            egui::include_image!("../../assets/cross_circle.svg");
+                                        if ui
+                                            .add(default_button(
+                                                Some(cancel_icon),
+                                                "Cancel",
                                                 ui.available_width(),
                                                 ui.visuals().text_color(),
-                                            ),
-                                        )
-                                        .clicked()
-                                    {
-                                        let mut app_clone = app.clone();
-                                        std::thread::spawn(move || {
-                                            match app_clone.send() {
-                                                Ok(_) => {
-                                                    app_clone.history.lock().unwrap().push(
-                                                        app_clone.request.lock().unwrap().clone(),
-                                                    );
-                                                }
-                                                Err(_) => {
-                                                    todo!("Send the error to notification mutex.")
-                                                }
-                                            };
-                                        });
+                                            ))
+                                            .clicked()
+                                        {
+                                            app.cancel(request);
+                                        }
+                                    } else {
+                                        let send_icon =
+                                            egui::include_image!("../../assets/paper_plane.svg");
+                                        if ui
+                                            .add_enabled(
+                                                !request.address.uri.trim().is_empty()
+                                                    && !request.address.notification.is_error(),
+                                                default_button(
+                                                    Some(send_icon),
+                                                    "Send!",
+                                                    ui.available_width(),
+                                                    ui.visuals().text_color(),
+                                                ),
+                                            )
+                                            .on_disabled_hover_text(
+                                                "Enter a valid URL to send the request.",
+                                            )
+                                            .clicked()
+                                        {
+                                            // Any error is attached to the request's notification
+                                            let _ = app.send(request);
+                                        }
                                     }
 
                                     request.notification.display(ui);
@@ -2,19 +2,23 @@ use eframe::egui::{self, scroll_area::ScrollSource};
 
 use crate::{
     ui::widgets::{
+        buttons::default_button,
         chip::Chip,
+        connection,
         groups::centered_group,
         headers::{self, StringToVec},
         tabs::tabs,
     },
     utils::{
-        request::{Request, ResponseView},
+        request::{Request, RequestEvent, ResponseView},
+        reqwestur::Reqwestur,
+        snippets::Snippet,
         traits::{ToColour, ToStringForeign},
     },
 };
 
 /// The panel showing the request's response
-pub fn panel(request: &mut Request) -> impl egui::Widget {
+pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui::Widget + 'a {
     move |ui: &mut egui::Ui| {
         let frame = egui::frame::Frame {
             outer_margin: 0.0.into(),
@@ -32,50 +36,68 @@ pub fn panel(request: &mut Request) -> impl egui::Widget {
                     .show(ui, |ui| {
                         let response = request.response.clone();
                         match request.event {
-                            crate::utils::request::RequestEvent::UNSENT => {
-                                ui.add(centered_group(|ui| {
-                                    let globe_icon = egui::include_image!("../../assets/globe.svg");
-                                    ui.add(
-                                        egui::Image::new(globe_icon)
-                                            .fit_to_original_size(2.5)
-                                            .tint(ui.visuals().text_color()),
-                                    );
-
-                                    ui.add_space(5.);
-                                    ui.label("You haven't sent the request yet!");
-                                }));
-                            }
-                            crate::utils::request::RequestEvent::PENDING => {
+                            RequestEvent::PENDING => {
                                 ui.add_sized(
                                     egui::vec2(ui.available_width(), ui.available_height()),
                                     egui::Spinner::new().size(ui.available_width() / 10.),
                                 );
                             }
-                            crate::utils::request::RequestEvent::SENT => {
-                                egui::Frame::new()
-                                    .inner_margin(egui::Margin {
-                                        left: 5,
-                                        right: 5,
-                                        top: 5,
-                                        bottom: 2,
-                                    })
-                                    .show(ui, |ui| {
-                                        ui.horizontal(|ui| {
-                                            Chip::new(
-                                                response.status.to_string(),
-                                                response.status.to_colour(ui.visuals().dark_mode),
-                                            )
-                                            .show(ui);
-                                            Chip::new(response.body.len().to_string() + "B", None)
+                            RequestEvent::UNSENT | RequestEvent::SENT => {
+                                if request.event == RequestEvent::SENT {
+                                    egui::Frame::new()
+                                        .inner_margin(egui::Margin {
+                                            left: 5,
+                                            right: 5,
+                                            top: 5,
+                                            bottom: 2,
+                                        })
+                                        .show(ui, |ui| {
+                                            ui.horizontal(|ui| {
+                                                Chip::new(
+                                                    response.status.to_string(),
+                                                    response
+                                                        .status
+                                                        .to_colour(ui.visuals().dark_mode),
+                                                )
+                                                .show(ui);
+                                                Chip::new(
+                                                    response.body.len().to_string() + "B",
+                                                    None,
+                                                )
+                                                .show(ui);
+                                                Chip::new(
+                                                    response.duration.to_string() + "ms",
+                                                    None,
+                                                )
                                                 .show(ui);
+                                            });
                                         });
-                                    });
+                                }
+
                                 ui.add(tabs(
                                     ResponseView::values(),
                                     response.view,
                                     &mut request.response.view,
                                 ));
                                 match response.view {
+                                    // Code can be generated before the request is sent
+                                    ResponseView::CODE => {
+                                        ui.add(self::snippet_viewer(&mut app.snippet, request));
+                                    }
+                                    _ if request.event == RequestEvent::UNSENT => {
+                                        ui.add(centered_group(|ui| {
+                                            let globe_icon =
+                                                egui::include_image!("../../assets/globe.svg");
+                                            ui.add(
+                                                egui::Image::new(globe_icon)
+                                                    .fit_to_original_size(2.5)
+                                                    .tint(ui.visuals().text_color()),
+                                            );
+
+                                            ui.add_space(5.);
+                                            ui.label("You haven't sent the request yet!");
+                                        }));
+                                    }
                                     ResponseView::RESPONSE => {
                                         ui.add(self::body_editor(&mut request.response.body));
                                     }
@@ -83,7 +105,17 @@ pub fn panel(request: &mut Request) -> impl egui::Widget {
                                         ui.add(headers::viewer(response.headers.convert().clone()));
                                     }
                                     ResponseView::COOKIES => {
-                                        ui.add(headers::viewer(response.cookies.convert().clone()));
+                                        ui.add(headers::viewer(response.cookies.convert()));
+                                    }
+                                    ResponseView::CONNECTION => {
+                                        let mut inspect = false;
+                                        ui.add(connection::viewer(
+                                            &response.connection,
+                                            &mut inspect,
+                                        ));
+                                        if inspect {
+                                            app.inspect(request);
+                                        }
                                     }
                                 }
                             }
@@ -137,3 +169,70 @@ fn body_editor(body: &mut String) -> impl egui::Widget {
             .response
     }
 }
+
+/// The generated code for the request, in the selected language
+fn snippet_viewer<'a>(snippet: &'a mut Snippet, request: &'a Request) -> impl egui::Widget + 'a {
+    move |ui: &mut egui::Ui| {
+        ui.add_space(5.);
+        ui.horizontal(|ui| {
+            ui.add_space(3.);
+            egui::ComboBox::from_id_salt("snippet_language")
+                .selected_text(snippet.to_string())
+                .show_ui(ui, |ui| {
+                    for language in Snippet::values() {
+                        ui.selectable_value(snippet, language.clone(), language.to_string());
+                    }
+                });
+
+            if ui
+                .add(default_button(None, "Copy", 80., ui.visuals().text_color()))
+                .clicked()
+            {
+                ui.ctx().copy_text(snippet.generate(request));
+            }
+        });
+        ui.add_space(2.);
+
+        let code = snippet.generate(request);
+        let syntax = snippet.syntax();
+
+        egui::Frame::new()
+            .outer_margin(egui::Margin {
+                left: 3,
+                right: 3,
+                top: 1,
+                bottom: 3,
+            })
+            .stroke(egui::Stroke::new(
+                1.,
+                ui.style().noninteractive().bg_stroke.color,
+            ))
+            .show(ui, |ui| {
+                let theme =
+                    egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx(), ui.style());
+                let mut layouter = |ui: &egui::Ui, buf: &dyn egui::TextBuffer, _| {
+                    let mut layout_job = egui_extras::syntax_highlighting::highlight(
+                        ui.ctx(),
+                        ui.style(),
+                        &theme.clone(),
+                        buf.as_str(),
+                        syntax,
+                    );
+
+                    layout_job.wrap.max_width = ui.available_width() - 20.;
+
+                    ui.fonts(|f| f.layout_job(layout_job))
+                };
+
+                // The snippet is generated from the request, so it can't be edited
+                ui.add(
+                    egui::TextEdit::multiline(&mut code.as_str())
+                        .code_editor()
+                        .layouter(&mut layouter)
+                        .desired_width(ui.available_width())
+                        .min_size(egui::vec2(ui.available_width(), ui.available_height())),
+                )
+            })
+            .response
+    }
+}
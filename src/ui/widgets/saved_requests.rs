@@ -1,7 +1,7 @@
 use eframe::egui::{self, AtomExt, IntoAtoms};
 
 use crate::{
-    ui::widgets::chip::Chip,
+    ui::widgets::{chip::Chip, curl},
     utils::{
         request::Request,
         reqwestur::{AppView, Reqwestur},
@@ -15,7 +15,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
         egui::CentralPanel::default()
             .show(ui.ctx(), |ui| {
                 ui.vertical(|ui| {
-                    if app.saved_requests.len() == 0 {
+                    if app.saved_requests.is_empty() {
                         ui.label("You haven't saved any requests yet!");
                     } else {
                         egui::ScrollArea::vertical()
@@ -34,13 +34,18 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                             .corner_radius(5.)
                                             .show(ui, |ui| {
                                                 let Request {
+                                                    name,
                                                     method,
                                                     headers: _,
+                                                    auth: _,
                                                     address,
                                                     timestamp: _,
                                                     content_type: _,
                                                     body: _,
                                                     params: _,
+                                                    timeout: _,
+                                                    certificate: _,
+                                                    proxy: _,
                                                     response: _,
                                                     notification: _,
                                                     event: _,
@@ -48,6 +53,11 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
 
                                                 // Create widget to add
                                                 let method = method.to_string();
+                                                let label = if name.is_empty() {
+                                                    &address.uri
+                                                } else {
+                                                    name
+                                                };
 
                                                 ui.with_layout(
                                                     egui::Layout::left_to_right(egui::Align::Min)
@@ -70,7 +80,6 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                         let custom_button_id =
                                                             egui::Id::new("custom_button");
 
-                                                        // TODO: Readable label
                                                         let saved_request_button =
                                                             egui::Button::new((
                                                                 egui::Image::from(open_icon)
@@ -84,7 +93,7 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                                         20.,
                                                                     ),
                                                                 ),
-                                                                egui::RichText::new(&address.uri)
+                                                                egui::RichText::new(label)
                                                                     .size(16.)
                                                                     .into_atoms(),
                                                             ))
@@ -93,6 +102,11 @@ pub fn panel<'a>(app: &'a mut Reqwestur, request: &'a mut Request) -> impl egui:
                                                             .frame(false)
                                                             .atom_ui(ui);
 
+                                                        curl::context_menu(
+                                                            &saved_request_button.response,
+                                                            row_data,
+                                                        );
+
                                                         // Handle adding custom content
                                                         if let Some(rect) = saved_request_button
                                                             .rect(custom_button_id)
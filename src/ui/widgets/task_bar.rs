@@ -9,12 +9,15 @@ use crate::{
     ui::{
         widgets::{
             buttons::toggle_switch,
+            environments,
             notification::{Notification, NotificationKind},
         },
         window::PRIMARY,
     },
     utils::{
+        environments::redact,
         exports::{ExportType, RequestSourceType, ReqwesturIO},
+        proxy::ProxySettings,
         request::Request,
         reqwestur::Reqwestur,
     },
@@ -108,43 +111,72 @@ pub fn task_bar(app: &mut Reqwestur, request: &mut Request) -> impl egui::Widget
                                     .ui(ui, |ui| {
                                         ui.menu_button("Export", |ui| {
                                             ui.menu_button("History", |ui| {
-                                                for option in ExportType::values() {
+                                                for option in ExportType::values()
+                                                    .into_iter()
+                                                    .filter(ExportType::can_export)
+                                                {
                                                     if ui
                                                         .button(option.to_string().to_uppercase())
                                                         .clicked()
                                                     {
-                                                        let history = app.history.lock().unwrap();
-                                                        if let Some(output) = ReqwesturIO::new(
-                                                            history.clone(),
+                                                        export(
+                                                            app,
                                                             RequestSourceType::HISTORY,
                                                             option,
-                                                        ) {
-                                                            let _ = output.export();
-                                                        }
+                                                        );
                                                     }
                                                 }
                                             });
                                             ui.menu_button("Requests", |ui| {
-                                                for option in ExportType::values() {
+                                                for option in ExportType::values()
+                                                    .into_iter()
+                                                    .filter(ExportType::can_export)
+                                                {
                                                     if ui
                                                         .button(option.to_string().to_uppercase())
                                                         .clicked()
                                                     {
-                                                        if let Some(output) = ReqwesturIO::new(
-                                                            app.saved_requests.clone(),
+                                                        export(
+                                                            app,
                                                             RequestSourceType::SAVED,
                                                             option,
-                                                        ) {
-                                                            let _ = output.export();
-                                                        }
+                                                        );
                                                     }
                                                 }
                                             });
                                         });
 
                                         ui.menu_button("Import", |ui| {
-                                            if ui.button("History").clicked() {};
-                                            if ui.button("Requests").clicked() {};
+                                            if ui.button("History").clicked()
+                                                && let Some(input) = ReqwesturIO::open()
+                                            {
+                                                match input.import() {
+                                                    Ok(imported) => {
+                                                        app.notification = imported.notification();
+                                                        app.history
+                                                            .lock()
+                                                            .unwrap()
+                                                            .extend(imported.requests);
+                                                    }
+                                                    Err(notification) => {
+                                                        app.notification = notification;
+                                                    }
+                                                }
+                                            };
+                                            if ui.button("Requests").clicked()
+                                                && let Some(input) = ReqwesturIO::open()
+                                            {
+                                                match input.import() {
+                                                    Ok(imported) => {
+                                                        app.notification = imported.notification();
+                                                        app.saved_requests
+                                                            .extend(imported.requests);
+                                                    }
+                                                    Err(notification) => {
+                                                        app.notification = notification;
+                                                    }
+                                                }
+                                            };
                                         });
 
                                         if ui.button("Save Request").clicked() {
@@ -155,25 +187,35 @@ pub fn task_bar(app: &mut Reqwestur, request: &mut Request) -> impl egui::Widget
                                                 );
                                             } else {
                                                 let Request {
+                                                    name,
                                                     method,
                                                     headers,
+                                                    auth,
                                                     address,
                                                     timestamp: _,
                                                     content_type,
                                                     body,
                                                     params,
+                                                    timeout,
+                                                    certificate,
+                                                    proxy,
                                                     response: _,
                                                     notification: _,
                                                     event: _,
                                                 } = request.clone();
 
                                                 app.saved_requests.push(Request {
+                                                    name,
                                                     method,
                                                     headers,
+                                                    auth,
                                                     address,
                                                     content_type,
                                                     body,
                                                     params,
+                                                    timeout,
+                                                    certificate,
+                                                    proxy,
                                                     ..Default::default()
                                                 });
                                                 app.notification = Notification::new(
@@ -258,6 +300,26 @@ pub fn task_bar(app: &mut Reqwestur, request: &mut Request) -> impl egui::Widget
                                             app.about_modal_open = true;
                                         };
                                     });
+
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        let vault = if app.vault.is_unlocked() {
+                                            "Lock Vault"
+                                        } else {
+                                            "Unlock Vault"
+                                        };
+                                        if ui.button(vault).clicked() {
+                                            app.vault_modal_open = true;
+                                        }
+
+                                        if ui.button("Environments").clicked() {
+                                            app.environment_editor_open = true;
+                                        }
+
+                                        environments::picker(app, ui);
+                                    },
+                                );
                             });
                         });
                 });
@@ -265,3 +327,41 @@ pub fn task_bar(app: &mut Reqwestur, request: &mut Request) -> impl egui::Widget
             .response
     }
 }
+
+/// Export the requests with the secrets redacted, asking for the vault to be unlocked while secrets are locked
+fn export(app: &mut Reqwestur, source: RequestSourceType, option: ExportType) {
+    if app.secrets_locked() {
+        app.notification = Notification::new(
+            "Unlock the vault to export, the values of locked secrets can't be redacted.",
+            NotificationKind::WARN,
+        );
+        app.vault_modal_open = true;
+        return;
+    }
+
+    let requests = match source {
+        RequestSourceType::HISTORY => app.history.lock().unwrap().clone(),
+        RequestSourceType::SAVED => app.saved_requests.clone(),
+    };
+    if let Some(output) =
+        ReqwesturIO::new(redact_secrets(&requests, &app.secrets()), source, option)
+        && let Err(notification) = output.export()
+    {
+        app.notification = notification;
+    }
+}
+
+/// Replace the values of the unlocked secrets, and the credentials and proxy passwords typed into requests, with placeholders before the requests are exported
+fn redact_secrets(requests: &[Request], secrets: &[(String, String)]) -> Vec<Request> {
+    requests
+        .iter()
+        .map(|request| {
+            let request = redact(request, secrets);
+            Request {
+                auth: request.auth.masked(),
+                proxy: request.proxy.as_ref().map(ProxySettings::masked),
+                ..request
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,173 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::buttons::default_button,
+    utils::{reqwestur::Reqwestur, tls::TlsVersion},
+};
+
+/// The TLS settings window, used by every request
+pub fn editor(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    ui.ctx().show_viewport_immediate(
+        egui::ViewportId::from_hash_of("tls_editor"),
+        egui::ViewportBuilder::default()
+            .with_title("TLS Settings")
+            .with_inner_size([500.0, 500.0]),
+        |context, _class| {
+            egui::CentralPanel::default().show(ui.ctx(), |ui| {
+                let tls = &mut app.tls;
+
+                ui.horizontal(|ui| {
+                    ui.label("Minimum TLS Version:");
+                    egui::ComboBox::from_id_salt("min_tls_version")
+                        .selected_text(tls.min_version.to_string())
+                        .show_ui(ui, |ui| {
+                            for version in TlsVersion::values() {
+                                let label = version.to_string();
+                                ui.selectable_value(&mut tls.min_version, version, label);
+                            }
+                        });
+                });
+
+                ui.checkbox(&mut tls.sni, "Send the host as the server name (SNI)?");
+
+                ui.add_space(2.);
+                ui.separator();
+                ui.add_space(2.);
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .max_height(ui.available_height() - 34.)
+                    .max_width(ui.available_width())
+                    .show(ui, |ui| {
+                        ui.label(egui::RichText::new("CA Certificates").size(14.));
+                        ui.label("Trusted as well as the system's, e.g. for private CAs.");
+
+                        let mut deleted = None;
+                        for (row, file_path) in tls.root_certificates.iter().enumerate() {
+                            ui.group(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        egui::Label::new(file_path.display().to_string())
+                                            .truncate(),
+                                    );
+
+                                    ui.with_layout(
+                                        egui::Layout::right_to_left(egui::Align::Center),
+                                        |ui| {
+                                            if remove_button(ui, "Remove CA Certificate") {
+                                                deleted = Some(row);
+                                            }
+                                        },
+                                    );
+                                });
+                            });
+                        }
+
+                        if let Some(row) = deleted {
+                            tls.root_certificates.remove(row);
+                        }
+
+                        let upload_icon = egui::include_image!("../../assets/upload.svg");
+                        if ui
+                            .add(default_button(
+                                Some(upload_icon),
+                                "Add a CA Certificate.",
+                                ui.available_width(),
+                                ui.visuals().text_color(),
+                            ))
+                            .clicked()
+                            && let Some(file) = rfd::FileDialog::new()
+                                .add_filter("Certificates", &["pem", "crt", "cer", "der"])
+                                .set_directory("/")
+                                .pick_file()
+                        {
+                            tls.root_certificates.push(file);
+                        }
+
+                        ui.add_space(4.);
+                        ui.label(egui::RichText::new("Address Overrides").size(14.));
+                        ui.label(
+                            "Connect to a host at another IP address, still sending the host as \
+                             the server name, e.g. api.example.com at 10.0.0.5. The port is only \
+                             used when the URL doesn't have one.",
+                        );
+
+                        let mut deleted = None;
+                        for (row, (host, address)) in tls.connect_to.iter_mut().enumerate() {
+                            ui.push_id(row, |ui| {
+                                ui.group(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(host)
+                                                .hint_text("Host")
+                                                .margin(5.)
+                                                .vertical_align(egui::Align::Center)
+                                                .desired_width(ui.available_width() / 2. - 30.),
+                                        );
+
+                                        ui.add(
+                                            egui::TextEdit::singleline(address)
+                                                .hint_text("IP Address, optional Port")
+                                                .margin(5.)
+                                                .vertical_align(egui::Align::Center)
+                                                .desired_width(ui.available_width() - 40.),
+                                        );
+
+                                        if remove_button(ui, "Remove Override") {
+                                            deleted = Some(row);
+                                        }
+                                    });
+                                });
+                            });
+                        }
+
+                        if let Some(row) = deleted {
+                            tls.connect_to.remove(row);
+                        }
+
+                        let add_icon = egui::include_image!("../../assets/plus.svg");
+                        if ui
+                            .add(default_button(
+                                Some(add_icon),
+                                "New Override",
+                                ui.available_width(),
+                                ui.visuals().text_color(),
+                            ))
+                            .clicked()
+                        {
+                            tls.connect_to.push((String::new(), String::new()));
+                        }
+                    });
+
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
+                    if ui
+                        .add(default_button(
+                            None,
+                            "Done!",
+                            ui.available_width(),
+                            ui.visuals().text_color(),
+                        ))
+                        .clicked()
+                    {
+                        app.tls_editor_open = false;
+                    }
+                });
+            });
+
+            if context.input(|i| i.viewport().close_requested()) {
+                app.tls_editor_open = false;
+            }
+        },
+    );
+}
+
+/// A bin button, returning whether it was clicked
+pub(crate) fn remove_button(ui: &mut egui::Ui, text: &str) -> bool {
+    let bin_icon = egui::include_image!("../../assets/trash.svg");
+    ui.add(
+        egui::Button::image(egui::Image::new(bin_icon).fit_to_exact_size(egui::vec2(16., 16.)))
+            .image_tint_follows_text_color(true),
+    )
+    .on_hover_text(text)
+    .clicked()
+}
@@ -0,0 +1,75 @@
+use eframe::egui;
+
+use crate::{
+    ui::widgets::notification::{Notification, NotificationKind},
+    utils::reqwestur::Reqwestur,
+};
+
+/// The modal to unlock, create or lock the secrets vault
+pub fn panel(app: &mut Reqwestur, ui: &mut egui::Ui) {
+    egui::Modal::new("VaultModal".into()).show(ui.ctx(), |ui| {
+        ui.set_min_width(300.);
+
+        ui.heading("Secrets Vault");
+
+        if app.vault.is_unlocked() {
+            ui.label("The vault is unlocked, secrets are filled into requests.");
+
+            ui.horizontal(|ui| {
+                if ui.button("Lock").clicked() {
+                    app.lock_vault();
+                    app.vault_modal_open = false;
+                }
+
+                if ui.button("Close").clicked() {
+                    app.vault_modal_open = false;
+                }
+            });
+            return;
+        }
+
+        let (message, action) = if app.vault.exists() {
+            ("Enter the master password to unlock your secrets.", "Unlock")
+        } else {
+            (
+                "Choose a master password to encrypt your secrets, it cannot be recovered if forgotten.",
+                "Create Vault",
+            )
+        };
+        ui.label(message);
+
+        let password = ui.add(
+            egui::TextEdit::singleline(&mut app.vault_password)
+                .hint_text("Master Password")
+                .password(true)
+                .margin(5.)
+                .desired_width(ui.available_width()),
+        );
+        let submitted =
+            password.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+
+        app.vault.notification.display(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button(action).clicked() || submitted {
+                let password = std::mem::take(&mut app.vault_password);
+                match app.unlock_vault(&password) {
+                    Ok(()) => {
+                        app.vault.notification.clear();
+                        app.notification =
+                            Notification::new("Vault unlocked.", NotificationKind::INFO);
+                        app.vault_modal_open = false;
+                    }
+                    Err(error) => {
+                        app.vault.notification = Notification::new(error, NotificationKind::ERROR);
+                    }
+                }
+            }
+
+            if ui.button("Close").clicked() {
+                app.vault_password.clear();
+                app.vault_modal_open = false;
+            }
+        });
+    });
+}
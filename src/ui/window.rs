@@ -2,8 +2,11 @@ use eframe::egui;
 
 use crate::{
     ui::widgets::{
-        about, certificates, headers, help, history, home, menu, payload, request, response,
-        saved_requests, task_bar::task_bar,
+        about, auth, certificates, cookies, curl, environments, headers, help, history, home, menu,
+        notification::{Notification, NotificationKind},
+        payload, proxy, query, request, response, saved_requests,
+        task_bar::task_bar,
+        tls, vault,
     },
     utils::{
         request::Request,
@@ -11,8 +14,8 @@ use crate::{
     },
 };
 
-pub const PRIMARY: &'static str = "#1b3c79";
-pub const _SECONDARY: &'static str = "#112e65";
+pub const PRIMARY: &str = "#1b3c79";
+pub const _SECONDARY: &str = "#112e65";
 
 /// Main Window controller of the UI
 pub fn window(app: &mut Reqwestur, ui: &mut egui::Ui, shortcuts: AppShortcuts) {
@@ -37,13 +40,18 @@ pub fn window(app: &mut Reqwestur, ui: &mut egui::Ui, shortcuts: AppShortcuts) {
     // A banner to track incoming notifications
     app.notification.banner(ui);
 
+    // Accepting invalid certificates is only meant for testing, so it can't be dismissed
+    if let Some(warning) = app.tls_warning() {
+        Notification::new(warning, NotificationKind::WARN).fixed_banner(ui, "tls_warning_banner");
+    }
+
     match app.view {
         AppView::Main => {
             ui.add(home::panel(app));
         }
         AppView::Request => {
             ui.add(request::panel(app, &mut request));
-            ui.add(response::panel(&mut request));
+            ui.add(response::panel(app, &mut request));
         }
         AppView::Saved => {
             ui.add(saved_requests::panel(app, &mut request));
@@ -61,6 +69,14 @@ pub fn window(app: &mut Reqwestur, ui: &mut egui::Ui, shortcuts: AppShortcuts) {
         headers::editor(app, &mut request, ui);
     }
 
+    if app.query_editor_open {
+        query::editor(app, &mut request, ui);
+    }
+
+    if app.auth_editor_open {
+        auth::editor(app, &mut request, ui);
+    }
+
     if app.payload_editor_open {
         payload::editor(app, &mut request, ui);
     }
@@ -69,6 +85,26 @@ pub fn window(app: &mut Reqwestur, ui: &mut egui::Ui, shortcuts: AppShortcuts) {
         certificates::editor(app, ui)
     }
 
+    if app.tls_editor_open {
+        tls::editor(app, ui)
+    }
+
+    if app.proxy_editor_open {
+        proxy::editor(app, &mut request, ui);
+    }
+
+    if app.curl_editor_open {
+        curl::editor(app, &mut request, ui);
+    }
+
+    if app.environment_editor_open {
+        environments::editor(app, ui);
+    }
+
+    if app.cookie_editor_open {
+        cookies::editor(app, ui);
+    }
+
     if app.about_modal_open {
         about::panel(app, ui);
     }
@@ -76,6 +112,10 @@ pub fn window(app: &mut Reqwestur, ui: &mut egui::Ui, shortcuts: AppShortcuts) {
     if app.help_modal_open {
         help::panel(app, ui);
     }
+
+    if app.vault_modal_open {
+        vault::panel(app, ui);
+    }
 }
 
 /// Register the keyboard shortcuts
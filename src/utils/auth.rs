@@ -0,0 +1,404 @@
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use md5::Digest;
+
+use crate::utils::{
+    environments,
+    oauth2::{GrantType, OAuth2},
+    sigv4::{self, SigV4},
+};
+
+/// The characters escaped in an API key query parameter, `{{variable}}` placeholders are kept as they are
+const QUERY: &percent_encoding::AsciiSet = &percent_encoding::NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~')
+    .remove(b'{')
+    .remove(b'}');
+
+/// The ways a request can authenticate
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub enum AuthKind {
+    #[default]
+    NONE,
+    BASIC,
+    BEARER,
+    APIKEY,
+    DIGEST,
+    OAUTH2,
+    AWSSIGV4,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for AuthKind {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::NONE => "No Auth",
+            Self::BASIC => "Basic",
+            Self::BEARER => "Bearer Token",
+            Self::APIKEY => "API Key",
+            Self::DIGEST => "Digest",
+            Self::OAUTH2 => "OAuth 2.0",
+            Self::AWSSIGV4 => "AWS Signature",
+        };
+
+        str.to_string()
+    }
+}
+
+impl AuthKind {
+    const OPTIONS: [Self; 7] = [
+        Self::NONE,
+        Self::BASIC,
+        Self::BEARER,
+        Self::APIKEY,
+        Self::DIGEST,
+        Self::OAUTH2,
+        Self::AWSSIGV4,
+    ];
+
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+}
+
+/// Where an API key is sent
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub enum ApiKeyLocation {
+    #[default]
+    HEADER,
+    QUERY,
+}
+
+impl ToString for ApiKeyLocation {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::HEADER => "Header",
+            Self::QUERY => "Query Parameter",
+        };
+
+        str.to_string()
+    }
+}
+
+impl ApiKeyLocation {
+    const OPTIONS: [Self; 2] = [Self::HEADER, Self::QUERY];
+
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+}
+
+/// The credentials a request authenticates with, only those used by its kind are sent
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Auth {
+    pub kind: AuthKind,
+
+    /// Used by Basic and Digest authentication, and the OAuth 2.0 password grant
+    pub username: String,
+    pub password: String,
+
+    /// Sent as `Authorization: Bearer <token>`, OAuth 2.0 tokens are filled in when the request is sent
+    pub token: String,
+
+    /// The name of the header or query parameter carrying the API key
+    pub key_name: String,
+    pub key_value: String,
+    pub key_location: ApiKeyLocation,
+
+    /// How OAuth 2.0 access tokens are fetched
+    pub oauth2: OAuth2,
+
+    /// The credentials AWS Signature Version 4 signs with
+    pub sigv4: SigV4,
+}
+
+impl Auth {
+    /// The credentials sent by the kind of authentication, which may contain `{{variable}}` placeholders
+    pub fn fields(&self) -> Vec<&String> {
+        match self.kind {
+            AuthKind::NONE => vec![],
+            AuthKind::BASIC | AuthKind::DIGEST => vec![&self.username, &self.password],
+            AuthKind::BEARER => vec![&self.token],
+            AuthKind::APIKEY => vec![&self.key_name, &self.key_value],
+            AuthKind::OAUTH2 => {
+                let oauth2 = &self.oauth2;
+                let mut fields = vec![
+                    &oauth2.token_url,
+                    &oauth2.client_id,
+                    &oauth2.client_secret,
+                    &oauth2.scope,
+                ];
+                match oauth2.grant {
+                    GrantType::CLIENTCREDENTIALS => {}
+                    GrantType::PASSWORD => fields.extend([&self.username, &self.password]),
+                    GrantType::REFRESHTOKEN => fields.push(&oauth2.refresh_token),
+                    GrantType::AUTHORIZATIONCODE => fields.push(&oauth2.authorization_url),
+                }
+                fields
+            }
+            AuthKind::AWSSIGV4 => vec![
+                &self.sigv4.access_key,
+                &self.sigv4.secret_key,
+                &self.sigv4.session_token,
+                &self.sigv4.region,
+                &self.sigv4.service,
+            ],
+        }
+    }
+
+    /// Every credential, including those not sent by the kind of authentication
+    pub fn fields_mut(&mut self) -> Vec<&mut String> {
+        vec![
+            &mut self.username,
+            &mut self.password,
+            &mut self.token,
+            &mut self.key_name,
+            &mut self.key_value,
+            &mut self.oauth2.token_url,
+            &mut self.oauth2.authorization_url,
+            &mut self.oauth2.client_id,
+            &mut self.oauth2.client_secret,
+            &mut self.oauth2.scope,
+            &mut self.oauth2.refresh_token,
+            &mut self.sigv4.access_key,
+            &mut self.sigv4.secret_key,
+            &mut self.sigv4.session_token,
+            &mut self.sigv4.region,
+            &mut self.sigv4.service,
+        ]
+    }
+
+    /// The headers sent by Basic, Bearer and API key authentication, used by exports and generated code
+    ///
+    /// Digest and AWS signatures depend on the server's challenge or the time of sending, and OAuth 2.0
+    /// tokens are only known once one has been fetched. Basic credentials containing placeholders can't be
+    /// encoded until they're filled in, so they're written as a `{{basicAuth}}` placeholder instead.
+    pub fn headers(&self) -> Vec<(String, String)> {
+        let authorization = match self.kind {
+            AuthKind::BASIC
+                if [&self.username, &self.password]
+                    .iter()
+                    .any(|field| !environments::placeholders(field).is_empty()) =>
+            {
+                "Basic {{basicAuth}}".to_string()
+            }
+            AuthKind::BASIC => format!(
+                "Basic {}",
+                STANDARD.encode(format!("{}:{}", self.username, self.password))
+            ),
+            AuthKind::BEARER => format!("Bearer {}", self.token),
+            AuthKind::OAUTH2 if !self.token.is_empty() => format!("Bearer {}", self.token),
+            AuthKind::APIKEY
+                if self.key_location == ApiKeyLocation::HEADER && !self.key_name.is_empty() =>
+            {
+                return vec![(self.key_name.clone(), self.key_value.clone())];
+            }
+            _ => return Vec::new(),
+        };
+
+        vec![("Authorization".to_string(), authorization)]
+    }
+
+    /// The query parameter an API key sent in the URL is added as
+    pub fn query(&self) -> Option<(String, String)> {
+        (self.kind == AuthKind::APIKEY
+            && self.key_location == ApiKeyLocation::QUERY
+            && !self.key_name.is_empty())
+        .then(|| (self.key_name.clone(), self.key_value.clone()))
+    }
+
+    /// The URI with the API key's query parameter added, if the key is sent in the URL
+    pub fn uri(&self, uri: &str) -> String {
+        let Some((name, value)) = self.query() else {
+            return uri.to_string();
+        };
+
+        let separator = if uri.contains('?') { '&' } else { '?' };
+        format!(
+            "{uri}{separator}{}={}",
+            percent_encoding::utf8_percent_encode(&name, QUERY),
+            percent_encoding::utf8_percent_encode(&value, QUERY)
+        )
+    }
+
+    /// The credentials with the secrets typed in, rather than filled in from a variable, replaced by placeholders
+    ///
+    /// Used before requests are exported, OAuth 2.0 tokens are dropped as they're fetched again when sent.
+    pub fn masked(&self) -> Self {
+        let mask = |value: &str, name: &str| {
+            if value.is_empty() || !environments::placeholders(value).is_empty() {
+                value.to_string()
+            } else {
+                format!("{{{{{name}}}}}")
+            }
+        };
+
+        let mut masked = self.clone();
+        masked.password = mask(&self.password, "password");
+        masked.token = match self.kind {
+            AuthKind::OAUTH2 => String::new(),
+            _ => mask(&self.token, "token"),
+        };
+        masked.key_value = mask(&self.key_value, "apiKey");
+        masked.oauth2.client_secret = mask(&self.oauth2.client_secret, "clientSecret");
+        masked.oauth2.refresh_token = mask(&self.oauth2.refresh_token, "refreshToken");
+        masked.sigv4.secret_key = mask(&self.sigv4.secret_key, "secretKey");
+        masked.sigv4.session_token = mask(&self.sigv4.session_token, "sessionToken");
+        masked
+    }
+
+    /// Add the credentials to the request, replacing any `Authorization` header already set
+    ///
+    /// Digest credentials are only sent once the server has challenged the request, see [`digest`].
+    /// AWS signatures cover the rest of the request, so this must be the last change made to it.
+    pub fn apply(&self, request: &mut reqwest::Request) -> Result<(), String> {
+        match self.kind {
+            AuthKind::NONE | AuthKind::DIGEST => return Ok(()),
+            AuthKind::AWSSIGV4 => return sigv4::sign(&self.sigv4, request, chrono::Utc::now()),
+            AuthKind::APIKEY if self.key_name.is_empty() => {
+                return Err("The API key needs a name.".to_string());
+            }
+            _ => {}
+        }
+
+        if let Some((name, value)) = self.query() {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .append_pair(&name, &value);
+        }
+
+        for (name, value) in self.headers() {
+            header(request, &name, &value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Set the header on the request, replacing any existing value
+fn header(request: &mut reqwest::Request, name: &str, value: &str) -> Result<(), String> {
+    let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map_err(|error| format!("{name}: {error}"))?;
+    let value = reqwest::header::HeaderValue::from_str(value)
+        .map_err(|error| format!("{name}: {error}"))?;
+
+    request.headers_mut().insert(name, value);
+    Ok(())
+}
+
+/// A random client nonce for the Digest handshake
+pub fn cnonce() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The Digest challenge in a `WWW-Authenticate` header value, if it has one
+pub fn digest_challenge(header: &str) -> Option<Vec<(String, String)>> {
+    let start = header.to_ascii_lowercase().find("digest ")?;
+    Some(parameters(&header[start + "digest ".len()..]))
+}
+
+/// Answer a Digest challenge (RFC 7616), returning the `Authorization` header value
+///
+/// The method and uri are those of the request being retried, the uri being its path and query.
+pub fn digest(
+    auth: &Auth,
+    challenge: &[(String, String)],
+    method: &str,
+    uri: &str,
+    cnonce: &str,
+) -> Result<String, String> {
+    let parameter = |name: &str| {
+        challenge
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+
+    let realm = parameter("realm").unwrap_or_default();
+    let nonce = parameter("nonce").ok_or("The Digest challenge has no nonce.")?;
+    let algorithm = parameter("algorithm").unwrap_or("MD5");
+
+    let hash: fn(&str) -> String = match algorithm.to_uppercase().trim_end_matches("-SESS") {
+        "MD5" => |text| hex(&md5::Md5::digest(text)),
+        "SHA-256" => |text| hex(&sha2::Sha256::digest(text)),
+        _ => return Err(format!("The Digest algorithm {algorithm} isn't supported.")),
+    };
+
+    // Only `auth` protection is supported, servers offering `auth-int` alone are refused
+    let qop = match parameter("qop") {
+        Some(offered) if offered.split(',').any(|qop| qop.trim() == "auth") => Some("auth"),
+        Some(offered) => return Err(format!("The Digest protection {offered} isn't supported.")),
+        None => None,
+    };
+
+    let mut ha1 = hash(&format!("{}:{realm}:{}", auth.username, auth.password));
+    if algorithm.to_uppercase().ends_with("-SESS") {
+        ha1 = hash(&format!("{ha1}:{nonce}:{cnonce}"));
+    }
+    let ha2 = hash(&format!("{method}:{uri}"));
+
+    // The handshake is repeated for each request, so the nonce is only ever used once
+    let nc = "00000001";
+    let response = match qop {
+        Some(qop) => hash(&format!("{ha1}:{nonce}:{nc}:{cnonce}:{qop}:{ha2}")),
+        None => hash(&format!("{ha1}:{nonce}:{ha2}")),
+    };
+
+    let mut authorization = format!(
+        "Digest username=\"{}\", realm=\"{realm}\", nonce=\"{nonce}\", uri=\"{uri}\", algorithm={algorithm}, response=\"{response}\"",
+        auth.username
+    );
+    if let Some(qop) = qop {
+        authorization.push_str(&format!(", qop={qop}, nc={nc}, cnonce=\"{cnonce}\""));
+    }
+    if let Some(opaque) = parameter("opaque") {
+        authorization.push_str(&format!(", opaque=\"{opaque}\""));
+    }
+
+    Ok(authorization)
+}
+
+/// Split the comma separated `name=value` parameters of a challenge, values may be quoted
+fn parameters(text: &str) -> Vec<(String, String)> {
+    let mut parameters = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    loop {
+        let name: String = chars
+            .by_ref()
+            .skip_while(|char| *char == ',' || char.is_whitespace())
+            .take_while(|char| *char != '=')
+            .collect();
+        if name.is_empty() {
+            break;
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(char) = chars.next() {
+                match char {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    _ => value.push(char),
+                }
+            }
+        } else {
+            value = chars.by_ref().take_while(|char| *char != ',').collect();
+        }
+
+        parameters.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    parameters
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
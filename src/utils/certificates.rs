@@ -1,4 +1,22 @@
-use crate::ui::widgets::notification::Notification;
+use pkcs8::{
+    EncryptedPrivateKeyInfo, ObjectIdentifier, PrivateKeyInfo,
+    der::{
+        Decode, Encode, Reader, TagNumber,
+        asn1::{AnyRef, ContextSpecific, OctetStringRef},
+        pem::{self, LineEnding},
+    },
+    spki::AlgorithmIdentifierRef,
+};
+use sha2::Digest;
+use x509_cert::ext::pkix::{SubjectAltName, name::GeneralName};
+
+use crate::{ui::widgets::notification::Notification, utils::secrets::Secret};
+
+/// The OID of RSA keys, used to convert PKCS#1 keys to PKCS#8
+const RSA_ENCRYPTION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+
+/// The OID of elliptic curve keys, used to convert SEC1 keys to PKCS#8
+const EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
 
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone, Eq, PartialEq)]
 pub enum CertificateStatus {
@@ -13,6 +31,102 @@ pub enum CertificateStatus {
     ERROR,
 }
 
+/// How the certificate and its private key are stored
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, Eq, PartialEq)]
+pub enum CertificateFormat {
+    /// A PKCS#12 archive holding the certificate, its chain and the private key
+    #[default]
+    PKCS12,
+
+    /// PEM files, either separate certificate, key and chain files or one combined file
+    PEM,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for CertificateFormat {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::PKCS12 => "PKCS#12 (.pfx, .p12)",
+            Self::PEM => "PEM (.pem, .crt, .key)",
+        };
+
+        str.to_string()
+    }
+}
+
+impl CertificateFormat {
+    const OPTIONS: [Self; 2] = [Self::PKCS12, Self::PEM];
+
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+}
+
+/// What a certificate says about itself, shown so the right certificate can be picked
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct CertificateDetails {
+    pub subject: String,
+    pub issuer: String,
+
+    /// The names the certificate is valid for, e.g. `DNS:client.example.com`
+    pub subject_alt_names: Vec<String>,
+
+    /// When the certificate is valid from and until, as Unix timestamps
+    pub not_before: i64,
+    pub not_after: i64,
+
+    /// The SHA-256 hash of the DER encoded certificate, e.g. `AB:CD:...`
+    pub fingerprint: String,
+}
+
+impl CertificateDetails {
+    /// Read the details of a DER encoded certificate
+    pub fn from_der(der: &[u8]) -> Result<Self, String> {
+        let certificate = x509_cert::Certificate::from_der(der)
+            .map_err(|error| format!("The certificate can't be read - {error}"))?;
+        let tbs = &certificate.tbs_certificate;
+
+        let subject_alt_names = match tbs.get::<SubjectAltName>() {
+            Ok(Some((_, names))) => names.0.iter().map(general_name).collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            subject: tbs.subject.to_string(),
+            issuer: tbs.issuer.to_string(),
+            subject_alt_names,
+            not_before: tbs.validity.not_before.to_unix_duration().as_secs() as i64,
+            not_after: tbs.validity.not_after.to_unix_duration().as_secs() as i64,
+            fingerprint: sha2::Sha256::digest(der)
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<String>>()
+                .join(":"),
+        })
+    }
+
+    /// Whether the certificate is outside of its validity period
+    pub fn is_expired(&self) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        now < self.not_before || now > self.not_after
+    }
+
+    /// When the certificate expires, for display
+    pub fn expiry(&self) -> String {
+        chrono::DateTime::from_timestamp(self.not_after, 0)
+            .map(|expires| expires.format("%d/%m/%Y %H:%M UTC").to_string())
+            .unwrap_or("Unknown".to_string())
+    }
+
+    /// When the certificate is valid from, for display
+    pub fn valid_from(&self) -> String {
+        chrono::DateTime::from_timestamp(self.not_before, 0)
+            .map(|valid_from| valid_from.format("%d/%m/%Y %H:%M UTC").to_string())
+            .unwrap_or("Unknown".to_string())
+    }
+}
+
 /// A struct to contain all certificate related information
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone)]
 #[serde(default)]
@@ -20,11 +134,31 @@ pub struct Certificate {
     /// Whether Certificates are required, used to suggest whether certificates are loaded or not.
     // pub required: bool,
 
-    /// The file path to the selected certificate.
+    /// The name requests select the certificate by.
+    pub name: String,
+
+    /// The hosts the certificate is sent to when a request doesn't select one, e.g. `*.example.com`.
+    pub hosts: Vec<String>,
+
+    /// How the certificate is stored.
+    pub format: CertificateFormat,
+
+    /// The file path to the selected certificate, a PKCS#12 archive or PEM certificate.
     pub file_path: std::path::PathBuf,
 
-    /// The certificate passphrase.
-    pub passphrase: String,
+    /// The PEM private key, if it isn't in the certificate's file.
+    pub key_path: Option<std::path::PathBuf>,
+
+    /// The PEM intermediate certificates, if they aren't in the certificate's file.
+    pub chain_path: Option<std::path::PathBuf>,
+
+    /// The certificate passphrase, or the PEM private key's, kept in the vault.
+    #[serde(rename = "passphrase_secret")]
+    pub passphrase: Secret,
+
+    /// The passphrase saved in plain text before the vault, moved into it once the vault is unlocked.
+    #[serde(rename = "passphrase", skip_serializing_if = "String::is_empty")]
+    pub legacy_passphrase: String,
 
     /// The status of the loaded certificate.
     pub status: CertificateStatus,
@@ -35,19 +169,256 @@ pub struct Certificate {
     /// The identity loaded from the certificates.
     #[serde(skip)]
     pub identity: Option<reqwest::Identity>,
+
+    /// The details of the loaded certificate followed by its chain, read from PEM certificates.
+    #[serde(skip)]
+    pub details: Vec<CertificateDetails>,
 }
 
 impl Certificate {
+    /// Create an empty certificate with a name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the certificate is sent to the host, `*.` patterns match subdomains and `*` matches every host
+    pub fn matches(&self, host: &str) -> bool {
+        let host = host.to_ascii_lowercase();
+        self.hosts.iter().any(|pattern| {
+            let pattern = pattern.trim().to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(domain) => host.ends_with(&format!(".{domain}")),
+                None => pattern == "*" || pattern == host,
+            }
+        })
+    }
+
+    /// Select other files, keeping the name and hosts but clearing the previous files' passphrase and status
+    pub fn reset(&mut self, format: CertificateFormat, file_path: std::path::PathBuf) {
+        *self = Self {
+            name: std::mem::take(&mut self.name),
+            hosts: std::mem::take(&mut self.hosts),
+            format,
+            file_path,
+            ..Default::default()
+        };
+    }
+
+    /// Whether the files, and the passphrase a PKCS#12 archive needs, have been given
+    pub fn is_ready(&self) -> bool {
+        self.file_path.exists()
+            && match self.format {
+                CertificateFormat::PKCS12 => !self.passphrase.value.is_empty(),
+                CertificateFormat::PEM => true,
+            }
+    }
+
     /// Import the certificate based on the file path and passphrase
     pub fn import(&self) -> Result<reqwest::Identity, String> {
-        match std::fs::read(&self.file_path) {
-            Ok(file_bytes) => {
-                match reqwest::Identity::from_pkcs12_der(&file_bytes, &self.passphrase) {
-                    Ok(identity) => Ok(identity),
-                    Err(error) => Err(error.to_string()),
+        match self.format {
+            CertificateFormat::PKCS12 => {
+                let file_bytes =
+                    std::fs::read(&self.file_path).map_err(|error| error.to_string())?;
+                reqwest::Identity::from_pkcs12_der(&file_bytes, &self.passphrase.value)
+                    .map_err(|error| error.to_string())
+            }
+            CertificateFormat::PEM => {
+                let (certificates, key) = self.pem()?;
+
+                let mut chain = String::new();
+                for certificate in &certificates {
+                    chain.push_str(
+                        &pem::encode_string("CERTIFICATE", LineEnding::LF, certificate)
+                            .map_err(|error| error.to_string())?,
+                    );
                 }
+
+                reqwest::Identity::from_pkcs8_pem(chain.as_bytes(), key.as_bytes())
+                    .map_err(|error| format!("The certificate and key can't be used - {error}"))
             }
-            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    /// The details of the certificate and its chain, only PEM certificates can be read
+    pub fn inspect(&self) -> Result<Vec<CertificateDetails>, String> {
+        match self.format {
+            CertificateFormat::PKCS12 => Ok(Vec::new()),
+            CertificateFormat::PEM => self
+                .pem()?
+                .0
+                .iter()
+                .map(|certificate| CertificateDetails::from_der(certificate))
+                .collect(),
+        }
+    }
+
+    /// The DER encoded certificates, the certificate first, and the PKCS#8 PEM private key
+    fn pem(&self) -> Result<(Vec<Vec<u8>>, String), String> {
+        let file = read_pem(&self.file_path)?;
+        let chain = match &self.chain_path {
+            Some(chain_path) => read_pem(chain_path)?,
+            None => Vec::new(),
+        };
+        let keys = match &self.key_path {
+            Some(key_path) => read_pem(key_path)?,
+            None => file.clone(),
+        };
+
+        let certificates: Vec<Vec<u8>> = file
+            .into_iter()
+            .chain(chain)
+            .filter(|(label, _)| label == "CERTIFICATE")
+            .map(|(_, der)| der)
+            .collect();
+        if certificates.is_empty() {
+            return Err(format!(
+                "No certificates were found in {}.",
+                self.file_path.display()
+            ));
+        }
+
+        let (label, der) = keys
+            .into_iter()
+            .find(|(label, _)| label.ends_with("PRIVATE KEY"))
+            .ok_or("No private key was found, select the key file.")?;
+
+        Ok((
+            certificates,
+            private_key(&label, &der, &self.passphrase.value)?,
+        ))
+    }
+}
+
+/// The label and DER contents of each PEM block in the file
+fn read_pem(file_path: &std::path::Path) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let contents = std::fs::read_to_string(file_path)
+        .map_err(|error| format!("Could not read {} - {error}", file_path.display()))?;
+
+    let mut blocks = Vec::new();
+    let mut rest = contents.as_str();
+    while let Some(start) = rest.find("-----BEGIN ") {
+        let label_start = start + "-----BEGIN ".len();
+        let label = rest[label_start..]
+            .split_once("-----")
+            .map(|(label, _)| label)
+            .ok_or(format!("{} isn't a PEM file.", file_path.display()))?;
+
+        let end_marker = format!("-----END {label}-----");
+        let end = rest[start..]
+            .find(&end_marker)
+            .map(|end| start + end + end_marker.len())
+            .ok_or(format!(
+                "The {label} in {} isn't complete.",
+                file_path.display()
+            ))?;
+        let block = &rest[start..end];
+
+        if block.contains("Proc-Type:") {
+            return Err(
+                "Keys encrypted by older versions of OpenSSL can't be read, convert the key with `openssl pkcs8 -topk8`."
+                    .to_string(),
+            );
+        }
+
+        let (_, der) = pem::decode_vec(block.as_bytes()).map_err(|error| {
+            format!(
+                "The {label} in {} can't be read - {error}",
+                file_path.display()
+            )
+        })?;
+        blocks.push((label.to_string(), der));
+        rest = &rest[end..];
+    }
+
+    Ok(blocks)
+}
+
+/// Convert the private key to an unencrypted PKCS#8 PEM key, decrypting it with the passphrase
+fn private_key(label: &str, der: &[u8], passphrase: &str) -> Result<String, String> {
+    let pkcs8 = match label {
+        "PRIVATE KEY" => der.to_vec(),
+        "ENCRYPTED PRIVATE KEY" => {
+            if passphrase.is_empty() {
+                return Err("The private key is encrypted, enter its passphrase.".to_string());
+            }
+
+            EncryptedPrivateKeyInfo::try_from(der)
+                .map_err(|error| format!("The encrypted private key can't be read - {error}"))?
+                .decrypt(passphrase)
+                .map_err(|_| "The passphrase doesn't decrypt the private key.".to_string())?
+                .as_bytes()
+                .to_vec()
+        }
+        // PKCS#1, as written by `openssl genrsa`
+        "RSA PRIVATE KEY" => PrivateKeyInfo::new(
+            AlgorithmIdentifierRef {
+                oid: RSA_ENCRYPTION,
+                parameters: Some(AnyRef::NULL),
+            },
+            der,
+        )
+        .to_der()
+        .map_err(|error| format!("The RSA private key can't be read - {error}"))?,
+        // SEC1, as written by `openssl ecparam -genkey`
+        "EC PRIVATE KEY" => {
+            let curve = ec_curve(der)
+                .map_err(|error| format!("The EC private key can't be read - {error}"))?
+                .ok_or("The EC private key doesn't name its curve, convert it with openssl pkcs8 -topk8.")?;
+
+            PrivateKeyInfo::new(
+                AlgorithmIdentifierRef {
+                    oid: EC_PUBLIC_KEY,
+                    parameters: Some(AnyRef::from(&curve)),
+                },
+                der,
+            )
+            .to_der()
+            .map_err(|error| format!("The EC private key can't be read - {error}"))?
+        }
+        label => {
+            return Err(format!(
+                "The {label} can't be read, convert it to PKCS#8 with openssl pkcs8 -topk8."
+            ));
+        }
+    };
+
+    pem::encode_string("PRIVATE KEY", LineEnding::LF, &pkcs8).map_err(|error| error.to_string())
+}
+
+/// The named curve of a SEC1 EC private key, read from its optional parameters
+fn ec_curve(der: &[u8]) -> Result<Option<ObjectIdentifier>, pkcs8::der::Error> {
+    AnyRef::from_der(der)?.sequence(|reader| {
+        let _version = u8::decode(reader)?;
+        let _key = OctetStringRef::decode(reader)?;
+        let curve = ContextSpecific::<ObjectIdentifier>::decode_explicit(reader, TagNumber::N0)?;
+
+        // The public key which may follow isn't needed
+        reader.read_slice(reader.remaining_len())?;
+
+        Ok(curve.map(|curve| curve.value))
+    })
+}
+
+/// Describe a subject alternative name as OpenSSL does, e.g. `DNS:example.com`
+fn general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DnsName(dns) => format!("DNS:{}", dns.as_str()),
+        GeneralName::Rfc822Name(email) => format!("email:{}", email.as_str()),
+        GeneralName::UniformResourceIdentifier(uri) => format!("URI:{}", uri.as_str()),
+        GeneralName::IpAddress(ip) => match ip.as_bytes() {
+            [a, b, c, d] => format!("IP:{}", std::net::Ipv4Addr::new(*a, *b, *c, *d)),
+            bytes => match <[u8; 16]>::try_from(bytes) {
+                Ok(bytes) => format!("IP:{}", std::net::Ipv6Addr::from(bytes)),
+                Err(_) => "IP:<invalid>".to_string(),
+            },
+        },
+        GeneralName::DirectoryName(name) => format!("DirName:{name}"),
+        GeneralName::RegisteredId(oid) => format!("Registered ID:{oid}"),
+        GeneralName::OtherName(_) | GeneralName::EdiPartyName(_) => {
+            "othername:<unsupported>".to_string()
         }
     }
 }
@@ -0,0 +1,442 @@
+//! What's known about the connection a response came over, and why a connection failed
+
+use std::{sync::Arc, time::Duration};
+
+use rustls::{
+    DigitallySignedStruct, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::CryptoProvider,
+    pki_types::{CertificateDer, ServerName, UnixTime},
+};
+use tokio::io::AsyncWriteExt;
+
+use crate::utils::{certificates::CertificateDetails, tls::TlsSettings};
+
+/// How long connecting and the TLS handshake are given when the chain is read from the server
+const INSPECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a connection couldn't be made, classified from the client's error
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+pub enum ConnectionFailure {
+    /// The host name couldn't be resolved
+    DNS,
+
+    /// Nothing is listening on the port
+    REFUSED,
+
+    /// The server closed the connection part way through
+    RESET,
+
+    /// The certificate isn't issued by a trusted CA
+    UNTRUSTEDCERTIFICATE,
+
+    /// The certificate is signed by itself rather than a CA
+    SELFSIGNEDCERTIFICATE,
+
+    /// The certificate has expired, or isn't valid yet
+    EXPIREDCERTIFICATE,
+
+    /// The certificate is issued for another host
+    HOSTNAMEMISMATCH,
+
+    /// The server asked for a client certificate, or rejected the one sent
+    CLIENTCERTIFICATE,
+
+    /// The server and client have no TLS version or cipher suite in common
+    PROTOCOLVERSION,
+
+    /// The server didn't answer with TLS, e.g. an `http://` server on an `https://` URL
+    NOTTLS,
+
+    /// The TLS handshake failed for another reason
+    HANDSHAKE,
+
+    /// The connection failed for another reason
+    CONNECT,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for ConnectionFailure {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::DNS => "Host Not Found",
+            Self::REFUSED => "Connection Refused",
+            Self::RESET => "Connection Reset",
+            Self::UNTRUSTEDCERTIFICATE => "Untrusted Certificate",
+            Self::SELFSIGNEDCERTIFICATE => "Self-Signed Certificate",
+            Self::EXPIREDCERTIFICATE => "Expired Certificate",
+            Self::HOSTNAMEMISMATCH => "Hostname Mismatch",
+            Self::CLIENTCERTIFICATE => "Client Certificate Rejected",
+            Self::PROTOCOLVERSION => "No Common TLS Version",
+            Self::NOTTLS => "Not a TLS Server",
+            Self::HANDSHAKE => "TLS Handshake Failed",
+            Self::CONNECT => "Connection Failed",
+        };
+
+        str.to_string()
+    }
+}
+
+impl ConnectionFailure {
+    /// The phrases the TLS backends use for each failure, checked in order
+    const PHRASES: [(Self, &[&str]); 11] = [
+        (
+            Self::DNS,
+            &[
+                "dns error",
+                "failed to lookup address",
+                "name or service not known",
+            ],
+        ),
+        (Self::REFUSED, &["connection refused", "actively refused"]),
+        (
+            Self::HOSTNAMEMISMATCH,
+            &[
+                "hostname mismatch",
+                "certificate is not valid for",
+                "does not match",
+            ],
+        ),
+        (
+            Self::EXPIREDCERTIFICATE,
+            &[
+                "certificate has expired",
+                "certificate is not yet valid",
+                "expired",
+            ],
+        ),
+        (
+            Self::SELFSIGNEDCERTIFICATE,
+            &["self-signed certificate", "self signed certificate"],
+        ),
+        (
+            Self::UNTRUSTEDCERTIFICATE,
+            &[
+                "unable to get local issuer certificate",
+                "certificate verify failed",
+                "not trusted",
+                "untrusted root",
+            ],
+        ),
+        (
+            Self::CLIENTCERTIFICATE,
+            &["certificate required", "bad certificate", "unknown ca"],
+        ),
+        (
+            Self::PROTOCOLVERSION,
+            &[
+                "protocol version",
+                "unsupported protocol",
+                "no shared cipher",
+                "handshake failure",
+            ],
+        ),
+        (
+            Self::NOTTLS,
+            &[
+                "wrong version number",
+                "packet length too long",
+                "http request",
+            ],
+        ),
+        (
+            Self::RESET,
+            &["connection reset", "broken pipe", "unexpected eof"],
+        ),
+        (Self::HANDSHAKE, &["ssl routines", "tls", "handshake"]),
+    ];
+
+    /// Classify the client's error, `None` if it isn't a connection failure
+    pub fn classify(error: &reqwest::Error) -> Option<Self> {
+        let message = error_chain(error).to_lowercase();
+
+        Self::PHRASES
+            .into_iter()
+            .find(|(_, phrases)| phrases.iter().any(|phrase| message.contains(phrase)))
+            .map(|(failure, _)| failure)
+            .or(error.is_connect().then_some(Self::CONNECT))
+    }
+
+    /// Whether the failure happened in the TLS handshake, so the server's certificates can be read
+    pub fn is_tls(&self) -> bool {
+        !matches!(
+            self,
+            Self::DNS | Self::REFUSED | Self::RESET | Self::CONNECT
+        )
+    }
+
+    /// What the failure means and how it might be fixed
+    pub fn explanation(&self) -> &'static str {
+        match self {
+            Self::DNS => {
+                "The host name couldn't be found. Check the URL for typos, and that the host can be reached from this network, e.g. it may need a VPN."
+            }
+            Self::REFUSED => {
+                "The host was found, but nothing accepted the connection on the port. Check the port and that the server is running."
+            }
+            Self::RESET => {
+                "The server closed the connection part way through. It may have crashed, or a firewall or proxy may have cut it off."
+            }
+            Self::UNTRUSTEDCERTIFICATE => {
+                "The server's certificate isn't issued by a trusted CA. If it's from a private CA, add the CA certificate in the TLS settings."
+            }
+            Self::SELFSIGNEDCERTIFICATE => {
+                "The server's certificate is signed by itself. Add it as a CA certificate in the TLS settings, or accept invalid certificates in a test environment."
+            }
+            Self::EXPIREDCERTIFICATE => {
+                "The server's certificate has expired or isn't valid yet. Check the certificate's dates below, and this computer's clock."
+            }
+            Self::HOSTNAMEMISMATCH => {
+                "The server's certificate is issued for other host names. Compare its alternative names below with the URL's host."
+            }
+            Self::CLIENTCERTIFICATE => {
+                "The server needs a client certificate, or rejected the one sent. Check the request's certificate in the Certificate Library."
+            }
+            Self::PROTOCOLVERSION => {
+                "The server and client have no TLS version or cipher suite in common. Try lowering the minimum TLS version in the TLS settings."
+            }
+            Self::NOTTLS => {
+                "The server didn't answer with TLS. It may only serve plain HTTP, try an http:// URL or check the port."
+            }
+            Self::HANDSHAKE => "The TLS handshake with the server failed.",
+            Self::CONNECT => "The connection to the server couldn't be made.",
+        }
+    }
+}
+
+/// The server's side of a TLS handshake, read by a separate connection
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct Handshake {
+    /// The negotiated TLS version, e.g. `TLS 1.3`
+    pub version: String,
+
+    /// The application protocol the server chose from `h2` and `http/1.1`, if it chose one
+    pub alpn: Option<String>,
+
+    /// The negotiated cipher suite, e.g. `TLS13_AES_256_GCM_SHA384`
+    pub cipher_suite: String,
+
+    /// The address connected to
+    pub remote_address: String,
+
+    /// The server's certificate followed by the rest of the chain it sent
+    pub chain: Vec<CertificateDetails>,
+}
+
+/// What's known about the connection a response came over
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct Connection {
+    /// The address the response came from
+    pub remote_address: Option<String>,
+
+    /// The HTTP version of the response, e.g. `HTTP/1.1`
+    pub http_version: Option<String>,
+
+    /// The certificate the server presented for the request, if it was sent over TLS
+    pub certificate: Option<CertificateDetails>,
+
+    /// Why the connection failed, if it did
+    pub failure: Option<ConnectionFailure>,
+
+    /// The TLS handshake and certificate chain, read on request or after a TLS failure, or why it couldn't be read
+    pub handshake: Option<Result<Handshake, String>>,
+
+    /// Whether the handshake is being read
+    #[serde(skip)]
+    pub inspecting: bool,
+}
+
+impl Connection {
+    /// Whether the connection was, or was meant to be, encrypted with TLS
+    pub fn is_tls(&self) -> bool {
+        self.certificate.is_some()
+            || self
+                .failure
+                .as_ref()
+                .is_some_and(|failure| failure.is_tls())
+    }
+}
+
+/// The error and each of its sources, as the client's own message leaves out the cause
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut messages = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(error) = source {
+        let message = error.to_string();
+        if !messages.iter().any(|previous| previous.contains(&message)) {
+            messages.push(message);
+        }
+        source = error.source();
+    }
+
+    messages.join(": ")
+}
+
+/// Read the TLS handshake from the server of the URL, accepting any certificate so failing chains can be shown
+///
+/// The client used for requests only reports the server's certificate, so a separate connection is made.
+/// It honours the address overrides and the SNI setting, but not the proxy, minimum version or client certificate,
+/// so the handshake may differ from the request's. Only TLS 1.2 and 1.3 are supported, unlike the request's client.
+pub async fn inspect(url: &reqwest::Url, tls: &TlsSettings) -> Result<Handshake, String> {
+    tokio::time::timeout(INSPECT_TIMEOUT, handshake(url, tls))
+        .await
+        .map_err(|_| "Reading the TLS handshake timed out.".to_string())?
+}
+
+/// Connect to the server of the URL and read its side of the handshake
+async fn handshake(url: &reqwest::Url, tls: &TlsSettings) -> Result<Handshake, String> {
+    let host = url
+        .host_str()
+        .ok_or("The URL doesn't have a host.")?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let mut port = url.port_or_known_default().unwrap_or(443);
+
+    // As when the request is sent, the URL's port wins over the overridden address's
+    let overridden = tls
+        .addresses()?
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(host))
+        .map(|(_, mut address)| {
+            match url.port() {
+                Some(explicit) => address.set_port(explicit),
+                None if address.port() == 0 => address.set_port(port),
+                None => port = address.port(),
+            }
+            address
+        });
+    let connect = async {
+        match overridden {
+            Some(address) => tokio::net::TcpStream::connect(address).await,
+            None => tokio::net::TcpStream::connect((host, port)).await,
+        }
+    };
+    let stream = connect
+        .await
+        .map_err(|error| format!("Could not connect to {host}:{port} - {error}"))?;
+    let remote_address = stream
+        .peer_addr()
+        .map(|address| address.to_string())
+        .unwrap_or_default();
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_protocol_versions(rustls::ALL_VERSIONS)
+        .map_err(|error| error.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AnyCertificate(provider)))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    config.enable_sni = tls.sni;
+
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|error| format!("{host} can't be used as a server name - {error}"))?;
+    let mut stream = tokio_rustls::TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|error| {
+            if is_legacy_version(&error) {
+                "The server only offers TLS 1.1 or older, which can't be read here. The request \
+                 can still use it when the minimum TLS version allows, and the certificate it \
+                 received is shown above."
+                    .to_string()
+            } else {
+                format!("The TLS handshake failed - {}", error_chain(&error))
+            }
+        })?;
+
+    let (_, connection) = stream.get_ref();
+    let handshake = Handshake {
+        version: match connection.protocol_version() {
+            Some(rustls::ProtocolVersion::TLSv1_2) => "TLS 1.2".to_string(),
+            Some(rustls::ProtocolVersion::TLSv1_3) => "TLS 1.3".to_string(),
+            Some(version) => format!("{version:?}"),
+            None => "Unknown".to_string(),
+        },
+        alpn: connection
+            .alpn_protocol()
+            .map(|protocol| String::from_utf8_lossy(protocol).to_string()),
+        cipher_suite: connection
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite()))
+            .unwrap_or_default(),
+        remote_address,
+        chain: connection
+            .peer_certificates()
+            .unwrap_or_default()
+            .iter()
+            .map(|certificate| CertificateDetails::from_der(certificate))
+            .collect::<Result<Vec<_>, String>>()?,
+    };
+
+    let _ = stream.shutdown().await;
+
+    Ok(handshake)
+}
+
+/// Whether the handshake failed as the server only offers TLS 1.0 or 1.1, which rustls doesn't support
+fn is_legacy_version(error: &std::io::Error) -> bool {
+    matches!(
+        error
+            .get_ref()
+            .and_then(|error| error.downcast_ref::<rustls::Error>()),
+        Some(
+            rustls::Error::AlertReceived(rustls::AlertDescription::ProtocolVersion)
+                | rustls::Error::PeerIncompatible(
+                    rustls::PeerIncompatible::ServerTlsVersionIsDisabledByOurConfig
+                        | rustls::PeerIncompatible::ServerDoesNotSupportTls12Or13
+                )
+        )
+    )
+}
+
+/// Accepts any certificate, as the handshake is only made to read the chain the server sends
+#[derive(Debug)]
+struct AnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            certificate,
+            signature,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            certificate,
+            signature,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use cookie_store::{CookieDomain, CookieExpiration, RawCookie};
+use reqwest_cookie_store::{CookieStore, CookieStoreMutex};
+
+/// The first line of a Netscape `cookies.txt` file
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// The prefix marking HttpOnly cookies in a Netscape `cookies.txt` file
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// A cookie with the attributes it was set with
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+
+    /// The host the cookie is sent to
+    pub domain: String,
+
+    /// Whether the cookie is also sent to the domain's subdomains
+    pub include_subdomains: bool,
+    pub path: String,
+
+    /// When the cookie expires as a Unix timestamp, session cookies don't have one
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+impl Cookie {
+    /// A cookie set by a response to the url, the domain and path default to the url's
+    pub fn from_response(cookie: &reqwest::cookie::Cookie, url: &url::Url) -> Self {
+        let (domain, include_subdomains) = match cookie.domain() {
+            Some(domain) => (domain.trim_start_matches('.').to_lowercase(), true),
+            None => (url.host_str().unwrap_or_default().to_string(), false),
+        };
+
+        // Max-Age takes precedence over Expires
+        let expires = match (cookie.max_age(), cookie.expires()) {
+            (Some(max_age), _) => Some(chrono::Utc::now().timestamp() + max_age.as_secs() as i64),
+            (None, Some(expires)) => expires
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .map(|duration| duration.as_secs() as i64),
+            (None, None) => None,
+        };
+
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            include_subdomains,
+            path: cookie
+                .path()
+                .map(str::to_string)
+                .unwrap_or_else(|| default_path(url)),
+            expires,
+            secure: cookie.secure(),
+            http_only: cookie.http_only(),
+        }
+    }
+
+    /// When the cookie expires, for display
+    pub fn expiry(&self) -> String {
+        self.expires
+            .and_then(|expires| chrono::DateTime::from_timestamp(expires, 0))
+            .map(|expires| expires.format("%d/%m/%Y %H:%M UTC").to_string())
+            .unwrap_or("Session".to_string())
+    }
+
+    /// The cookie's flags, for display
+    pub fn flags(&self) -> String {
+        [
+            (self.secure, "Secure"),
+            (self.http_only, "HttpOnly"),
+            (self.include_subdomains, "Subdomains"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+impl From<&cookie_store::Cookie<'_>> for Cookie {
+    fn from(cookie: &cookie_store::Cookie<'_>) -> Self {
+        let (domain, include_subdomains) = match &cookie.domain {
+            CookieDomain::HostOnly(domain) => (domain.to_string(), false),
+            CookieDomain::Suffix(domain) => (domain.to_string(), true),
+            CookieDomain::NotPresent | CookieDomain::Empty => (String::new(), false),
+        };
+
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            include_subdomains,
+            path: String::from(&cookie.path),
+            expires: match &cookie.expires {
+                CookieExpiration::AtUtc(expires) => Some(expires.unix_timestamp()),
+                CookieExpiration::SessionEnd => None,
+            },
+            secure: cookie.secure().unwrap_or_default(),
+            http_only: cookie.http_only().unwrap_or_default(),
+        }
+    }
+}
+
+/// The path a cookie defaults to, the directory of the url's path
+fn default_path(url: &url::Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => url.path()[..index].to_string(),
+    }
+}
+
+/// Read the cookies of a response, the cookie values stored by earlier versions are kept without a name
+pub fn records_or_values<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Cookie>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Record(Cookie),
+        Value(String),
+    }
+
+    let stored: Vec<Stored> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(stored
+        .into_iter()
+        .map(|cookie| match cookie {
+            Stored::Record(cookie) => cookie,
+            Stored::Value(value) => Cookie {
+                value,
+                ..Default::default()
+            },
+        })
+        .collect())
+}
+
+/// Write the cookies as a Netscape `cookies.txt` file, as read by curl and browser extensions
+pub fn to_netscape(cookies: &[Cookie]) -> String {
+    let mut contents = format!("{NETSCAPE_HEADER}\n# Exported by Reqwestur\n\n");
+
+    for cookie in cookies {
+        let flag = |set: bool| if set { "TRUE" } else { "FALSE" };
+
+        contents.push_str(&format!(
+            "{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            if cookie.http_only {
+                HTTP_ONLY_PREFIX
+            } else {
+                ""
+            },
+            if cookie.include_subdomains { "." } else { "" },
+            cookie.domain,
+            flag(cookie.include_subdomains),
+            cookie.path,
+            flag(cookie.secure),
+            cookie.expires.unwrap_or_default(),
+            cookie.name,
+            cookie.value
+        ));
+    }
+
+    contents
+}
+
+/// Read the cookies from a Netscape `cookies.txt` file
+pub fn from_netscape(contents: &str) -> Result<Vec<Cookie>, String> {
+    let mut cookies = Vec::new();
+
+    for (number, line) in contents.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid = || format!("Line {} is not a cookies.txt entry.", number + 1);
+        let fields: Vec<&str> = line.splitn(7, '\t').collect();
+        let [
+            domain,
+            include_subdomains,
+            path,
+            secure,
+            expires,
+            name,
+            value,
+        ] = fields[..]
+        else {
+            return Err(invalid());
+        };
+        let expires: i64 = expires.trim().parse().map_err(|_| invalid())?;
+
+        cookies.push(Cookie {
+            name: name.to_string(),
+            value: value.trim_end_matches('\r').to_string(),
+            domain: domain.trim_start_matches('.').to_lowercase(),
+            include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+            path: path.to_string(),
+            expires: (expires != 0).then_some(expires),
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            http_only,
+        });
+    }
+
+    Ok(cookies)
+}
+
+/// The cookies kept between the requests sent in an environment
+///
+/// Clones share the same store, so the client sees the cookies set by earlier responses.
+#[derive(Default, Clone)]
+pub struct CookieJar(Arc<CookieStoreMutex>);
+
+impl CookieJar {
+    /// The store handed to the HTTP client
+    pub fn store(&self) -> Arc<CookieStoreMutex> {
+        self.0.clone()
+    }
+
+    /// Identifies the store, used to tell whether the client needs rebuilding
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+
+    /// The number of cookies that haven't expired
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().iter_unexpired().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every cookie
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    /// The cookies that haven't expired, ordered by domain, path and name
+    pub fn records(&self) -> Vec<Cookie> {
+        let mut cookies: Vec<Cookie> = self
+            .0
+            .lock()
+            .unwrap()
+            .iter_unexpired()
+            .map(Cookie::from)
+            .collect();
+
+        cookies.sort_by(|a, b| (&a.domain, &a.path, &a.name).cmp(&(&b.domain, &b.path, &b.name)));
+        cookies
+    }
+
+    /// Add the cookie, replacing any cookie with the same domain, path and name
+    pub fn insert(&self, cookie: &Cookie) -> Result<(), String> {
+        if cookie.name.is_empty() {
+            return Err("Cookies need a name.".to_string());
+        }
+
+        if cookie.domain.is_empty() {
+            return Err(format!("The cookie {} needs a domain.", cookie.name));
+        }
+
+        let path = if cookie.path.starts_with('/') {
+            cookie.path.clone()
+        } else {
+            format!("/{}", cookie.path)
+        };
+
+        // The cookie is stored as if it was set by a response from its domain
+        let scheme = if cookie.secure { "https" } else { "http" };
+        let url =
+            url::Url::parse(&format!("{scheme}://{}{path}", cookie.domain)).map_err(|error| {
+                format!("The cookie {} has an invalid domain - {error}", cookie.name)
+            })?;
+
+        let mut raw = RawCookie::new(cookie.name.clone(), cookie.value.clone());
+        raw.set_path(path);
+        raw.set_secure(cookie.secure);
+        raw.set_http_only(cookie.http_only);
+        if cookie.include_subdomains {
+            raw.set_domain(cookie.domain.clone());
+        }
+        if let Some(expires) = cookie.expires {
+            let expires = time::OffsetDateTime::from_unix_timestamp(expires).map_err(|error| {
+                format!("The cookie {} has an invalid expiry - {error}", cookie.name)
+            })?;
+            raw.set_expires(expires);
+        }
+
+        self.0
+            .lock()
+            .unwrap()
+            .insert_raw(&raw, &url)
+            .map(|_| ())
+            .map_err(|error| format!("Could not store the cookie {} - {error}", cookie.name))
+    }
+
+    /// Remove the cookie with the same domain, path and name
+    pub fn remove(&self, cookie: &Cookie) {
+        self.0
+            .lock()
+            .unwrap()
+            .remove(&cookie.domain, &cookie.path, &cookie.name);
+    }
+}
+
+impl PartialEq for CookieJar {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CookieJar {}
+
+impl serde::Serialize for CookieJar {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let store = self.0.lock().unwrap();
+        serializer.collect_seq(store.iter_unexpired())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CookieJar {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cookies: Vec<cookie_store::Cookie<'static>> =
+            serde::Deserialize::deserialize(deserializer)?;
+        let store = CookieStore::from_cookies(cookies.into_iter().map(Ok::<_, ()>), false)
+            .unwrap_or_default();
+
+        Ok(Self(Arc::new(CookieStoreMutex::new(store))))
+    }
+}
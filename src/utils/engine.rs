@@ -0,0 +1,444 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use crate::utils::{
+    auth::{self, AuthKind},
+    certificates::CertificateDetails,
+    connection::{self, Connection, ConnectionFailure},
+    cookies::{Cookie, CookieJar},
+    oauth2::{self, TokenCache},
+    proxy::{ProxyKind, ProxySettings},
+    request::{ContentType, Method, Request, Response},
+    tls::TlsSettings,
+};
+
+/// The User-Agent sent with every request
+pub const USER_AGENT: &str = "REQWESTUR";
+
+/// The number of seconds to wait for a response when the request doesn't set a timeout
+pub const DEFAULT_TIMEOUT: u64 = 30;
+
+/// The runtime shared by every request sent from the application
+pub fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("reqwestur-engine")
+            .build()
+            .expect("the tokio runtime could not be started")
+    })
+}
+
+/// The errors that can occur whilst building or executing a request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    /// The HTTP client could not be created from the provided settings
+    Client(String),
+
+    /// A header name or value is not valid
+    Header(String),
+
+    /// The request could not be built, e.g. the URI is invalid
+    Build(String),
+
+    /// No response was received before the timeout, in seconds
+    Timeout(u64),
+
+    /// The request was sent but failed before a response was received
+    Transport {
+        status: Option<u16>,
+        message: String,
+
+        /// Why the connection failed, if the request failed to connect
+        failure: Option<ConnectionFailure>,
+    },
+
+    /// The response was received but the body could not be read
+    Body(String),
+
+    /// No OAuth 2.0 access token could be fetched
+    Authorization(String),
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Client(message) => write!(f, "Could not create the client - {message}"),
+            Self::Header(message) => write!(f, "Invalid header - {message}"),
+            Self::Build(message) => write!(f, "Could not build the request - {message}"),
+            Self::Timeout(seconds) => write!(f, "Request timed out after {seconds} seconds."),
+            Self::Transport { message, .. } => write!(f, "Request failed - {message}"),
+            Self::Body(message) => write!(f, "Could not read the response body - {message}"),
+            Self::Authorization(message) => {
+                write!(f, "Could not get an OAuth 2.0 token - {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// The settings used to build the HTTP client
+#[derive(Default, Clone)]
+pub struct ClientSettings {
+    /// The client identity used for certificate authentication
+    pub identity: Option<reqwest::Identity>,
+
+    /// The jar that keeps cookies between requests, cookies aren't kept if unset
+    pub cookies: Option<CookieJar>,
+
+    /// The root certificates, TLS version and server names used to connect
+    pub tls: TlsSettings,
+
+    /// Whether invalid, e.g. self-signed or expired, server certificates are accepted
+    pub accept_invalid_certificates: bool,
+
+    /// Whether server certificates issued for other hosts are accepted
+    pub accept_invalid_hostnames: bool,
+
+    /// The proxy requests are sent through
+    pub proxy: ProxySettings,
+}
+
+/// The number of executors kept, so switching between a few certificates or proxies doesn't rebuild clients
+const CACHED_CLIENTS: usize = 8;
+
+/// Keeps the recently used executors, and so their connection pools and TLS sessions, by their settings
+#[derive(Default, Clone)]
+pub struct ClientCache {
+    built: Vec<(u64, Arc<RequestExecutor>)>,
+}
+
+impl ClientCache {
+    /// The executor built from the settings with the fingerprint, marking it as the most recently used
+    pub fn get(&mut self, fingerprint: u64) -> Option<Arc<RequestExecutor>> {
+        let index = self
+            .built
+            .iter()
+            .position(|(built, _)| *built == fingerprint)?;
+        let built = self.built.remove(index);
+        let executor = built.1.clone();
+        self.built.push(built);
+
+        Some(executor)
+    }
+
+    /// Keep the executor built from the settings with the fingerprint, dropping the least recently used one when full
+    pub fn insert(&mut self, fingerprint: u64, executor: RequestExecutor) -> Arc<RequestExecutor> {
+        let executor = Arc::new(executor);
+        self.built.retain(|(built, _)| *built != fingerprint);
+        if self.built.len() >= CACHED_CLIENTS {
+            self.built.remove(0);
+        }
+        self.built.push((fingerprint, executor.clone()));
+        executor
+    }
+
+    /// The number of executors kept
+    pub fn len(&self) -> usize {
+        self.built.len()
+    }
+
+    /// Whether no executor is kept
+    pub fn is_empty(&self) -> bool {
+        self.built.is_empty()
+    }
+
+    /// Drop the executors so the next requests build new ones
+    pub fn clear(&mut self) {
+        self.built.clear();
+    }
+}
+
+/// A UI-free executor that turns a `Request` into a `Response`
+pub struct RequestExecutor {
+    client: reqwest::Client,
+}
+
+impl RequestExecutor {
+    /// Create a new executor, building the HTTP client from the provided settings
+    pub fn new(settings: ClientSettings) -> Result<Self, EngineError> {
+        let mut client_builder =
+            reqwest::ClientBuilder::new().default_headers(reqwest::header::HeaderMap::from_iter([
+                (
+                    reqwest::header::USER_AGENT,
+                    reqwest::header::HeaderValue::from_static(USER_AGENT),
+                ),
+            ]));
+
+        if let Some(identity) = settings.identity {
+            client_builder = client_builder.identity(identity);
+        }
+
+        if let Some(cookies) = settings.cookies {
+            client_builder = client_builder.cookie_provider(cookies.store());
+        }
+
+        for certificate in settings.tls.certificates().map_err(EngineError::Client)? {
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
+
+        for (host, address) in settings.tls.addresses().map_err(EngineError::Client)? {
+            client_builder = client_builder.resolve(&host, address);
+        }
+
+        if let Some(version) = settings.tls.min_version.version() {
+            client_builder = client_builder.min_tls_version(version);
+        }
+
+        // Without an entered proxy the client uses the system's, unless it's turned off
+        client_builder = match settings.proxy.proxy().map_err(EngineError::Client)? {
+            Some(proxy) => client_builder.proxy(proxy),
+            None if settings.proxy.kind == ProxyKind::NONE => client_builder.no_proxy(),
+            None => client_builder,
+        };
+
+        client_builder = client_builder
+            .tls_info(true)
+            .tls_sni(settings.tls.sni)
+            .danger_accept_invalid_certs(settings.accept_invalid_certificates)
+            .danger_accept_invalid_hostnames(settings.accept_invalid_hostnames);
+
+        let client = client_builder
+            .build()
+            .map_err(|error| EngineError::Client(error.to_string()))?;
+
+        Ok(Self { client })
+    }
+
+    /// The HTTP client, shared with the OAuth 2.0 token requests
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Fill in the OAuth 2.0 access token, fetching or refreshing it if the cached token has expired
+    pub async fn authorize(
+        &self,
+        request: &mut Request,
+        tokens: &TokenCache,
+        browser: &(dyn Fn(&str) + Sync),
+    ) -> Result<(), EngineError> {
+        if request.auth.kind != AuthKind::OAUTH2 {
+            return Ok(());
+        }
+
+        let token = oauth2::token(&self.client, &request.auth, tokens, browser)
+            .await
+            .map_err(EngineError::Authorization)?;
+        request.auth.token = token.access_token;
+
+        Ok(())
+    }
+
+    /// Build the reqwest request without sending it
+    pub fn build(&self, request: &Request) -> Result<reqwest::Request, EngineError> {
+        let Request {
+            method,
+            headers,
+            address,
+            content_type,
+            body,
+            params,
+            timeout,
+            auth,
+            ..
+        } = request;
+
+        let uri = address.url().map_err(EngineError::Build)?;
+        let mut built_request = match method {
+            Method::GET => self.client.get(uri),
+            Method::POST => self.client.post(uri),
+            Method::PUT => self.client.put(uri),
+            Method::PATCH => self.client.patch(uri),
+            Method::DELETE => self.client.delete(uri),
+        }
+        .timeout(Duration::from_secs(timeout.unwrap_or(DEFAULT_TIMEOUT)));
+
+        if !headers.is_empty() {
+            let mut header_list = reqwest::header::HeaderMap::new();
+            for (name, value) in headers {
+                let header_name = reqwest::header::HeaderName::from_str(name)
+                    .map_err(|error| EngineError::Header(format!("{name}: {error}")))?;
+                let header_value = reqwest::header::HeaderValue::from_bytes(value.as_bytes())
+                    .map_err(|error| EngineError::Header(format!("{name}: {error}")))?;
+
+                header_list.append(header_name, header_value);
+            }
+
+            built_request = built_request.headers(header_list);
+        }
+
+        built_request = match content_type {
+            ContentType::EMPTY => built_request,
+            ContentType::MULTIPART => {
+                let mut form = reqwest::multipart::Form::new();
+                for (name, value) in params.clone() {
+                    form = form.text(name, value);
+                }
+                built_request.multipart(form)
+            }
+            ContentType::XWWWFORMURLENCODED => built_request.form(params),
+            _ => {
+                if let Some(body) = body {
+                    // JSON and text bodies are labelled with their type unless a header already is
+                    let has_content_type = headers
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"));
+
+                    if !has_content_type {
+                        built_request = built_request
+                            .header(reqwest::header::CONTENT_TYPE, content_type.to_string());
+                    }
+
+                    built_request.body(body.clone())
+                } else {
+                    built_request
+                }
+            }
+        };
+
+        let mut built_request = built_request
+            .build()
+            .map_err(|error| EngineError::Build(error.to_string()))?;
+        auth.apply(&mut built_request)
+            .map_err(EngineError::Header)?;
+
+        Ok(built_request)
+    }
+
+    /// Build and send the request, returning the typed response
+    pub async fn execute(&self, request: &Request) -> Result<Response, EngineError> {
+        let http_request = self.build(request)?;
+        let started = std::time::Instant::now();
+
+        let timeout = |error: &reqwest::Error| {
+            error
+                .is_timeout()
+                .then(|| EngineError::Timeout(request.timeout.unwrap_or(DEFAULT_TIMEOUT)))
+        };
+
+        let transport = |error: reqwest::Error| {
+            timeout(&error).unwrap_or(EngineError::Transport {
+                status: error.status().map(|status| status.as_u16()),
+                message: connection::error_chain(&error),
+                failure: ConnectionFailure::classify(&error),
+            })
+        };
+
+        let mut response = self.client.execute(http_request).await.map_err(transport)?;
+
+        // Digest credentials are sent in answer to the server's challenge
+        if let Some(authorization) = digest_authorization(request, &response)? {
+            let mut http_request = self.build(request)?;
+            http_request.headers_mut().insert(
+                reqwest::header::AUTHORIZATION,
+                reqwest::header::HeaderValue::from_str(&authorization)
+                    .map_err(|error| EngineError::Header(error.to_string()))?,
+            );
+
+            response = self.client.execute(http_request).await.map_err(transport)?;
+        }
+
+        let connection = Connection {
+            remote_address: response.remote_addr().map(|address| address.to_string()),
+            http_version: Some(format!("{:?}", response.version())),
+            certificate: response
+                .extensions()
+                .get::<reqwest::tls::TlsInfo>()
+                .and_then(|info| info.peer_certificate())
+                .and_then(|der| CertificateDetails::from_der(der).ok()),
+            ..Default::default()
+        };
+
+        let status = (
+            response.status().as_u16(),
+            response
+                .status()
+                .canonical_reason()
+                .unwrap_or("UNKNOWN")
+                .to_string(),
+        );
+
+        let headers: Vec<(String, String)> = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).to_string(),
+                )
+            })
+            .collect();
+
+        let cookies = response
+            .cookies()
+            .map(|cookie| Cookie::from_response(&cookie, response.url()))
+            .collect::<Vec<Cookie>>();
+
+        let text = response
+            .text()
+            .await
+            .map_err(|error| timeout(&error).unwrap_or(EngineError::Body(error.to_string())))?;
+
+        Ok(Response {
+            status,
+            headers,
+            cookies,
+            body: prettify(text),
+            duration: started.elapsed().as_millis() as u64,
+            connection,
+            ..Default::default()
+        })
+    }
+}
+
+/// The answer to a Digest challenge, if the request uses Digest authentication and was challenged
+fn digest_authorization(
+    request: &Request,
+    response: &reqwest::Response,
+) -> Result<Option<String>, EngineError> {
+    if request.auth.kind != AuthKind::DIGEST
+        || response.status() != reqwest::StatusCode::UNAUTHORIZED
+    {
+        return Ok(None);
+    }
+
+    let Some(challenge) = response
+        .headers()
+        .get_all(reqwest::header::WWW_AUTHENTICATE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(auth::digest_challenge)
+    else {
+        return Ok(None);
+    };
+
+    let url = response.url();
+    let uri = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+
+    auth::digest(
+        &request.auth,
+        &challenge,
+        &request.method.to_string(),
+        &uri,
+        &auth::cnonce(),
+    )
+    .map(Some)
+    .map_err(EngineError::Header)
+}
+
+/// Pretty print the body if it is JSON, otherwise return it unchanged
+pub fn prettify(text: String) -> String {
+    match serde_json::from_str::<serde_json::Value>(&text) {
+        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or(text),
+        Err(_) => text,
+    }
+}
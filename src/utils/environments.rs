@@ -0,0 +1,198 @@
+use crate::utils::{
+    cookies::CookieJar, formats::substitute, oauth2::TokenCache, request::Request, secrets::Secret,
+};
+
+/// A named set of variables, e.g. `baseUrl`, filled into requests as `{{baseUrl}}`
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Environment {
+    /// The name shown in the environment picker
+    pub name: String,
+
+    /// The variable names and their values
+    pub variables: Vec<(String, String)>,
+
+    /// The variables whose values are kept in the vault
+    pub secrets: Vec<(String, Secret)>,
+
+    /// Whether cookies set by responses are sent with later requests, e.g. to stay logged in
+    pub keep_cookies: bool,
+
+    /// The cookies kept between requests
+    pub cookies: CookieJar,
+
+    /// Whether invalid server certificates are accepted, e.g. self-signed certificates of test servers
+    pub accept_invalid_certificates: bool,
+
+    /// Whether server certificates issued for other hosts are accepted
+    pub accept_invalid_hostnames: bool,
+
+    /// The OAuth 2.0 tokens fetched for requests sent in the environment
+    #[serde(skip)]
+    pub tokens: TokenCache,
+}
+
+impl Environment {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The variables and the secrets unlocked from the vault
+    pub fn values(&self) -> Vec<(String, String)> {
+        let secrets = self
+            .secrets
+            .iter()
+            .filter(|(_, secret)| !secret.value.is_empty())
+            .map(|(name, secret)| (name.clone(), secret.value.clone()));
+
+        self.variables.iter().cloned().chain(secrets).collect()
+    }
+
+    /// A warning shown while the environment turns off any server certificate checks
+    pub fn tls_warning(&self) -> Option<String> {
+        let checks = match (
+            self.accept_invalid_certificates,
+            self.accept_invalid_hostnames,
+        ) {
+            (true, true) => "Invalid certificates and hostnames are",
+            (true, false) => "Invalid certificates are",
+            (false, true) => "Certificates for other hostnames are",
+            (false, false) => return None,
+        };
+
+        Some(format!(
+            "{checks} accepted in the {} environment, responses may not come from the server they claim to.",
+            self.name
+        ))
+    }
+
+    /// Fill in the variables used by the URI, headers, params, credentials and body
+    pub fn apply(&self, request: &Request) -> Request {
+        resolve(request, &self.values())
+    }
+}
+
+/// A `{{name}}` placeholder found in some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    /// The byte range of the placeholder, including the braces
+    pub range: std::ops::Range<usize>,
+
+    /// The variable name, without surrounding whitespace
+    pub name: String,
+}
+
+/// Find the placeholders in the text, in the order they appear
+pub fn placeholders(text: &str) -> Vec<Placeholder> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find("{{") {
+        let start = offset + start;
+        let Some(end) = text[start..].find("}}") else {
+            break;
+        };
+
+        let end = start + end + 2;
+        found.push(Placeholder {
+            range: start..end,
+            name: text[start + 2..end - 2].trim().to_string(),
+        });
+        offset = end;
+    }
+
+    found
+}
+
+/// Fill in the variables used by the URI, headers, params, credentials and body
+pub fn resolve(request: &Request, variables: &[(String, String)]) -> Request {
+    let mut resolved = request.clone();
+
+    resolved.address.uri = substitute(&request.address.uri, variables);
+    for (name, value) in resolved
+        .headers
+        .iter_mut()
+        .chain(resolved.params.iter_mut())
+    {
+        *name = substitute(name, variables);
+        *value = substitute(value, variables);
+    }
+    for field in resolved.auth.fields_mut() {
+        *field = substitute(field, variables);
+    }
+    resolved.body = request
+        .body
+        .as_ref()
+        .map(|body| substitute(body, variables));
+
+    resolved
+}
+
+/// Replace the secret values in the request with their placeholders, e.g. before it's exported
+pub fn redact(request: &Request, secrets: &[(String, String)]) -> Request {
+    let hide = |text: &str| {
+        secrets
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .fold(text.to_string(), |text, (name, value)| {
+                text.replace(value, &format!("{{{{{name}}}}}"))
+            })
+    };
+
+    let mut redacted = request.clone();
+
+    redacted.address.uri = hide(&request.address.uri);
+    for (_, value) in redacted
+        .headers
+        .iter_mut()
+        .chain(redacted.params.iter_mut())
+    {
+        *value = hide(value);
+    }
+    for field in redacted.auth.fields_mut().into_iter().chain(
+        redacted
+            .proxy
+            .iter_mut()
+            .flat_map(|proxy| proxy.fields_mut()),
+    ) {
+        *field = hide(field);
+    }
+    redacted.body = request.body.as_deref().map(hide);
+
+    for (_, value) in redacted.response.headers.iter_mut() {
+        *value = hide(value);
+    }
+    redacted.response.body = hide(&request.response.body);
+
+    redacted
+}
+
+/// The names of the placeholders in the request that have no variable, without duplicates
+pub fn unresolved(request: &Request, variables: &[(String, String)]) -> Vec<String> {
+    let fields = std::iter::once(&request.address.uri)
+        .chain(
+            request
+                .headers
+                .iter()
+                .chain(request.params.iter())
+                .flat_map(|(name, value)| [name, value]),
+        )
+        .chain(request.auth.fields())
+        .chain(request.body.as_ref());
+
+    let mut names: Vec<String> = Vec::new();
+    for field in fields {
+        for placeholder in placeholders(field) {
+            if !variables.iter().any(|(name, _)| *name == placeholder.name)
+                && !names.contains(&placeholder.name)
+            {
+                names.push(placeholder.name);
+            }
+        }
+    }
+
+    names
+}
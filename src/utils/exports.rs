@@ -1,6 +1,10 @@
-use serde_json::{Value, json};
-
-use crate::{ui::widgets::notification::Notification, utils::request::Request};
+use crate::{
+    ui::widgets::notification::{Notification, NotificationKind},
+    utils::{
+        formats::{self, ParsedRequests},
+        request::Request,
+    },
+};
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Eq, PartialEq)]
 pub enum RequestSourceType {
@@ -23,6 +27,9 @@ impl ToString for RequestSourceType {
 pub enum ExportType {
     JSON,
     HTTP,
+    POSTMAN,
+    HAR,
+    OPENAPI,
 }
 
 impl ToString for ExportType {
@@ -30,6 +37,9 @@ impl ToString for ExportType {
         let str = match self {
             ExportType::JSON => "json",
             ExportType::HTTP => "http",
+            ExportType::POSTMAN => "postman",
+            ExportType::HAR => "har",
+            ExportType::OPENAPI => "openapi",
         };
 
         str.to_owned()
@@ -37,11 +47,60 @@ impl ToString for ExportType {
 }
 
 impl ExportType {
-    const OPTIONS: [Self; 2] = [Self::HTTP, Self::JSON];
+    const OPTIONS: [Self; 5] = [
+        Self::HTTP,
+        Self::JSON,
+        Self::POSTMAN,
+        Self::HAR,
+        Self::OPENAPI,
+    ];
 
     pub fn values() -> Vec<Self> {
         Self::OPTIONS.to_vec()
     }
+
+    /// The extension of the exported file
+    pub fn extension(&self) -> String {
+        let str = match self {
+            ExportType::JSON => "json",
+            ExportType::HTTP => "http",
+            ExportType::POSTMAN => "postman_collection.json",
+            ExportType::HAR => "har",
+            ExportType::OPENAPI => "yaml",
+        };
+
+        str.to_owned()
+    }
+
+    /// The extensions accepted when importing, only the last part of the extension is matched
+    pub fn import_extensions(&self) -> Vec<String> {
+        match self {
+            ExportType::OPENAPI => vec!["yaml".to_owned(), "yml".to_owned(), "json".to_owned()],
+            _ => vec![
+                self.extension()
+                    .rsplit('.')
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned(),
+            ],
+        }
+    }
+
+    /// Whether requests can be exported in this format, OpenAPI documents can only be imported
+    pub fn can_export(&self) -> bool {
+        *self != ExportType::OPENAPI
+    }
+
+    /// Find the export type matching a file's extension
+    pub fn from_extension(file_path: &std::path::Path) -> Option<Self> {
+        let extension = file_path.extension()?.to_str()?.to_lowercase();
+
+        // Postman collections and OpenAPI documents are detected from their contents as they
+        // share the JSON extension
+        Self::values()
+            .into_iter()
+            .find(|export_type| export_type.import_extensions().contains(&extension))
+    }
 }
 
 pub struct ReqwesturIO {
@@ -56,86 +115,98 @@ impl ReqwesturIO {
         source_type: RequestSourceType,
         export_type: ExportType,
     ) -> Option<Self> {
-        if let Some(file_path) = rfd::FileDialog::new()
+        rfd::FileDialog::new()
             .set_file_name(format!(
                 "{}.{}",
                 source_type.to_string(),
-                export_type.to_string()
+                export_type.extension()
             ))
             .save_file()
-        {
-            Some(Self {
+            .map(|file_path| Self {
                 requests,
                 file_path,
                 export_type,
             })
-        } else {
-            None
-        }
     }
 
     pub fn export(&self) -> Result<(), Notification> {
-        match self.export_type {
-            ExportType::JSON => {
-                let mut json_requests: Vec<Value> = Vec::default();
-                for request in self.requests.clone() {
-                    json_requests.push(json!({
-                        "method": request.method,
-                        "contentType": request.content_type,
-                        "uri": request.address.uri,
-                        "headers": request.headers,
-                        "body": request.body,
-                        "params": request.params
-                    }));
-                }
-
-                let body = serde_json::to_string_pretty(&json!(json_requests)).unwrap();
-                let _ = std::fs::write(self.file_path.clone(), body);
+        let body = match self.export_type {
+            ExportType::JSON => formats::json::serialize(&self.requests),
+            ExportType::HTTP => formats::http::serialize(&self.requests),
+            ExportType::POSTMAN => {
+                // Name the collection after the file, e.g. `reqwestur_history`
+                let name = self
+                    .file_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default()
+                    .trim_end_matches(&format!(".{}", self.export_type.extension()))
+                    .to_string();
+
+                formats::postman::serialize(&name, &self.requests)
             }
-            ExportType::HTTP => {
-                let http_requests: Vec<String> = Vec::default();
-                for request in self.requests.clone() {
-                    let mut http_request: Vec<String> = Vec::default();
-
-                    // Method and URI
-                    http_request.push(format!(
-                        "{} {}",
-                        request.method.to_string(),
-                        request.address.uri
-                    ));
-
-                    // Headers
-                    let mut headers: String = String::default();
-                    for (name, value) in request.headers {
-                        headers += &format!("{name}={value}\n");
-                    }
-                    http_request.push(headers);
-
-                    // Params
-                    if !request.params.is_empty() {
-                        let mut params: Vec<String> = Vec::default();
-                        for (name, value) in request.params {
-                            params.push(format!("{name}={value}"));
-                        }
-
-                        http_request.push(params.join("\n&") + "\n");
-                    }
-
-                    // Body
-                    if let Some(body) = request.body {
-                        http_request.push(format!("{body}\n"));
-                    }
-                }
-
-                let body = http_requests.join("\n###\n\n");
-                let _ = std::fs::write(self.file_path.clone(), body);
+            ExportType::HAR => formats::har::serialize(&self.requests),
+            ExportType::OPENAPI => {
+                return Err(Notification::new(
+                    "OpenAPI documents can only be imported.",
+                    NotificationKind::ERROR,
+                ));
             }
+        };
+
+        std::fs::write(&self.file_path, body).map_err(|error| {
+            Notification::new(
+                format!("Could not write {} - {error}", self.file_path.display()),
+                NotificationKind::ERROR,
+            )
+        })
+    }
+
+    /// Ask the user for a file to import, the format is chosen from the file's extension
+    pub fn open() -> Option<Self> {
+        let mut dialog = rfd::FileDialog::new();
+        for export_type in ExportType::values() {
+            dialog = dialog.add_filter(
+                export_type.to_string().to_uppercase(),
+                &export_type.import_extensions(),
+            );
         }
 
-        Ok(())
+        dialog.pick_file().map(|file_path| Self {
+            requests: Vec::new(),
+            export_type: ExportType::from_extension(&file_path).unwrap_or(ExportType::JSON),
+            file_path,
+        })
+    }
+
+    /// Read the requests from the selected file, invalid entries are reported rather than imported
+    pub fn import(&self) -> Result<ParsedRequests, Notification> {
+        let contents = std::fs::read_to_string(&self.file_path).map_err(|error| {
+            Notification::new(
+                format!("Could not read {} - {error}", self.file_path.display()),
+                NotificationKind::ERROR,
+            )
+        })?;
+
+        parse(&self.export_type, &contents)
+            .map_err(|error| Notification::new(error, NotificationKind::ERROR))
     }
+}
 
-    pub fn _import(&self) -> Result<(), Notification> {
-        Ok(())
+/// Parse the contents of an imported file in the given format
+pub fn parse(export_type: &ExportType, contents: &str) -> Result<ParsedRequests, String> {
+    match export_type {
+        // Postman collections and OpenAPI documents share the extension of the JSON export
+        ExportType::JSON if formats::postman::is_collection(contents) => {
+            formats::postman::parse(contents)
+        }
+        ExportType::JSON if formats::openapi::is_document(contents) => {
+            formats::openapi::parse(contents)
+        }
+        ExportType::JSON => formats::json::parse(contents),
+        ExportType::HTTP => Ok(formats::http::parse(contents)),
+        ExportType::POSTMAN => formats::postman::parse(contents),
+        ExportType::HAR => formats::har::parse(contents),
+        ExportType::OPENAPI => formats::openapi::parse(contents),
     }
 }
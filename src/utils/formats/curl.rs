@@ -0,0 +1,626 @@
+//! `curl` command lines, as pasted from bug reports or copied from browser developer tools
+//!
+//! ```sh
+//! curl -X POST 'https://example.com/users' \
+//!   -H 'Authorization: Bearer token' \
+//!   --json '{"name": "Reqwestur"}'
+//! ```
+
+use std::str::FromStr;
+
+use crate::utils::{
+    auth::{ApiKeyLocation, Auth, AuthKind},
+    formats::{ParsedRequests, content_type_header, set_body},
+    proxy::{ProxyKind, ProxySettings},
+    request::{Address, ContentType, Method, Request},
+};
+
+/// The options which are followed by a value
+const VALUE_OPTIONS: [&str; 46] = [
+    "-X",
+    "--request",
+    "-H",
+    "--header",
+    "-d",
+    "--data",
+    "--data-raw",
+    "--data-binary",
+    "--data-ascii",
+    "--data-urlencode",
+    "--json",
+    "-F",
+    "--form",
+    "--form-string",
+    "-u",
+    "--user",
+    "--aws-sigv4",
+    "-E",
+    "--cert",
+    "--cert-type",
+    "--key",
+    "--key-type",
+    "--pass",
+    "--cacert",
+    "--url",
+    "-A",
+    "--user-agent",
+    "-e",
+    "--referer",
+    "-b",
+    "--cookie",
+    "-x",
+    "--proxy",
+    "-U",
+    "--proxy-user",
+    "--noproxy",
+    "--socks5",
+    "--socks5-hostname",
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "-w",
+    "--write-out",
+    "--retry",
+];
+
+/// The options which only change how curl displays or follows the response
+const IGNORED_OPTIONS: [&str; 27] = [
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-L",
+    "--location",
+    "-f",
+    "--fail",
+    "-#",
+    "--progress-bar",
+    "-N",
+    "--no-buffer",
+    "--http1.1",
+    "--http2",
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "-w",
+    "--write-out",
+    "--retry",
+    "--globoff",
+];
+
+/// Parse a curl command into a request, the options that can't be represented are reported
+pub fn parse(command: &str) -> ParsedRequests {
+    let mut parsed = ParsedRequests::default();
+    let request = parse_command(command, &mut parsed.warnings);
+    parsed.push(1, request);
+    parsed
+}
+
+/// The parts of the command that make up the request
+#[derive(Default)]
+struct Command {
+    method: Option<String>,
+    uri: Option<String>,
+    headers: Vec<(String, String)>,
+    data: Vec<String>,
+    form: Vec<(String, String)>,
+    auth: Auth,
+    proxy: Option<ProxySettings>,
+    proxy_user: Option<String>,
+    no_proxy: Option<String>,
+    json: bool,
+    get: bool,
+}
+
+impl Command {
+    /// Apply an option, and its value, to the command
+    fn apply(&mut self, flag: &str, value: String, warnings: &mut Vec<String>) {
+        match flag {
+            "-X" | "--request" => self.method = Some(value),
+            "-H" | "--header" => match value.split_once(':') {
+                Some((name, value)) => self
+                    .headers
+                    .push((name.trim().to_string(), value.trim().to_string())),
+                None => warnings.push(format!("the invalid header \"{value}\"")),
+            },
+            "-d" | "--data" | "--data-ascii" | "--data-binary" if value.starts_with('@') => {
+                warnings.push(format!("the {flag} option, files can't be uploaded"))
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" | "--data-raw" => {
+                self.data.push(value)
+            }
+            "--data-urlencode" => self.data.push(url_encode_data(&value)),
+            "--json" => {
+                self.data.push(value);
+                self.json = true;
+            }
+            "-F" | "--form" if value.contains("=@") || value.contains("=<") => {
+                let name = value.split('=').next().unwrap_or_default();
+                warnings.push(format!("file field \"{name}\""));
+            }
+            "-F" | "--form" | "--form-string" => {
+                let (name, value) = value.split_once('=').unwrap_or((&value, ""));
+                self.form.push((name.to_string(), value.to_string()));
+            }
+            "-u" | "--user" => {
+                let (username, password) = value.split_once(':').unwrap_or((&value, ""));
+                self.auth.username = username.to_string();
+                self.auth.password = password.to_string();
+                if !matches!(self.auth.kind, AuthKind::DIGEST | AuthKind::AWSSIGV4) {
+                    self.auth.kind = AuthKind::BASIC;
+                }
+            }
+            "--basic" => self.auth.kind = AuthKind::BASIC,
+            "--digest" => self.auth.kind = AuthKind::DIGEST,
+            "--aws-sigv4" => {
+                // e.g. aws:amz:eu-west-2:execute-api, the region and service are optional to curl
+                let mut providers = value.split(':').skip(2);
+                self.auth.kind = AuthKind::AWSSIGV4;
+                self.auth.sigv4.region = providers.next().unwrap_or_default().to_string();
+                self.auth.sigv4.service = providers.next().unwrap_or_default().to_string();
+            }
+            "-A" | "--user-agent" => self.headers.push(("User-Agent".to_string(), value)),
+            "-e" | "--referer" => self.headers.push(("Referer".to_string(), value)),
+            "-b" | "--cookie" if !value.contains('=') => {
+                warnings.push(format!("the {flag} option, cookie files can't be read"))
+            }
+            "-b" | "--cookie" => self.headers.push(("Cookie".to_string(), value)),
+            "-G" | "--get" => self.get = true,
+            "-I" | "--head" => self.method = Some("HEAD".to_string()),
+            "--url" => self.uri = Some(value),
+            // Responses are always decompressed
+            "--compressed" => {}
+            "-k" | "--insecure" => warnings.push(format!(
+                "the {flag} option, invalid certificates are accepted in the environment settings"
+            )),
+            "--cacert" => warnings.push(format!(
+                "the {flag} option, CA certificates are added in the TLS settings"
+            )),
+            "-E" | "--cert" | "--cert-type" | "--key" | "--key-type" | "--pass" => warnings.push(
+                format!("the {flag} option, a certificate from the Certificate Library is used"),
+            ),
+            "-x" | "--proxy" | "--socks5" | "--socks5-hostname" => {
+                let url = if flag.starts_with("--socks5") {
+                    format!("socks5://{value}")
+                } else {
+                    value
+                };
+
+                match ProxySettings::from_url(&url) {
+                    Ok(proxy) => self.proxy = Some(proxy),
+                    Err(_) => warnings.push(format!(
+                        "the {flag} option, the proxy {url} isn't supported"
+                    )),
+                }
+            }
+            "-U" | "--proxy-user" => self.proxy_user = Some(value),
+            "--noproxy" => self.no_proxy = Some(value),
+            flag if IGNORED_OPTIONS.contains(&flag) => {}
+            flag => warnings.push(format!("the unknown option {flag}")),
+        }
+    }
+}
+
+/// Convert the command line into a request
+fn parse_command(command: &str, warnings: &mut Vec<String>) -> Result<Request, String> {
+    let mut arguments = split_arguments(command)?.into_iter().peekable();
+
+    if arguments.peek().is_some_and(|program| program == "curl") {
+        arguments.next();
+    }
+
+    let mut command = Command::default();
+
+    while let Some(argument) = arguments.next() {
+        if argument.starts_with("--") {
+            let value = if VALUE_OPTIONS.contains(&argument.as_str()) {
+                arguments
+                    .next()
+                    .ok_or(format!("Missing a value for {argument}."))?
+            } else {
+                String::new()
+            };
+
+            command.apply(&argument, value, warnings);
+        } else if argument.starts_with('-') && argument.len() > 1 {
+            // Short options may be combined, e.g. `-sSL` or `-XPOST`
+            for (index, character) in argument.char_indices().skip(1) {
+                let flag = format!("-{character}");
+
+                if VALUE_OPTIONS.contains(&flag.as_str()) {
+                    let rest = &argument[index + character.len_utf8()..];
+                    let value = if rest.is_empty() {
+                        arguments
+                            .next()
+                            .ok_or(format!("Missing a value for {flag}."))?
+                    } else {
+                        rest.to_string()
+                    };
+
+                    command.apply(&flag, value, warnings);
+                    break;
+                }
+
+                command.apply(&flag, String::new(), warnings);
+            }
+        } else if command.uri.is_none() {
+            command.uri = Some(argument);
+        } else {
+            warnings.push(format!("the additional URL {argument}"));
+        }
+    }
+
+    build_request(command)
+}
+
+/// Build the request from the parsed command, following curl's defaults
+fn build_request(command: Command) -> Result<Request, String> {
+    let mut uri = command.uri.ok_or("The command has no URL.")?;
+
+    if !command.data.is_empty() && !command.form.is_empty() {
+        return Err("The -d and -F options can't be used together.".to_string());
+    }
+
+    // curl sends the data as the query with -G
+    let (data, get) = if command.get && !command.data.is_empty() {
+        uri.push(if uri.contains('?') { '&' } else { '?' });
+        uri.push_str(&command.data.join("&"));
+        (Vec::new(), true)
+    } else {
+        (command.data, command.get)
+    };
+
+    let has_body = !data.is_empty() || !command.form.is_empty();
+    let method = match command.method {
+        Some(method) => Method::from_str(&method)?,
+        None if has_body && !get => Method::POST,
+        None => Method::GET,
+    };
+
+    // The AWS credentials are given with -u, and the session token as a header
+    let mut auth = command.auth;
+    let mut headers = command.headers;
+    if auth.kind == AuthKind::AWSSIGV4 {
+        auth.sigv4.access_key = std::mem::take(&mut auth.username);
+        auth.sigv4.secret_key = std::mem::take(&mut auth.password);
+        if let Some(index) = headers
+            .iter()
+            .position(|(name, _)| name.eq_ignore_ascii_case("x-amz-security-token"))
+        {
+            auth.sigv4.session_token = headers.remove(index).1;
+        }
+    }
+
+    // The proxy options become the request's own proxy, `--noproxy '*'` alone connects directly
+    let mut proxy = command.proxy;
+    match &mut proxy {
+        Some(proxy) => {
+            if let Some(user) = command.proxy_user {
+                let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+                proxy.username = username.to_string();
+                proxy.password.value = password.to_string();
+            }
+            if let Some(no_proxy) = command.no_proxy {
+                proxy.bypass = no_proxy.split(',').map(str::to_string).collect();
+            }
+        }
+        None if command
+            .no_proxy
+            .is_some_and(|no_proxy| no_proxy.trim() == "*") =>
+        {
+            proxy = Some(ProxySettings {
+                kind: ProxyKind::NONE,
+                ..Default::default()
+            });
+        }
+        None => {}
+    }
+
+    let mut request = Request {
+        method,
+        headers,
+        auth,
+        address: Address {
+            uri,
+            ..Default::default()
+        },
+        proxy,
+        ..Default::default()
+    };
+
+    if !command.form.is_empty() {
+        request.content_type = ContentType::MULTIPART;
+        request.params = command.form;
+        request
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+    } else if !data.is_empty() {
+        let has_header = |request: &Request, name: &str| {
+            request
+                .headers
+                .iter()
+                .any(|(header, _)| header.eq_ignore_ascii_case(name))
+        };
+
+        // --json sets both headers, otherwise curl sends the data as a form
+        if command.json && !has_header(&request, "accept") {
+            request
+                .headers
+                .push(("Accept".to_string(), ContentType::JSON.to_string()));
+        }
+        if content_type_header(&request).is_none() {
+            let content_type = if command.json {
+                ContentType::JSON
+            } else {
+                ContentType::XWWWFORMURLENCODED
+            };
+            request
+                .headers
+                .push(("Content-Type".to_string(), content_type.to_string()));
+        }
+
+        let body = data.join("&");
+        let mime = content_type_header(&request).unwrap_or_default();
+        set_body(&mut request, body.clone());
+
+        // Data which isn't made of fields, e.g. JSON without a header, is sent as it is
+        if request.content_type == ContentType::XWWWFORMURLENCODED && !is_form_data(&body) {
+            request.content_type = ContentType::TEXT;
+            request.params = Vec::new();
+            request.body = Some(body);
+            request.headers.push(("Content-Type".to_string(), mime));
+        }
+    }
+
+    Ok(request)
+}
+
+/// Whether the data is made up of `name=value` fields
+fn is_form_data(data: &str) -> bool {
+    data.split('&').all(|field| {
+        field
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty())
+            && !field.contains(char::is_whitespace)
+    })
+}
+
+/// Encode the value of a `--data-urlencode` option, the name is expected to be encoded already
+fn url_encode_data(data: &str) -> String {
+    let encode = |value: &str| url::form_urlencoded::byte_serialize(value.as_bytes()).collect();
+
+    match data.split_once('=') {
+        Some(("", value)) => encode(value),
+        Some((name, value)) => format!("{name}={}", encode(value)),
+        None => encode(data),
+    }
+}
+
+/// Split the command line into its arguments, following the quoting rules of a POSIX shell
+fn split_arguments(command: &str) -> Result<Vec<String>, String> {
+    let unterminated = || "The command has an unterminated quote.".to_string();
+
+    let mut arguments: Vec<String> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut characters = command.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            // A backslash before a line break continues the command on the next line
+            '\\' => match characters.next() {
+                Some('\n') => {}
+                Some('\r') if characters.peek() == Some(&'\n') => {
+                    characters.next();
+                }
+                Some(escaped) => current.get_or_insert_default().push(escaped),
+                None => {}
+            },
+            '\'' => {
+                let argument = current.get_or_insert_default();
+                loop {
+                    match characters.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        character => argument.push(character),
+                    }
+                }
+            }
+            '"' => {
+                let argument = current.get_or_insert_default();
+                loop {
+                    match characters.next().ok_or_else(unterminated)? {
+                        '"' => break,
+                        '\\' => match characters.next().ok_or_else(unterminated)? {
+                            escaped @ ('"' | '\\' | '$' | '`') => argument.push(escaped),
+                            '\n' => {}
+                            escaped => {
+                                argument.push('\\');
+                                argument.push(escaped);
+                            }
+                        },
+                        character => argument.push(character),
+                    }
+                }
+            }
+            // ANSI-C quoting, e.g. $'{"name":\n"Reqwestur"}' as copied from Chrome
+            '$' if characters.peek() == Some(&'\'') => {
+                characters.next();
+                let argument = current.get_or_insert_default();
+                loop {
+                    match characters.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        '\\' => match characters.next().ok_or_else(unterminated)? {
+                            'n' => argument.push('\n'),
+                            'r' => argument.push('\r'),
+                            't' => argument.push('\t'),
+                            escaped @ ('\\' | '\'' | '"') => argument.push(escaped),
+                            escaped => {
+                                argument.push('\\');
+                                argument.push(escaped);
+                            }
+                        },
+                        character => argument.push(character),
+                    }
+                }
+            }
+            character if character.is_whitespace() => {
+                if let Some(argument) = current.take() {
+                    arguments.push(argument);
+                }
+            }
+            character => current.get_or_insert_default().push(character),
+        }
+    }
+
+    if let Some(argument) = current {
+        arguments.push(argument);
+    }
+
+    Ok(arguments)
+}
+
+/// Quote an argument for a POSIX shell, if it needs to be
+fn quote(argument: &str) -> String {
+    let is_safe = !argument.is_empty()
+        && argument
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "-_./:=@%+,".contains(character));
+
+    if is_safe {
+        argument.to_string()
+    } else {
+        format!("'{}'", argument.replace('\'', "'\\''"))
+    }
+}
+
+/// Render the request as a curl command, split over lines
+pub fn serialize(request: &Request) -> String {
+    let auth = &request.auth;
+    let uri = auth.uri(&request.address.uri);
+    let mut arguments = vec![format!("curl {}", quote(&uri))];
+
+    let has_body = match request.content_type {
+        ContentType::EMPTY => false,
+        ContentType::JSON | ContentType::TEXT => {
+            request.body.as_ref().is_some_and(|body| !body.is_empty())
+        }
+        ContentType::XWWWFORMURLENCODED | ContentType::MULTIPART => !request.params.is_empty(),
+    };
+
+    // curl picks the method from whether there is a body
+    let inferred = if has_body { Method::POST } else { Method::GET };
+    if request.method != inferred {
+        arguments.push(format!("-X {}", request.method.to_string()));
+    }
+
+    for (name, value) in &request.headers {
+        arguments.push(format!("-H {}", quote(&format!("{name}: {value}"))));
+    }
+
+    let credentials = quote(&format!("{}:{}", auth.username, auth.password));
+    match (&auth.kind, &auth.key_location) {
+        (AuthKind::NONE, _) | (AuthKind::APIKEY, ApiKeyLocation::QUERY) => {}
+        (AuthKind::BASIC, _) => arguments.push(format!("-u {credentials}")),
+        (AuthKind::DIGEST, _) => arguments.push(format!("--digest -u {credentials}")),
+        // OAuth 2.0 tokens are only known once the request is sent
+        (AuthKind::OAUTH2, _) if auth.token.is_empty() => {}
+        (AuthKind::BEARER | AuthKind::OAUTH2, _) => arguments.push(format!(
+            "-H {}",
+            quote(&format!("Authorization: Bearer {}", auth.token))
+        )),
+        (AuthKind::APIKEY, ApiKeyLocation::HEADER) => arguments.push(format!(
+            "-H {}",
+            quote(&format!("{}: {}", auth.key_name, auth.key_value))
+        )),
+        (AuthKind::AWSSIGV4, _) => {
+            let sigv4 = &auth.sigv4;
+            arguments.push(format!(
+                "--aws-sigv4 {}",
+                quote(&format!("aws:amz:{}:{}", sigv4.region, sigv4.service))
+            ));
+            arguments.push(format!(
+                "-u {}",
+                quote(&format!("{}:{}", sigv4.access_key, sigv4.secret_key))
+            ));
+            if !sigv4.session_token.is_empty() {
+                arguments.push(format!(
+                    "-H {}",
+                    quote(&format!("X-Amz-Security-Token: {}", sigv4.session_token))
+                ));
+            }
+        }
+    }
+
+    // Requests without their own proxy use the proxy of every request, which isn't included,
+    // and the password is only kept in the vault so it's written as a placeholder
+    if let Some(proxy) = request.proxy.as_ref().map(ProxySettings::masked) {
+        match (&proxy.kind, proxy.url()) {
+            (ProxyKind::NONE, _) => arguments.push("--noproxy '*'".to_string()),
+            (_, Some(url)) => {
+                arguments.push(format!("-x {}", quote(&url)));
+                if !proxy.username.is_empty() {
+                    arguments.push(format!(
+                        "-U {}",
+                        quote(&format!("{}:{}", proxy.username, proxy.password.value))
+                    ));
+                }
+                if !proxy.bypass.is_empty() {
+                    arguments.push(format!("--noproxy {}", quote(&proxy.bypass.join(","))));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The form content types have their header set by curl
+    let sets_header = matches!(request.content_type, ContentType::JSON | ContentType::TEXT);
+    if has_body && sets_header && content_type_header(request).is_none() {
+        arguments.push(format!(
+            "-H {}",
+            quote(&format!(
+                "Content-Type: {}",
+                request.content_type.to_string()
+            ))
+        ));
+    }
+
+    if has_body {
+        match request.content_type {
+            ContentType::EMPTY => {}
+            ContentType::JSON | ContentType::TEXT => arguments.push(format!(
+                "--data-raw {}",
+                quote(request.body.as_deref().unwrap_or_default())
+            )),
+            ContentType::XWWWFORMURLENCODED => {
+                for (name, value) in &request.params {
+                    let name: String =
+                        url::form_urlencoded::byte_serialize(name.as_bytes()).collect();
+                    arguments.push(format!(
+                        "--data-urlencode {}",
+                        quote(&format!("{name}={value}"))
+                    ));
+                }
+            }
+            ContentType::MULTIPART => {
+                for (name, value) in &request.params {
+                    // Values starting with @ or < are read from files by -F
+                    let flag = if value.starts_with(['@', '<']) {
+                        "--form-string"
+                    } else {
+                        "-F"
+                    };
+                    arguments.push(format!("{flag} {}", quote(&format!("{name}={value}"))));
+                }
+            }
+        }
+    }
+
+    arguments.join(" \\\n  ")
+}
@@ -0,0 +1,464 @@
+//! HTTP Archive (HAR) 1.2 files, as exported by browser developer tools, see http://www.softwareishard.com/blog/har-12-spec/
+
+use std::str::FromStr;
+
+use base64::Engine;
+use serde_json::{Value, json};
+
+use crate::utils::{
+    cookies::Cookie,
+    engine::prettify,
+    formats::{ParsedRequests, content_type_header, request_headers, set_body},
+    request::{Address, ContentType, Method, Request, RequestEvent, Response, TIMESTAMP_FORMAT},
+};
+
+/// The version of the HAR specification written to archives
+const VERSION: &str = "1.2";
+
+/// The HTTP version recorded for each request, the actual version isn't kept
+const HTTP_VERSION: &str = "HTTP/1.1";
+
+/// The root of a HAR file
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Archive {
+    log: Log,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Log {
+    version: String,
+    entries: Vec<Entry>,
+}
+
+/// A request and its response
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Entry {
+    started_date_time: String,
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+
+    /// The name of the request, a custom field written by Reqwestur
+    #[serde(rename = "_name")]
+    name: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    headers: Vec<NameValue>,
+    post_data: Option<PostData>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    status_text: String,
+    headers: Vec<NameValue>,
+    cookies: Vec<HarCookie>,
+    content: Content,
+}
+
+/// A cookie set by a response, the optional attributes are only present if the cookie set them
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct HarCookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    expires: Option<String>,
+    http_only: bool,
+    secure: bool,
+}
+
+/// A header, cookie or query parameter
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct NameValue {
+    name: String,
+    value: String,
+}
+
+/// The body of a request
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PostData {
+    mime_type: String,
+    text: String,
+    params: Vec<Param>,
+}
+
+/// A form field, which may be an uploaded file
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Param {
+    name: String,
+    value: String,
+    file_name: Option<String>,
+}
+
+/// The body of a response
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Content {
+    text: String,
+    encoding: Option<String>,
+}
+
+/// Parse a HAR file, keeping the response of each entry
+pub fn parse(contents: &str) -> Result<ParsedRequests, String> {
+    let archive = serde_json::from_str::<Archive>(contents)
+        .map_err(|error| format!("The file is not a HAR archive - {error}"))?;
+
+    let mut parsed = ParsedRequests::default();
+
+    if !archive.log.version.is_empty() && archive.log.version != VERSION {
+        parsed.warnings.push(format!(
+            "the archive version \"{}\" is not {VERSION}, it was read as {VERSION}",
+            archive.log.version
+        ));
+    }
+
+    for (index, entry) in archive.log.entries.iter().enumerate() {
+        let position = index + 1;
+        let request = parse_entry(position, entry, &mut parsed.warnings);
+        parsed.push(position, request);
+    }
+
+    Ok(parsed)
+}
+
+/// Whether the header is set by the client when the request is sent
+fn is_generated_header(name: &str) -> bool {
+    // HTTP/2 captures include pseudo headers such as `:authority`
+    name.starts_with(':')
+        || name.eq_ignore_ascii_case("host")
+        || name.eq_ignore_ascii_case("content-length")
+}
+
+/// Convert a single entry, recording a warning for each unsupported feature
+fn parse_entry(
+    position: usize,
+    entry: &Entry,
+    warnings: &mut Vec<String>,
+) -> Result<Request, String> {
+    let mut request = Request {
+        name: entry.name.to_string(),
+        method: Method::from_str(&entry.request.method)?,
+        headers: entry
+            .request
+            .headers
+            .iter()
+            .filter(|header| !is_generated_header(&header.name))
+            .map(|header| (header.name.to_string(), header.value.to_string()))
+            .collect(),
+        address: Address {
+            uri: entry.request.url.to_string(),
+            ..Default::default()
+        },
+        timestamp: chrono::DateTime::parse_from_rfc3339(&entry.started_date_time)
+            .map(|started| {
+                started
+                    .with_timezone(&chrono::Utc)
+                    .format(TIMESTAMP_FORMAT)
+                    .to_string()
+            })
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    if let Some(post_data) = &entry.request.post_data {
+        if content_type_header(&request).is_none() && !post_data.mime_type.is_empty() {
+            request
+                .headers
+                .push(("Content-Type".to_string(), post_data.mime_type.to_string()));
+        }
+
+        set_body(&mut request, post_data.text.to_string());
+
+        // The fields are preferred over the text, which may contain the contents of uploaded files
+        let is_form = matches!(
+            request.content_type,
+            ContentType::XWWWFORMURLENCODED | ContentType::MULTIPART
+        );
+        if is_form && !post_data.params.is_empty() {
+            request.params = post_data
+                .params
+                .iter()
+                .filter(|param| {
+                    if param.file_name.is_some() {
+                        warnings.push(format!("file field \"{}\" on entry {position}", param.name));
+                    }
+                    param.file_name.is_none()
+                })
+                .map(|param| (param.name.to_string(), param.value.to_string()))
+                .collect();
+        }
+    }
+
+    let response = &entry.response;
+    if response.status == 0 {
+        return Ok(request);
+    }
+
+    let body = match response.content.encoding.as_deref() {
+        Some("base64") => base64::engine::general_purpose::STANDARD
+            .decode(response.content.text.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or_else(|| {
+                warnings.push(format!("binary response body on entry {position}"));
+                String::new()
+            }),
+        _ => response.content.text.to_string(),
+    };
+
+    let status_text = if response.status_text.is_empty() {
+        reqwest::StatusCode::from_u16(response.status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("UNKNOWN")
+            .to_string()
+    } else {
+        response.status_text.to_string()
+    };
+
+    // Cookies without a domain were set by the requested host
+    let host = url::Url::parse(&request.address.uri)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    request.response = Response {
+        status: (response.status, status_text),
+        headers: response
+            .headers
+            .iter()
+            .map(|header| (header.name.to_string(), header.value.to_string()))
+            .collect(),
+        cookies: response
+            .cookies
+            .iter()
+            .map(|cookie| Cookie {
+                name: cookie.name.to_string(),
+                value: cookie.value.to_string(),
+                include_subdomains: cookie
+                    .domain
+                    .as_ref()
+                    .is_some_and(|domain| domain.starts_with('.')),
+                domain: cookie
+                    .domain
+                    .as_deref()
+                    .unwrap_or(&host)
+                    .trim_start_matches('.')
+                    .to_string(),
+                path: cookie.path.clone().unwrap_or("/".to_string()),
+                expires: cookie
+                    .expires
+                    .as_deref()
+                    .and_then(|expires| chrono::DateTime::parse_from_rfc3339(expires).ok())
+                    .map(|expires| expires.timestamp()),
+                secure: cookie.secure,
+                http_only: cookie.http_only,
+            })
+            .collect(),
+        body: prettify(body),
+        duration: entry.time.max(0.) as u64,
+        ..Default::default()
+    };
+    request.event = RequestEvent::SENT;
+
+    Ok(request)
+}
+
+/// Serialise the requests, and their responses, into a HAR file
+pub fn serialize(requests: &[Request]) -> String {
+    let archive = json!({
+        "log": {
+            "version": VERSION,
+            "creator": {
+                "name": "Reqwestur",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": requests.iter().map(serialize_entry).collect::<Vec<Value>>(),
+        }
+    });
+
+    serde_json::to_string_pretty(&archive).unwrap_or_default()
+}
+
+/// Serialise a single request and its response
+fn serialize_entry(request: &Request) -> Value {
+    let started = chrono::NaiveDateTime::parse_from_str(&request.timestamp, TIMESTAMP_FORMAT)
+        .map(|started| started.and_utc())
+        .unwrap_or_else(|_| chrono::Utc::now())
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    let mut entry = json!({
+        "startedDateTime": started,
+        "time": request.response.duration,
+        "request": serialize_request(request),
+        "response": serialize_response(&request.response),
+        "cache": {},
+        "timings": {
+            "send": 0,
+            "wait": request.response.duration,
+            "receive": 0,
+        },
+    });
+
+    if !request.name.is_empty() {
+        entry["_name"] = json!(request.name);
+    }
+
+    entry
+}
+
+/// Convert the headers into HAR name/value pairs
+fn name_values(pairs: &[(String, String)]) -> Vec<Value> {
+    pairs
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+/// Serialise the request, with the headers it would be sent with
+fn serialize_request(request: &Request) -> Value {
+    let mut headers = request_headers(request);
+
+    // The boundary of multipart bodies is only chosen when they're sent
+    let sets_header = !matches!(
+        request.content_type,
+        ContentType::EMPTY | ContentType::MULTIPART
+    );
+    if sets_header && content_type_header(request).is_none() {
+        headers.push(("Content-Type".to_string(), request.content_type.to_string()));
+    }
+
+    let cookies: Vec<Value> = headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("cookie"))
+        .flat_map(|(_, value)| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect();
+
+    let uri = request.auth.uri(&request.address.uri);
+    let query: Vec<(String, String)> = reqwest::Url::parse(&uri)
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default();
+
+    let mime = content_type_header(request).unwrap_or(request.content_type.to_string());
+    let post_data = match request.content_type {
+        ContentType::EMPTY => None,
+        ContentType::JSON | ContentType::TEXT => Some(json!({
+            "mimeType": mime,
+            "text": request.body.clone().unwrap_or_default(),
+        })),
+        ContentType::XWWWFORMURLENCODED => Some(json!({
+            "mimeType": mime,
+            "text": url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(&request.params)
+                .finish(),
+            "params": name_values(&request.params),
+        })),
+        ContentType::MULTIPART => Some(json!({
+            "mimeType": mime,
+            "text": "",
+            "params": name_values(&request.params),
+        })),
+    };
+
+    let body_size = post_data
+        .as_ref()
+        .and_then(|post_data| post_data["text"].as_str())
+        .map_or(-1, |text| text.len() as i64);
+
+    let mut har_request = json!({
+        "method": request.method.to_string(),
+        "url": uri,
+        "httpVersion": HTTP_VERSION,
+        "cookies": cookies,
+        "headers": name_values(&headers),
+        "queryString": name_values(&query),
+        "headersSize": -1,
+        "bodySize": body_size,
+    });
+
+    if let Some(post_data) = post_data {
+        har_request["postData"] = post_data;
+    }
+
+    har_request
+}
+
+/// Serialise the response, the cookies are read from its `Set-Cookie` headers
+fn serialize_response(response: &Response) -> Value {
+    let header = |header: &str| {
+        response
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(header))
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_default()
+    };
+
+    let cookies: Vec<Value> = response
+        .headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+        .map(|(_, value)| serialize_set_cookie(value))
+        .collect();
+
+    json!({
+        "status": response.status.0,
+        "statusText": response.status.1,
+        "httpVersion": HTTP_VERSION,
+        "cookies": cookies,
+        "headers": name_values(&response.headers),
+        "content": {
+            "size": response.body.len(),
+            "mimeType": header("content-type"),
+            "text": response.body,
+        },
+        "redirectURL": header("location"),
+        "headersSize": -1,
+        "bodySize": response.body.len(),
+    })
+}
+
+/// Convert a `Set-Cookie` header into a HAR cookie
+fn serialize_set_cookie(header: &str) -> Value {
+    let mut attributes = header.split(';').map(str::trim);
+    let (name, value) = attributes
+        .next()
+        .and_then(|cookie| cookie.split_once('='))
+        .unwrap_or_default();
+
+    let mut cookie = json!({ "name": name, "value": value });
+
+    for attribute in attributes {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        match key.to_lowercase().as_str() {
+            "path" => cookie["path"] = json!(value),
+            "domain" => cookie["domain"] = json!(value),
+            "expires" => {
+                if let Ok(expires) = chrono::DateTime::parse_from_rfc2822(value) {
+                    cookie["expires"] = json!(expires.to_rfc3339());
+                }
+            }
+            "httponly" => cookie["httpOnly"] = json!(true),
+            "secure" => cookie["secure"] = json!(true),
+            _ => {}
+        }
+    }
+
+    cookie
+}
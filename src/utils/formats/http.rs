@@ -0,0 +1,319 @@
+//! The `.http` file format used by the VS Code REST Client and the JetBrains HTTP Client
+//!
+//! ```http
+//! @host = https://example.com
+//!
+//! # @name Create User
+//! POST {{host}}/users
+//! Content-Type: application/json
+//!
+//! {"name": "Reqwestur"}
+//!
+//! ###
+//! ```
+
+use std::str::FromStr;
+
+use crate::utils::{
+    auth::{Auth, AuthKind},
+    formats::{ParsedRequests, content_type_header, request_headers, set_body, substitute},
+    request::{Address, ContentType, Method, Request},
+};
+
+/// The line separating each request in the file
+const SEPARATOR: &str = "###";
+
+/// The boundary used when writing multipart bodies
+const MULTIPART_BOUNDARY: &str = "ReqwesturBoundary";
+
+/// Serialise the requests into the contents of a `.http` file
+pub fn serialize(requests: &[Request]) -> String {
+    requests
+        .iter()
+        .map(serialize_request)
+        .collect::<Vec<String>>()
+        .join(&format!("\n{SEPARATOR}\n\n"))
+}
+
+/// Serialise a single request block
+fn serialize_request(request: &Request) -> String {
+    let mut lines: Vec<String> = Vec::new();
+
+    if !request.name.is_empty() {
+        lines.push(format!("# @name {}", request.name));
+    }
+
+    // Request line
+    lines.push(format!(
+        "{} {}",
+        request.method.to_string(),
+        request.auth.uri(&request.address.uri)
+    ));
+
+    // Headers, including the credentials. Basic credentials are written unencoded, as REST Client
+    // accepts them, so placeholders in them can still be filled in
+    let basic = request.auth.kind == AuthKind::BASIC;
+    for (name, value) in request_headers(request) {
+        if basic && name == "Authorization" {
+            let auth = &request.auth;
+            lines.push(format!("{name}: Basic {} {}", auth.username, auth.password));
+        } else {
+            lines.push(format!("{name}: {value}"));
+        }
+    }
+
+    if content_type_header(request).is_none() {
+        match request.content_type {
+            ContentType::EMPTY => {}
+            ContentType::MULTIPART => lines.push(format!(
+                "Content-Type: {}; boundary={MULTIPART_BOUNDARY}",
+                request.content_type.to_string()
+            )),
+            _ => lines.push(format!(
+                "Content-Type: {}",
+                request.content_type.to_string()
+            )),
+        }
+    }
+
+    // Body
+    let body = match request.content_type {
+        ContentType::EMPTY => None,
+        ContentType::XWWWFORMURLENCODED => Some(
+            request
+                .params
+                .iter()
+                .map(|(name, value)| {
+                    url::form_urlencoded::Serializer::new(String::new())
+                        .append_pair(name, value)
+                        .finish()
+                })
+                .collect::<Vec<String>>()
+                .join("\n&"),
+        ),
+        ContentType::MULTIPART => {
+            let mut parts: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| {
+                    format!(
+                        "--{MULTIPART_BOUNDARY}\nContent-Disposition: form-data; name=\"{name}\"\n\n{value}"
+                    )
+                })
+                .collect();
+            parts.push(format!("--{MULTIPART_BOUNDARY}--"));
+
+            Some(parts.join("\n"))
+        }
+        ContentType::JSON | ContentType::TEXT => request.body.clone(),
+    };
+
+    if let Some(body) = body.filter(|body| !body.is_empty()) {
+        lines.push(String::new());
+        lines.push(body);
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Parse a `.http` file, each request is separated by a line starting with `###`
+pub fn parse(contents: &str) -> ParsedRequests {
+    let blocks = split_blocks(contents);
+    let variables = file_variables(&blocks);
+
+    let mut parsed = ParsedRequests::default();
+    let mut entry = 0;
+
+    for block in blocks {
+        match parse_block(&block, &variables) {
+            Ok(None) => {}
+            request => {
+                entry += 1;
+                parsed.push(entry, request.map(Option::unwrap_or_default));
+            }
+        }
+    }
+
+    parsed
+}
+
+/// A request block, with the name given after its `###` separator
+struct Block<'a> {
+    name: String,
+    lines: Vec<&'a str>,
+}
+
+/// Split the file into the lines of each request block
+fn split_blocks(contents: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block> = vec![Block {
+        name: String::new(),
+        lines: Vec::new(),
+    }];
+
+    for line in contents.lines() {
+        if let Some(name) = line.trim_start().strip_prefix(SEPARATOR) {
+            blocks.push(Block {
+                name: name.trim_start_matches('#').trim().to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(block) = blocks.last_mut() {
+            block.lines.push(line);
+        }
+    }
+
+    blocks
+}
+
+/// Whether the line is a comment, outside of the body
+fn is_comment(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with('#') || line.starts_with("//")
+}
+
+/// Read a `@name = value` variable definition
+fn variable_definition(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.trim().strip_prefix('@')?.split_once('=')?;
+    let name = name.trim();
+
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some((name.to_string(), value.trim().to_string()))
+}
+
+/// Read a `# @name value` request name comment
+fn name_comment(line: &str) -> Option<String> {
+    let line = line.trim_start();
+    let comment = line.strip_prefix('#').or_else(|| line.strip_prefix("//"))?;
+    let name = comment.trim_start().strip_prefix("@name")?;
+
+    // `# @name=value` is also accepted
+    if !name.starts_with([' ', '\t', '=']) {
+        return None;
+    }
+
+    Some(name.trim_start_matches([' ', '\t', '=']).trim().to_string())
+}
+
+/// Collect the variables defined before the request line of every block, in order
+fn file_variables(blocks: &[Block]) -> Vec<(String, String)> {
+    let mut variables: Vec<(String, String)> = Vec::new();
+
+    for block in blocks {
+        for line in block.lines.iter().take_while(|line| {
+            line.trim().is_empty() || is_comment(line) || variable_definition(line).is_some()
+        }) {
+            if let Some((name, value)) = variable_definition(line) {
+                // Variables may reference those defined before them
+                let value = substitute(&value, &variables);
+                variables.retain(|(existing, _)| *existing != name);
+                variables.push((name, value));
+            }
+        }
+    }
+
+    variables
+}
+
+/// Parse a single request block, returning `None` if it only contains comments or variables
+fn parse_block(block: &Block, variables: &[(String, String)]) -> Result<Option<Request>, String> {
+    let mut lines = block.lines.iter().copied().peekable();
+    let mut request = Request {
+        name: block.name.clone(),
+        ..Default::default()
+    };
+
+    // Skip anything before the request line, keeping the request's name
+    while let Some(line) = lines.peek() {
+        if let Some(name) = name_comment(line) {
+            request.name = name;
+        } else if !(line.trim().is_empty()
+            || is_comment(line)
+            || variable_definition(line).is_some())
+        {
+            break;
+        }
+
+        lines.next();
+    }
+
+    let Some(request_line) = lines.next() else {
+        return Ok(None);
+    };
+
+    let request_line = substitute(request_line, variables);
+    let mut parts = request_line.split_whitespace();
+    let first = parts.next().unwrap_or_default();
+
+    // The method may be omitted, in which case it's a GET
+    let mut uri = if first.contains("://") {
+        first.to_string()
+    } else {
+        request.method = Method::from_str(first)?;
+        parts
+            .next()
+            .ok_or("The request line has no URI.")?
+            .to_string()
+    };
+
+    // The query may continue over the following lines, starting with `?` or `&`
+    while let Some(line) = lines.peek() {
+        let line = line.trim();
+        if !(line.starts_with('?') || line.starts_with('&')) {
+            break;
+        }
+
+        // The HTTP version may follow the last line of the query
+        let query = line.split_whitespace().next().unwrap_or_default();
+        uri.push_str(&substitute(query, variables));
+        lines.next();
+    }
+
+    request.address = Address {
+        uri,
+        ..Default::default()
+    };
+
+    // Headers run until the first blank line
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+
+        if is_comment(line) {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once(':')
+            .ok_or(format!("Invalid header line \"{line}\"."))?;
+        request
+            .headers
+            .push((name.trim().to_string(), substitute(value.trim(), variables)));
+    }
+
+    // `Authorization: Basic <username> <password>` is encoded when the request is sent
+    if let Some(index) = request
+        .headers
+        .iter()
+        .position(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+        && let ["Basic", username, password] = request.headers[index]
+            .1
+            .split_whitespace()
+            .collect::<Vec<&str>>()[..]
+    {
+        request.auth = Auth {
+            kind: AuthKind::BASIC,
+            username: username.to_string(),
+            password: password.to_string(),
+            ..Default::default()
+        };
+        request.headers.remove(index);
+    }
+
+    let body = lines.collect::<Vec<&str>>().join("\n").trim().to_string();
+    set_body(&mut request, substitute(&body, variables));
+
+    Ok(Some(request))
+}
@@ -0,0 +1,80 @@
+use crate::utils::{
+    auth::Auth,
+    formats::ParsedRequests,
+    request::{Address, ContentType, Method, Request},
+};
+
+/// The portable representation of a request, as written by `ExportType::JSON`
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, rename_all = "camelCase")]
+struct JsonRequest {
+    name: String,
+    method: Method,
+    content_type: ContentType,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    params: Vec<(String, String)>,
+
+    /// The credentials, with those typed in replaced by placeholders, files written before it have none
+    auth: Auth,
+}
+
+impl From<Request> for JsonRequest {
+    fn from(request: Request) -> Self {
+        Self {
+            name: request.name,
+            method: request.method,
+            content_type: request.content_type,
+            uri: request.address.uri,
+            headers: request.headers,
+            body: request.body,
+            params: request.params,
+            auth: request.auth.masked(),
+        }
+    }
+}
+
+impl From<JsonRequest> for Request {
+    fn from(request: JsonRequest) -> Self {
+        Self {
+            name: request.name,
+            method: request.method,
+            content_type: request.content_type,
+            address: Address {
+                uri: request.uri,
+                ..Default::default()
+            },
+            headers: request.headers,
+            body: request.body,
+            params: request.params,
+            auth: request.auth,
+            ..Default::default()
+        }
+    }
+}
+
+/// Serialise the requests into a pretty printed JSON array
+pub fn serialize(requests: &[Request]) -> String {
+    let json_requests: Vec<JsonRequest> = requests.iter().cloned().map(JsonRequest::from).collect();
+
+    serde_json::to_string_pretty(&json_requests).unwrap_or_default()
+}
+
+/// Parse a JSON array of requests, each entry is validated separately
+pub fn parse(contents: &str) -> Result<ParsedRequests, String> {
+    let entries = serde_json::from_str::<Vec<serde_json::Value>>(contents)
+        .map_err(|error| format!("The file is not a JSON array of requests - {error}"))?;
+
+    let mut parsed = ParsedRequests::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        parsed.push(
+            index + 1,
+            serde_json::from_value::<JsonRequest>(entry)
+                .map(Request::from)
+                .map_err(|error| error.to_string()),
+        );
+    }
+
+    Ok(parsed)
+}
@@ -0,0 +1,204 @@
+pub mod curl;
+pub mod har;
+pub mod http;
+pub mod json;
+pub mod openapi;
+pub mod postman;
+
+use std::str::FromStr;
+
+use crate::{
+    ui::widgets::notification::{Notification, NotificationKind},
+    utils::request::{ContentType, Request},
+};
+
+/// The requests read from an imported file, alongside the entries that were skipped
+#[derive(Default)]
+pub struct ParsedRequests {
+    /// The requests that were parsed and validated successfully
+    pub requests: Vec<Request>,
+
+    /// A message per entry that could not be imported
+    pub errors: Vec<String>,
+
+    /// A message for each unsupported feature that was ignored whilst importing
+    pub warnings: Vec<String>,
+}
+
+impl ParsedRequests {
+    /// Validate the request before adding it, recording an error against the entry if it's invalid
+    pub fn push(&mut self, entry: impl std::fmt::Display, request: Result<Request, String>) {
+        match request.and_then(|request| validate(&request).map(|_| request)) {
+            Ok(request) => self.requests.push(request),
+            Err(error) => self.errors.push(format!("Entry {entry}: {error}")),
+        }
+    }
+
+    /// Summarise the import as a notification
+    pub fn notification(&self) -> Notification {
+        let mut message = format!("Imported {} request(s).", self.requests.len());
+
+        if !self.errors.is_empty() {
+            message += &format!(
+                " Skipped {} - {}.",
+                self.errors.len(),
+                self.errors.join("; ")
+            );
+        }
+
+        if !self.warnings.is_empty() {
+            message += &format!(" Ignored - {}.", self.warnings.join("; "));
+        }
+
+        let kind = if self.requests.is_empty() && !self.errors.is_empty() {
+            NotificationKind::ERROR
+        } else if !self.errors.is_empty() || !self.warnings.is_empty() {
+            NotificationKind::WARN
+        } else {
+            NotificationKind::INFO
+        };
+
+        Notification::new(message, kind)
+    }
+}
+
+/// Replace each `{{name}}` with the value of the variable, unknown variables are left in place
+pub fn substitute(text: &str, variables: &[(String, String)]) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+
+        let name = rest[start + 2..start + end].trim();
+        output.push_str(&rest[..start]);
+
+        match variables.iter().find(|(variable, _)| variable == name) {
+            Some((_, value)) => output.push_str(value),
+            None => output.push_str(&rest[start..start + end + 2]),
+        }
+
+        rest = &rest[start + end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Check that a parsed request could be sent
+pub fn validate(request: &Request) -> Result<(), String> {
+    if request.address.uri.trim().is_empty() {
+        return Err("The request has no URI.".to_string());
+    }
+
+    // Placeholders, e.g. `{{baseUrl}}/users`, are filled in before the request is sent
+    if !request.address.uri.contains("{{")
+        && let Err(error) = reqwest::Url::parse(&request.address.uri)
+    {
+        return Err(format!("Invalid URI {} - {error}", request.address.uri));
+    }
+
+    for (name, _) in &request.headers {
+        if reqwest::header::HeaderName::from_str(name).is_err() {
+            return Err(format!("Invalid header name \"{name}\"."));
+        }
+    }
+
+    Ok(())
+}
+
+/// Find the value of the Content-Type header
+pub(crate) fn content_type_header(request: &Request) -> Option<String> {
+    request
+        .headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.to_string())
+}
+
+/// The request's headers and those sent by its authentication, which replace headers of the same name
+pub(crate) fn request_headers(request: &Request) -> Vec<(String, String)> {
+    let auth = request.auth.headers();
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .filter(|(name, _)| !auth.iter().any(|(auth, _)| auth.eq_ignore_ascii_case(name)))
+        .cloned()
+        .collect();
+
+    headers.extend(auth);
+    headers
+}
+
+/// Set the content type and the body/params of the request from the raw body
+pub(crate) fn set_body(request: &mut Request, body: String) {
+    let mime = content_type_header(request).unwrap_or_default();
+
+    request.content_type = match ContentType::from_mime(&mime) {
+        ContentType::EMPTY if body.is_empty() => ContentType::EMPTY,
+        ContentType::EMPTY => ContentType::TEXT,
+        content_type => content_type,
+    };
+
+    match request.content_type {
+        ContentType::EMPTY => {}
+        ContentType::XWWWFORMURLENCODED => {
+            // Fields may be split across lines, e.g. `&name=value` on each line
+            let body: String = body.lines().map(str::trim).collect();
+            request.params = url::form_urlencoded::parse(body.as_bytes())
+                .into_owned()
+                .collect();
+        }
+        ContentType::MULTIPART => {
+            request.params = parse_multipart(&mime, &body);
+        }
+        ContentType::JSON | ContentType::TEXT => request.body = Some(body),
+    }
+
+    // The Content-Type header is written from the content type when serialising, and the form
+    // content types are encoded, with their header set, when the request is sent
+    let written = match request.content_type {
+        ContentType::JSON | ContentType::TEXT => {
+            mime.eq_ignore_ascii_case(&request.content_type.to_string())
+        }
+        _ => true,
+    };
+
+    if written {
+        request
+            .headers
+            .retain(|(name, _)| !name.eq_ignore_ascii_case("content-type"));
+    }
+}
+
+/// Read the text fields from a multipart body
+fn parse_multipart(mime: &str, body: &str) -> Vec<(String, String)> {
+    let Some(boundary) = mime.split(';').find_map(|parameter| {
+        parameter
+            .trim()
+            .strip_prefix("boundary=")
+            .map(|boundary| boundary.trim_matches('"'))
+    }) else {
+        return Vec::new();
+    };
+
+    let body = body.replace("\r\n", "\n");
+    body.split(&format!("--{boundary}"))
+        .filter_map(|part| {
+            let (headers, value) = part.trim_start_matches('\n').split_once("\n\n")?;
+            let name = headers.lines().find_map(|header| {
+                let (_, disposition) = header.split_once(':')?;
+                disposition.split(';').find_map(|parameter| {
+                    parameter
+                        .trim()
+                        .strip_prefix("name=")
+                        .map(|name| name.trim_matches('"').to_string())
+                })
+            })?;
+
+            Some((name, value.trim_end_matches('\n').to_string()))
+        })
+        .collect()
+}
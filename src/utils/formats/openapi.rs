@@ -0,0 +1,542 @@
+//! OpenAPI 3.x and Swagger 2.0 documents, written as JSON or YAML
+//!
+//! Each operation becomes a request template, path parameters and values without an example are
+//! written as `{{name}}` placeholders.
+
+use serde_json::{Map, Value, json};
+
+use crate::utils::{
+    formats::ParsedRequests,
+    request::{Address, ContentType, Method, Request},
+};
+
+/// The operations that can be imported, in the order they're read from each path
+const METHODS: [(&str, Method); 5] = [
+    ("get", Method::GET),
+    ("post", Method::POST),
+    ("put", Method::PUT),
+    ("patch", Method::PATCH),
+    ("delete", Method::DELETE),
+];
+
+/// The operations that can't be sent
+const UNSUPPORTED_METHODS: [&str; 3] = ["head", "options", "trace"];
+
+/// How deep schemas are followed when generating examples, recursive schemas are cut off here
+const MAX_DEPTH: usize = 8;
+
+/// Whether the JSON contents are an OpenAPI or Swagger document
+pub fn is_document(contents: &str) -> bool {
+    serde_json::from_str::<Value>(contents).is_ok_and(|value| {
+        (value.get("openapi").is_some() || value.get("swagger").is_some())
+            && value.get("paths").is_some_and(Value::is_object)
+    })
+}
+
+/// The specification the document is written against
+#[derive(PartialEq)]
+enum Version {
+    Swagger,
+    OpenApi,
+}
+
+/// The document, used to resolve references
+struct Document<'a> {
+    root: &'a Value,
+    version: Version,
+}
+
+impl<'a> Document<'a> {
+    /// Follow a local `$ref`, e.g. `#/components/schemas/User`, returning the value if it isn't one
+    fn resolve(&self, value: &'a Value, warnings: &mut Vec<String>) -> &'a Value {
+        let mut value = value;
+
+        // References may point at other references
+        for _ in 0..MAX_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return value;
+            };
+
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => value = target,
+                None => {
+                    let warning = format!("the external reference \"{reference}\"");
+                    if !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                    return &Value::Null;
+                }
+            }
+        }
+
+        value
+    }
+}
+
+/// Parse an OpenAPI or Swagger document, creating a request per operation
+pub fn parse(contents: &str) -> Result<ParsedRequests, String> {
+    let root: Value = match serde_json::from_str(contents) {
+        Ok(root) => root,
+        Err(_) => serde_yaml::from_str(contents)
+            .map_err(|error| format!("The file is not an OpenAPI or Swagger document - {error}"))?,
+    };
+
+    let version = if root["openapi"].as_str().is_some_and(|v| v.starts_with('3')) {
+        Version::OpenApi
+    } else if root["swagger"].as_str() == Some("2.0") {
+        Version::Swagger
+    } else {
+        return Err("The document is not OpenAPI 3.x or Swagger 2.0.".to_string());
+    };
+
+    let document = Document {
+        root: &root,
+        version,
+    };
+
+    let mut parsed = ParsedRequests::default();
+    let base_url = base_url(&document);
+
+    let Some(paths) = root["paths"].as_object() else {
+        return Err("The document has no paths.".to_string());
+    };
+
+    for (path, item) in paths {
+        let item = document.resolve(item, &mut parsed.warnings);
+
+        for method in UNSUPPORTED_METHODS {
+            if item.get(method).is_some() {
+                parsed.warnings.push(format!(
+                    "the {} operation on \"{path}\"",
+                    method.to_uppercase()
+                ));
+            }
+        }
+
+        for (key, method) in METHODS {
+            let Some(operation) = item.get(key) else {
+                continue;
+            };
+
+            let name = operation_name(path, &method, operation);
+            let request = Request {
+                name: name.to_string(),
+                method,
+                ..Default::default()
+            };
+            let request = parse_operation(
+                &document,
+                request,
+                &base_url,
+                path,
+                item,
+                operation,
+                &mut parsed.warnings,
+            );
+            parsed.push(format!("\"{name}\""), Ok(request));
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// The URL requests are made against, `{{baseUrl}}` is used if the document doesn't have one
+fn base_url(document: &Document) -> String {
+    let root = document.root;
+
+    let url = match document.version {
+        Version::OpenApi => {
+            let server = &root["servers"][0];
+            let mut url = server["url"].as_str().unwrap_or_default().to_string();
+
+            // Server variables are replaced by their defaults
+            if let Some(variables) = server["variables"].as_object() {
+                for (name, variable) in variables {
+                    let default = variable["default"].as_str().unwrap_or_default();
+                    url = url.replace(&format!("{{{name}}}"), default);
+                }
+            }
+
+            url
+        }
+        Version::Swagger => match root["host"].as_str() {
+            Some(host) => format!(
+                "{}://{host}{}",
+                root["schemes"][0].as_str().unwrap_or("https"),
+                root["basePath"].as_str().unwrap_or_default()
+            ),
+            None => root["basePath"].as_str().unwrap_or_default().to_string(),
+        },
+    };
+
+    // Relative URLs are resolved against wherever the document is hosted
+    let url = if url.contains("://") {
+        url
+    } else {
+        format!("{{{{baseUrl}}}}{url}")
+    };
+
+    url.trim_end_matches('/').to_string()
+}
+
+/// Name the request after the operation, in a folder named after its first tag
+fn operation_name(path: &str, method: &Method, operation: &Value) -> String {
+    let name = operation["summary"]
+        .as_str()
+        .or(operation["operationId"].as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{} {path}", method.to_string()));
+
+    match operation["tags"][0].as_str() {
+        Some(tag) => format!("{tag}/{}", name.replace('/', "-")),
+        None => name,
+    }
+}
+
+/// The value to use for a parameter, or a placeholder if it has no example
+fn parameter_value(document: &Document, parameter: &Value, warnings: &mut Vec<String>) -> String {
+    let name = parameter["name"].as_str().unwrap_or_default();
+    let schema = document.resolve(&parameter["schema"], warnings);
+
+    let example = [
+        &parameter["example"],
+        &parameter["x-example"],
+        &parameter["default"],
+        &schema["example"],
+        &schema["default"],
+    ]
+    .into_iter()
+    .find(|example| !example.is_null());
+
+    match example {
+        Some(Value::String(example)) => example.to_string(),
+        Some(example) => example.to_string(),
+        None => format!("{{{{{name}}}}}"),
+    }
+}
+
+/// Build the request for an operation
+fn parse_operation(
+    document: &Document,
+    mut request: Request,
+    base_url: &str,
+    path: &str,
+    item: &Value,
+    operation: &Value,
+    warnings: &mut Vec<String>,
+) -> Request {
+    // Operation parameters override those shared by the path
+    let mut parameters: Vec<&Value> = Vec::new();
+    for parameter in item["parameters"]
+        .as_array()
+        .into_iter()
+        .chain(operation["parameters"].as_array())
+        .flatten()
+    {
+        let parameter = document.resolve(parameter, warnings);
+        parameters.retain(|existing| {
+            existing["name"] != parameter["name"] || existing["in"] != parameter["in"]
+        });
+        parameters.push(parameter);
+    }
+
+    let mut uri = base_url.to_string() + path;
+    let mut query: Vec<(String, String)> = Vec::new();
+    let mut form: Vec<(String, String)> = Vec::new();
+    let mut has_file = false;
+
+    for parameter in &parameters {
+        let name = parameter["name"].as_str().unwrap_or_default();
+        let required = parameter["required"].as_bool().unwrap_or(false);
+
+        match parameter["in"].as_str().unwrap_or_default() {
+            "path" => uri = uri.replace(&format!("{{{name}}}"), &format!("{{{{{name}}}}}")),
+            "query" if required => query.push((
+                name.to_string(),
+                parameter_value(document, parameter, warnings),
+            )),
+            "header" if required => request.headers.push((
+                name.to_string(),
+                parameter_value(document, parameter, warnings),
+            )),
+            // Swagger 2.0 describes the body as a parameter
+            "body" => {
+                let schema = document.resolve(&parameter["schema"], warnings);
+                let example = example(document, schema, 0, warnings);
+                request.content_type = ContentType::JSON;
+                request.body = Some(serde_json::to_string_pretty(&example).unwrap_or_default());
+            }
+            "formData" if parameter["type"] == "file" => {
+                has_file = true;
+                warnings.push(format!("file field \"{name}\" on \"{}\"", request.name));
+            }
+            "formData" => form.push((
+                name.to_string(),
+                parameter_value(document, parameter, warnings),
+            )),
+            _ => {}
+        }
+    }
+
+    if !query.is_empty() {
+        // Placeholders are kept readable rather than being encoded
+        let query: Vec<String> = query
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        uri = format!("{uri}?{}", query.join("&"));
+    }
+
+    request.address = Address {
+        uri,
+        ..Default::default()
+    };
+
+    if !form.is_empty() || has_file {
+        let consumes = operation["consumes"]
+            .as_array()
+            .or(document.root["consumes"].as_array());
+        let multipart = has_file
+            || consumes.is_some_and(|consumes| {
+                consumes
+                    .iter()
+                    .any(|mime| mime.as_str() == Some("multipart/form-data"))
+            });
+
+        request.content_type = if multipart {
+            ContentType::MULTIPART
+        } else {
+            ContentType::XWWWFORMURLENCODED
+        };
+        request.params = form;
+    }
+
+    if document.version == Version::OpenApi {
+        let body = document.resolve(&operation["requestBody"], warnings);
+        if let Some(content) = body["content"].as_object() {
+            set_body(document, &mut request, content, warnings);
+        }
+    }
+
+    set_security(document, &mut request, operation);
+
+    request
+}
+
+/// Set the body from the preferred media type of an OpenAPI 3 request body
+fn set_body(
+    document: &Document,
+    request: &mut Request,
+    content: &Map<String, Value>,
+    warnings: &mut Vec<String>,
+) {
+    let preferred = |content_type: ContentType| {
+        content
+            .iter()
+            .find(|(mime, _)| ContentType::from_mime(mime) == content_type)
+    };
+
+    let Some((mime, media)) = preferred(ContentType::JSON)
+        .or_else(|| preferred(ContentType::XWWWFORMURLENCODED))
+        .or_else(|| preferred(ContentType::MULTIPART))
+        .or_else(|| content.iter().next())
+    else {
+        return;
+    };
+
+    let schema = document.resolve(&media["schema"], warnings);
+    let example = if media["example"].is_null() {
+        media["examples"]
+            .as_object()
+            .and_then(|examples| examples.values().next())
+            .map(|example| document.resolve(example, warnings)["value"].clone())
+            .filter(|example| !example.is_null())
+            .unwrap_or_else(|| example(document, schema, 0, warnings))
+    } else {
+        media["example"].clone()
+    };
+
+    request.content_type = ContentType::from_mime(mime);
+    match request.content_type {
+        ContentType::EMPTY => {}
+        ContentType::JSON => {
+            request.body = Some(serde_json::to_string_pretty(&example).unwrap_or_default());
+        }
+        ContentType::XWWWFORMURLENCODED | ContentType::MULTIPART => {
+            let properties = schema_properties(document, schema, warnings);
+            request.params = example
+                .as_object()
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| {
+                    let property = properties.get(name.as_str()).unwrap_or(&Value::Null);
+                    let is_file = property["format"] == "binary" || property["format"] == "base64";
+                    if is_file {
+                        warnings.push(format!("file field \"{name}\" on \"{}\"", request.name));
+                    }
+                    !is_file
+                })
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::String(value) => value.to_string(),
+                        value => value.to_string(),
+                    };
+                    (name.to_string(), value)
+                })
+                .collect();
+        }
+        ContentType::TEXT => {
+            request
+                .headers
+                .push(("Content-Type".to_string(), mime.to_string()));
+            request.body = Some(match example {
+                Value::String(example) => example,
+                Value::Null => String::new(),
+                example => example.to_string(),
+            });
+        }
+    }
+}
+
+/// Add a placeholder for each credential the operation requires
+fn set_security(document: &Document, request: &mut Request, operation: &Value) {
+    let root = document.root;
+    let schemes = match document.version {
+        Version::OpenApi => &root["components"]["securitySchemes"],
+        Version::Swagger => &root["securityDefinitions"],
+    };
+
+    // The operation's requirements replace the document's, an empty list removes them
+    let requirements = operation["security"]
+        .as_array()
+        .or(root["security"].as_array());
+
+    let Some(requirement) = requirements.and_then(|requirements| requirements.first()) else {
+        return;
+    };
+
+    for name in requirement.as_object().into_iter().flat_map(Map::keys) {
+        let scheme = &schemes[name];
+        let header = match (scheme["type"].as_str(), scheme["scheme"].as_str()) {
+            (Some("apiKey"), _) if scheme["in"] == "header" => {
+                let header = scheme["name"].as_str().unwrap_or_default();
+                Some((header.to_string(), format!("{{{{{header}}}}}")))
+            }
+            (Some("http"), Some(scheme)) if scheme.eq_ignore_ascii_case("bearer") => {
+                Some(("Authorization".to_string(), "Bearer {{token}}".to_string()))
+            }
+            (Some("http"), Some(scheme)) if scheme.eq_ignore_ascii_case("basic") => Some((
+                "Authorization".to_string(),
+                "Basic {{credentials}}".to_string(),
+            )),
+            (Some("basic"), _) => Some((
+                "Authorization".to_string(),
+                "Basic {{credentials}}".to_string(),
+            )),
+            (Some("oauth2" | "openIdConnect"), _) => {
+                Some(("Authorization".to_string(), "Bearer {{token}}".to_string()))
+            }
+            _ => None,
+        };
+
+        if let Some((name, value)) = header
+            && !request
+                .headers
+                .iter()
+                .any(|(existing, _)| existing.eq_ignore_ascii_case(&name))
+        {
+            request.headers.push((name, value));
+        }
+    }
+}
+
+/// The properties of an object schema, including those it inherits through `allOf`
+fn schema_properties<'a>(
+    document: &Document<'a>,
+    schema: &'a Value,
+    warnings: &mut Vec<String>,
+) -> Map<String, Value> {
+    let mut properties = schema["properties"]
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+
+    for part in schema["allOf"].as_array().into_iter().flatten() {
+        let part = document.resolve(part, warnings);
+        properties.extend(schema_properties(document, part, warnings));
+    }
+
+    properties
+}
+
+/// Generate an example value from a schema
+fn example(document: &Document, schema: &Value, depth: usize, warnings: &mut Vec<String>) -> Value {
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+
+    let schema = document.resolve(schema, warnings);
+
+    for key in ["example", "default"] {
+        if !schema[key].is_null() {
+            return schema[key].clone();
+        }
+    }
+
+    if let Some(first) = schema["enum"].as_array().and_then(|values| values.first()) {
+        return first.clone();
+    }
+
+    if let Some(first) = schema["oneOf"]
+        .as_array()
+        .or(schema["anyOf"].as_array())
+        .and_then(|schemas| schemas.first())
+    {
+        return example(document, first, depth + 1, warnings);
+    }
+
+    let kind = match &schema["type"] {
+        Value::String(kind) => kind.as_str(),
+        // OpenAPI 3.1 allows a list of types, e.g. ["string", "null"]
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| *kind != "null")
+            .unwrap_or_default(),
+        _ if !schema["properties"].is_null() || !schema["allOf"].is_null() => "object",
+        _ => "",
+    };
+
+    match kind {
+        "object" => {
+            let mut object = Map::new();
+            for part in schema["allOf"].as_array().into_iter().flatten() {
+                if let Value::Object(part) = example(document, part, depth + 1, warnings) {
+                    object.extend(part);
+                }
+            }
+            for (name, property) in schema["properties"].as_object().into_iter().flatten() {
+                object.insert(
+                    name.to_string(),
+                    example(document, property, depth + 1, warnings),
+                );
+            }
+            Value::Object(object)
+        }
+        "array" => json!([example(document, &schema["items"], depth + 1, warnings)]),
+        "string" => json!(match schema["format"].as_str().unwrap_or_default() {
+            "date-time" => "2025-01-01T00:00:00Z",
+            "date" => "2025-01-01",
+            "email" => "user@example.com",
+            "uuid" => "00000000-0000-0000-0000-000000000000",
+            "uri" | "url" => "https://example.com",
+            _ => "string",
+        }),
+        "integer" | "number" => json!(0),
+        "boolean" => json!(true),
+        _ => Value::Null,
+    }
+}
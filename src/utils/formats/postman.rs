@@ -0,0 +1,569 @@
+//! Postman Collection v2.1 files, see https://schema.postman.com/collection/json/v2.1.0/draft-07/docs/index.html
+
+use std::str::FromStr;
+
+use serde_json::{Value, json};
+
+use crate::utils::{
+    auth::AuthKind,
+    formats::{ParsedRequests, request_headers, substitute},
+    request::{Address, ContentType, Method, Request},
+};
+
+/// The schema written to, and expected from, collection files
+pub const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// The root of a Postman collection
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Collection {
+    info: Info,
+    item: Vec<Item>,
+    variable: Vec<KeyValue>,
+    auth: Option<Auth>,
+    event: Vec<Value>,
+}
+
+/// The collection's description
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Info {
+    name: String,
+    schema: String,
+}
+
+/// A folder, containing further items, or a single request
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Item {
+    name: String,
+    item: Option<Vec<Item>>,
+    request: Option<RequestDefinition>,
+    auth: Option<Auth>,
+    event: Vec<Value>,
+}
+
+/// A request can be written as only its URL
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum RequestDefinition {
+    Url(String),
+    Request(Box<PostmanRequest>),
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct PostmanRequest {
+    method: String,
+    header: Vec<KeyValue>,
+    url: Option<Url>,
+    body: Option<Body>,
+    auth: Option<Auth>,
+}
+
+/// The URL may be a plain string or broken down into its components
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Url {
+    Raw(String),
+    Parts(UrlParts),
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct UrlParts {
+    raw: String,
+    protocol: String,
+    host: Value,
+    port: String,
+    path: Value,
+    query: Vec<KeyValue>,
+}
+
+/// A key/value pair, used by headers, variables, query params and form fields
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct KeyValue {
+    key: String,
+    value: Value,
+    disabled: bool,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+impl KeyValue {
+    /// The value as a string, non-string values are written as JSON
+    fn value(&self) -> String {
+        match &self.value {
+            Value::Null => String::new(),
+            Value::String(value) => value.to_string(),
+            value => value.to_string(),
+        }
+    }
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Body {
+    mode: String,
+    raw: String,
+    urlencoded: Vec<KeyValue>,
+    formdata: Vec<KeyValue>,
+    options: Value,
+    disabled: bool,
+}
+
+/// The auth settings, the attributes are keyed by the auth type e.g. `basic` or `bearer`
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(flatten)]
+    attributes: serde_json::Map<String, Value>,
+}
+
+impl Auth {
+    /// Find an attribute of the auth type, supporting both the v2.1 list and v2.0 object layouts
+    fn attribute(&self, key: &str) -> String {
+        let value = match self.attributes.get(&self.kind) {
+            Some(Value::Array(attributes)) => attributes
+                .iter()
+                .find(|attribute| attribute.get("key").and_then(Value::as_str) == Some(key))
+                .and_then(|attribute| attribute.get("value")),
+            Some(Value::Object(attributes)) => attributes.get(key),
+            _ => None,
+        };
+
+        match value {
+            Some(Value::String(value)) => value.to_string(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Whether the contents look like a Postman collection rather than a list of requests
+pub fn is_collection(contents: &str) -> bool {
+    serde_json::from_str::<Value>(contents).is_ok_and(|value| {
+        value.get("info").is_some() && value.get("item").is_some_and(Value::is_array)
+    })
+}
+
+/// Parse a Postman collection, each request is named after the folders that contain it
+pub fn parse(contents: &str) -> Result<ParsedRequests, String> {
+    let collection = serde_json::from_str::<Collection>(contents)
+        .map_err(|error| format!("The file is not a Postman collection - {error}"))?;
+
+    let mut parsed = ParsedRequests::default();
+
+    if !collection.info.schema.contains("v2.1") {
+        parsed.warnings.push(format!(
+            "the collection schema \"{}\" is not v2.1, it was read as v2.1",
+            collection.info.schema
+        ));
+    }
+
+    if !collection.event.is_empty() {
+        parsed
+            .warnings
+            .push(format!("scripts on \"{}\"", collection.info.name));
+    }
+
+    let variables: Vec<(String, String)> = collection
+        .variable
+        .iter()
+        .filter(|variable| !variable.disabled)
+        .map(|variable| (variable.key.to_string(), variable.value()))
+        .collect();
+
+    let context = Context {
+        variables: &variables,
+        path: Vec::new(),
+        auth: collection.auth.as_ref(),
+    };
+
+    for item in &collection.item {
+        parse_item(item, &context, &mut parsed);
+    }
+
+    Ok(parsed)
+}
+
+/// The state inherited from the collection and the folders above an item
+struct Context<'a> {
+    variables: &'a [(String, String)],
+    path: Vec<&'a str>,
+    auth: Option<&'a Auth>,
+}
+
+/// Parse a folder or request, recursing into folders
+fn parse_item<'a>(item: &'a Item, context: &Context<'a>, parsed: &mut ParsedRequests) {
+    let mut path = context.path.clone();
+    path.push(&item.name);
+    let name = path.join("/");
+
+    if !item.event.is_empty() {
+        parsed.warnings.push(format!("scripts on \"{name}\""));
+    }
+
+    let auth = item.auth.as_ref().or(context.auth);
+
+    if let Some(items) = &item.item {
+        let context = Context {
+            variables: context.variables,
+            path,
+            auth,
+        };
+
+        for item in items {
+            parse_item(item, &context, parsed);
+        }
+    } else if let Some(definition) = &item.request {
+        let request = match definition {
+            RequestDefinition::Url(uri) => Ok(Request {
+                address: Address {
+                    uri: substitute(uri, context.variables),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            RequestDefinition::Request(request) => {
+                let auth = request.auth.as_ref().or(auth);
+                parse_request(
+                    request,
+                    auth,
+                    context.variables,
+                    &name,
+                    &mut parsed.warnings,
+                )
+            }
+        };
+
+        parsed.push(
+            format!("\"{name}\""),
+            request.map(|request| Request {
+                name: name.to_string(),
+                ..request
+            }),
+        );
+    }
+}
+
+/// Map a Postman request onto a request
+fn parse_request(
+    postman: &PostmanRequest,
+    auth: Option<&Auth>,
+    variables: &[(String, String)],
+    name: &str,
+    warnings: &mut Vec<String>,
+) -> Result<Request, String> {
+    let mut request = Request {
+        method: if postman.method.is_empty() {
+            Method::GET
+        } else {
+            Method::from_str(&postman.method)?
+        },
+        address: Address {
+            uri: substitute(&url(postman.url.as_ref()), variables),
+            ..Default::default()
+        },
+        headers: postman
+            .header
+            .iter()
+            .filter(|header| !header.disabled)
+            .map(|header| {
+                (
+                    header.key.to_string(),
+                    substitute(&header.value(), variables),
+                )
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    if let Some(auth) = auth {
+        match auth.kind.as_str() {
+            "noauth" => {}
+            // Encoded when the request is sent, once any placeholders left have been filled in
+            "basic" => {
+                request.auth = crate::utils::auth::Auth {
+                    kind: AuthKind::BASIC,
+                    username: substitute(&auth.attribute("username"), variables),
+                    password: substitute(&auth.attribute("password"), variables),
+                    ..Default::default()
+                };
+            }
+            "bearer" => request.headers.push((
+                "Authorization".to_string(),
+                format!("Bearer {}", substitute(&auth.attribute("token"), variables)),
+            )),
+            kind => warnings.push(format!("{kind} auth on \"{name}\"")),
+        }
+    }
+
+    if let Some(body) = postman.body.as_ref().filter(|body| !body.disabled) {
+        let mut fields = |fields: &[KeyValue]| -> Vec<(String, String)> {
+            fields
+                .iter()
+                .filter(|field| !field.disabled)
+                .filter(|field| {
+                    let is_file = field.kind == "file";
+                    if is_file {
+                        warnings.push(format!("file field \"{}\" on \"{name}\"", field.key));
+                    }
+                    !is_file
+                })
+                .map(|field| (field.key.to_string(), substitute(&field.value(), variables)))
+                .collect()
+        };
+
+        match body.mode.as_str() {
+            "" => {}
+            "raw" => {
+                let language = body
+                    .options
+                    .pointer("/raw/language")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let mime = request
+                    .headers
+                    .iter()
+                    .find(|(header, _)| header.eq_ignore_ascii_case("content-type"))
+                    .map(|(_, value)| value.to_string())
+                    .unwrap_or_default();
+
+                request.content_type = if language == "json" {
+                    ContentType::JSON
+                } else {
+                    match ContentType::from_mime(&mime) {
+                        ContentType::JSON => ContentType::JSON,
+                        _ => ContentType::TEXT,
+                    }
+                };
+                request.body = Some(substitute(&body.raw, variables));
+            }
+            "urlencoded" => {
+                request.content_type = ContentType::XWWWFORMURLENCODED;
+                request.params = fields(&body.urlencoded);
+            }
+            "formdata" => {
+                request.content_type = ContentType::MULTIPART;
+                request.params = fields(&body.formdata);
+            }
+            mode => warnings.push(format!("{mode} body on \"{name}\"")),
+        }
+
+        // The form content types set their own header when the request is sent
+        if [ContentType::XWWWFORMURLENCODED, ContentType::MULTIPART].contains(&request.content_type)
+        {
+            request
+                .headers
+                .retain(|(header, _)| !header.eq_ignore_ascii_case("content-type"));
+        }
+    }
+
+    Ok(request)
+}
+
+/// Build the URL from its raw value, or from its parts if there isn't one
+fn url(url: Option<&Url>) -> String {
+    let parts = match url {
+        None => return String::new(),
+        Some(Url::Raw(raw)) => return raw.to_string(),
+        Some(Url::Parts(parts)) if !parts.raw.is_empty() => return parts.raw.to_string(),
+        Some(Url::Parts(parts)) => parts,
+    };
+
+    let join = |value: &Value, separator: &str| match value {
+        Value::String(value) => value.to_string(),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                Value::String(value) => value.to_string(),
+                value => value
+                    .get("value")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(separator),
+        _ => String::new(),
+    };
+
+    let mut uri = String::new();
+    if !parts.protocol.is_empty() {
+        uri += &format!("{}://", parts.protocol);
+    }
+    uri += &join(&parts.host, ".");
+    if !parts.port.is_empty() {
+        uri += &format!(":{}", parts.port);
+    }
+
+    let path = join(&parts.path, "/");
+    if !path.is_empty() {
+        uri += &format!("/{}", path.trim_start_matches('/'));
+    }
+
+    let query: Vec<String> = parts
+        .query
+        .iter()
+        .filter(|param| !param.disabled)
+        .map(|param| format!("{}={}", param.key, param.value()))
+        .collect();
+    if !query.is_empty() {
+        uri += &format!("?{}", query.join("&"));
+    }
+
+    uri
+}
+
+/// Serialise the requests into a Postman collection, names containing `/` are written as folders
+pub fn serialize(name: &str, requests: &[Request]) -> String {
+    let mut items: Vec<Value> = Vec::new();
+
+    for request in requests {
+        let (folders, item_name) = if request.name.is_empty() {
+            (
+                Vec::new(),
+                format!("{} {}", request.method.to_string(), request.address.uri),
+            )
+        } else {
+            let mut path: Vec<&str> = request.name.split('/').collect();
+            let item_name = path.pop().unwrap_or_default().to_string();
+            (path, item_name)
+        };
+
+        insert_item(&mut items, &folders, serialize_request(&item_name, request));
+    }
+
+    let collection = json!({
+        "info": {
+            "name": name,
+            "schema": SCHEMA,
+        },
+        "item": items,
+    });
+
+    serde_json::to_string_pretty(&collection).unwrap_or_default()
+}
+
+/// Add the item to the folder at the given path, creating the folders that don't exist yet
+fn insert_item(items: &mut Vec<Value>, folders: &[&str], item: Value) {
+    let Some((folder, folders)) = folders.split_first() else {
+        items.push(item);
+        return;
+    };
+
+    let position = items.iter().position(|existing| {
+        existing.get("name").and_then(Value::as_str) == Some(folder)
+            && existing.get("item").is_some_and(Value::is_array)
+    });
+
+    let position = position.unwrap_or_else(|| {
+        items.push(json!({ "name": folder, "item": [] }));
+        items.len() - 1
+    });
+
+    if let Some(Value::Array(children)) = items[position].get_mut("item") {
+        insert_item(children, folders, item);
+    }
+}
+
+/// Serialise a single request item
+fn serialize_request(name: &str, request: &Request) -> Value {
+    // Basic credentials are written as Postman's own auth, so placeholders in them can still be filled in
+    let basic = request.auth.kind == AuthKind::BASIC;
+    let headers: Vec<Value> = request_headers(request)
+        .iter()
+        .filter(|(key, _)| !(basic && key == "Authorization"))
+        .map(|(key, value)| json!({ "key": key, "value": value, "type": "text" }))
+        .collect();
+
+    let fields = |fields: &[(String, String)]| -> Vec<Value> {
+        fields
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": value, "type": "text" }))
+            .collect()
+    };
+
+    let body = match request.content_type {
+        ContentType::EMPTY => None,
+        ContentType::JSON | ContentType::TEXT => Some(json!({
+            "mode": "raw",
+            "raw": request.body.clone().unwrap_or_default(),
+            "options": {
+                "raw": {
+                    "language": if request.content_type == ContentType::JSON { "json" } else { "text" },
+                },
+            },
+        })),
+        ContentType::XWWWFORMURLENCODED => Some(json!({
+            "mode": "urlencoded",
+            "urlencoded": fields(&request.params),
+        })),
+        ContentType::MULTIPART => Some(json!({
+            "mode": "formdata",
+            "formdata": fields(&request.params),
+        })),
+    };
+
+    let mut postman_request = json!({
+        "method": request.method.to_string(),
+        "header": headers,
+        "url": serialize_url(&request.auth.uri(&request.address.uri)),
+    });
+
+    if let Some(body) = body {
+        postman_request["body"] = body;
+    }
+
+    if basic {
+        postman_request["auth"] = json!({
+            "type": "basic",
+            "basic": [
+                { "key": "username", "value": request.auth.username, "type": "string" },
+                { "key": "password", "value": request.auth.password, "type": "string" },
+            ],
+        });
+    }
+
+    json!({
+        "name": name,
+        "request": postman_request,
+    })
+}
+
+/// Serialise the URL into its parts, keeping the raw value that is read back on import
+fn serialize_url(uri: &str) -> Value {
+    let Ok(url) = reqwest::Url::parse(uri) else {
+        return json!({ "raw": uri });
+    };
+
+    let mut parts = json!({
+        "raw": uri,
+        "protocol": url.scheme(),
+        "host": url.host_str().unwrap_or_default().split('.').collect::<Vec<&str>>(),
+        "path": url
+            .path_segments()
+            .map(|segments| segments.filter(|segment| !segment.is_empty()).collect::<Vec<&str>>())
+            .unwrap_or_default(),
+    });
+
+    if let Some(port) = url.port() {
+        parts["port"] = json!(port.to_string());
+    }
+
+    if let Some(query) = url.query() {
+        parts["query"] = query
+            .split('&')
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                json!({ "key": key, "value": value })
+            })
+            .collect();
+    }
+
+    parts
+}
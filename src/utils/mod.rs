@@ -1,6 +1,19 @@
+pub mod auth;
 pub(crate) mod breakpoints;
-pub(crate) mod certificates;
-pub(crate) mod exports;
-pub(crate) mod request;
-pub(crate) mod reqwestur;
-pub(crate) mod traits;
+pub mod certificates;
+pub mod connection;
+pub mod cookies;
+pub mod engine;
+pub mod environments;
+pub mod exports;
+pub mod formats;
+pub mod oauth2;
+pub mod proxy;
+pub mod request;
+pub mod reqwestur;
+pub mod secrets;
+pub mod sigv4;
+pub mod snippets;
+pub mod tls;
+pub mod traits;
+pub mod uri;
@@ -0,0 +1,433 @@
+//! OAuth 2.0 access tokens (RFC 6749), fetched from the token endpoint before requests are sent
+//!
+//! The authorization code grant is protected with PKCE (RFC 7636) and receives the code on a
+//! loopback redirect, e.g. `http://127.0.0.1:8400/callback`.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use aes_gcm::aead::{OsRng, rand_core::RngCore};
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use sha2::Digest;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::utils::{auth::Auth, engine::prettify};
+
+/// Tokens are refreshed when they expire within this many seconds
+pub const EXPIRY_MARGIN: i64 = 30;
+
+/// The number of seconds to wait for the browser to return the authorization code
+pub const AUTHORIZATION_TIMEOUT: u64 = 300;
+
+/// The path the authorization code is redirected to
+const REDIRECT_PATH: &str = "/callback";
+
+/// The ways an access token can be granted
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+pub enum GrantType {
+    #[default]
+    CLIENTCREDENTIALS,
+    PASSWORD,
+    REFRESHTOKEN,
+    AUTHORIZATIONCODE,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for GrantType {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::CLIENTCREDENTIALS => "Client Credentials",
+            Self::PASSWORD => "Password",
+            Self::REFRESHTOKEN => "Refresh Token",
+            Self::AUTHORIZATIONCODE => "Authorization Code (PKCE)",
+        };
+
+        str.to_string()
+    }
+}
+
+impl GrantType {
+    const OPTIONS: [Self; 4] = [
+        Self::CLIENTCREDENTIALS,
+        Self::PASSWORD,
+        Self::REFRESHTOKEN,
+        Self::AUTHORIZATIONCODE,
+    ];
+
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+}
+
+/// How an access token is fetched, the password grant uses the username and password of the `Auth`
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct OAuth2 {
+    pub grant: GrantType,
+    pub token_url: String,
+
+    /// Where the browser is sent to authorise the authorization code grant
+    pub authorization_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+
+    /// The space separated scopes requested
+    pub scope: String,
+
+    /// The token exchanged by the refresh token grant
+    pub refresh_token: String,
+
+    /// The loopback port the authorization code is redirected to, any free port is used if 0
+    pub redirect_port: u16,
+
+    /// Send the client credentials as form fields rather than with Basic authentication
+    pub credentials_in_body: bool,
+}
+
+/// An access token from the token endpoint
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct Token {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+
+    /// When the token expires as a Unix timestamp, if the endpoint said
+    pub expires_at: Option<i64>,
+
+    /// The token endpoint's response, shown for debugging
+    pub response: String,
+}
+
+impl Token {
+    /// Whether the token has expired, or will within the margin
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at - EXPIRY_MARGIN <= chrono::Utc::now().timestamp())
+    }
+
+    /// When the token expires, for display
+    pub fn expiry(&self) -> String {
+        self.expires_at
+            .and_then(|expires| chrono::DateTime::from_timestamp(expires, 0))
+            .map(|expires| expires.format("%d/%m/%Y %H:%M:%S UTC").to_string())
+            .unwrap_or("Unknown".to_string())
+    }
+}
+
+/// The tokens fetched in an environment, shared with the requests being sent
+///
+/// Tokens are only kept in memory, they're fetched again once the application restarts.
+#[derive(Default, Clone)]
+pub struct TokenCache(Arc<Mutex<BTreeMap<String, Token>>>);
+
+impl TokenCache {
+    pub fn get(&self, auth: &Auth) -> Option<Token> {
+        self.0.lock().unwrap().get(&key(auth)).cloned()
+    }
+
+    pub fn insert(&self, auth: &Auth, token: Token) {
+        self.0.lock().unwrap().insert(key(auth), token);
+    }
+
+    pub fn remove(&self, auth: &Auth) {
+        self.0.lock().unwrap().remove(&key(auth));
+    }
+}
+
+impl PartialEq for TokenCache {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for TokenCache {}
+
+/// Tokens are cached per grant, client, scope and user
+fn key(auth: &Auth) -> String {
+    let oauth2 = &auth.oauth2;
+    [
+        oauth2.grant.to_string().as_str(),
+        &oauth2.token_url,
+        &oauth2.client_id,
+        &oauth2.scope,
+        &auth.username,
+    ]
+    .join("\n")
+}
+
+/// The cached token, refreshed or fetched again if it has expired
+///
+/// The browser is asked to open the authorization URL for the authorization code grant.
+pub async fn token(
+    client: &reqwest::Client,
+    auth: &Auth,
+    cache: &TokenCache,
+    browser: &(dyn Fn(&str) + Sync),
+) -> Result<Token, String> {
+    let cached = cache.get(auth);
+    if let Some(token) = &cached
+        && !token.is_expired()
+    {
+        return Ok(token.clone());
+    }
+
+    // An expired token is refreshed if it can be, otherwise the grant is repeated
+    let refreshed = match cached.and_then(|token| token.refresh_token) {
+        Some(refresh_token) => refresh(client, auth, &refresh_token).await.ok(),
+        None => None,
+    };
+
+    let token = match refreshed {
+        Some(token) => token,
+        None => fetch(client, auth, browser).await?,
+    };
+
+    cache.insert(auth, token.clone());
+    Ok(token)
+}
+
+/// Fetch a new token with the configured grant, ignoring any cached token
+pub async fn fetch(
+    client: &reqwest::Client,
+    auth: &Auth,
+    browser: &(dyn Fn(&str) + Sync),
+) -> Result<Token, String> {
+    let oauth2 = &auth.oauth2;
+    let scope = ("scope", oauth2.scope.as_str());
+
+    match oauth2.grant {
+        GrantType::CLIENTCREDENTIALS => {
+            request(
+                client,
+                oauth2,
+                vec![("grant_type", "client_credentials"), scope],
+            )
+            .await
+        }
+        GrantType::PASSWORD => {
+            let fields = vec![
+                ("grant_type", "password"),
+                ("username", auth.username.as_str()),
+                ("password", auth.password.as_str()),
+                scope,
+            ];
+            request(client, oauth2, fields).await
+        }
+        GrantType::REFRESHTOKEN => refresh(client, auth, &oauth2.refresh_token).await,
+        GrantType::AUTHORIZATIONCODE => authorization_code(client, oauth2, browser).await,
+    }
+}
+
+/// Exchange the refresh token for a new token, keeping the refresh token if another isn't issued
+async fn refresh(
+    client: &reqwest::Client,
+    auth: &Auth,
+    refresh_token: &str,
+) -> Result<Token, String> {
+    let fields = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("scope", auth.oauth2.scope.as_str()),
+    ];
+
+    let mut token = request(client, &auth.oauth2, fields).await?;
+    token
+        .refresh_token
+        .get_or_insert_with(|| refresh_token.to_string());
+
+    Ok(token)
+}
+
+/// Send the browser to authorise the client, then exchange the code it's redirected with
+async fn authorization_code(
+    client: &reqwest::Client,
+    oauth2: &OAuth2,
+    browser: &(dyn Fn(&str) + Sync),
+) -> Result<Token, String> {
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", oauth2.redirect_port))
+        .await
+        .map_err(|error| format!("Could not listen for the redirect - {error}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|error| error.to_string())?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{REDIRECT_PATH}");
+
+    let verifier = random();
+    let challenge = URL_SAFE_NO_PAD.encode(sha2::Sha256::digest(&verifier));
+    let state = random();
+
+    let mut url = url::Url::parse(&oauth2.authorization_url)
+        .map_err(|error| format!("The authorization URL is invalid - {error}"))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &oauth2.client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", &oauth2.scope)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256");
+    browser(url.as_str());
+
+    let parameters = tokio::time::timeout(
+        Duration::from_secs(AUTHORIZATION_TIMEOUT),
+        redirect(&listener),
+    )
+    .await
+    .map_err(|_| "The authorization wasn't completed in the browser.".to_string())??;
+
+    let parameter = |name: &str| {
+        parameters
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    if let Some(error) = parameter("error") {
+        return Err(format!(
+            "The authorization was refused - {error} {}",
+            parameter("error_description").unwrap_or_default()
+        )
+        .trim()
+        .to_string());
+    }
+
+    if parameter("state") != Some(state.as_str()) {
+        return Err("The authorization response doesn't match the request.".to_string());
+    }
+
+    let code = parameter("code").ok_or("The authorization response has no code.")?;
+    let fields = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("code_verifier", verifier.as_str()),
+    ];
+
+    request(client, oauth2, fields).await
+}
+
+/// Wait for the browser to be redirected, returning the query parameters it brought
+async fn redirect(listener: &tokio::net::TcpListener) -> Result<Vec<(String, String)>, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|error| format!("Could not receive the redirect - {error}"))?;
+
+        let mut buffer = vec![0u8; 8192];
+        let read = stream.read(&mut buffer).await.unwrap_or_default();
+        let received = String::from_utf8_lossy(&buffer[..read]);
+
+        // e.g. `GET /callback?code=...&state=... HTTP/1.1`, anything else such as a favicon is ignored
+        let target = received.split_whitespace().nth(1).unwrap_or_default();
+        let Some(query) = target.strip_prefix(REDIRECT_PATH) else {
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        };
+
+        let body = "Reqwestur has received the authorization, you can close this window.";
+        let _ = stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await;
+
+        return Ok(
+            url::form_urlencoded::parse(query.trim_start_matches('?').as_bytes())
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        );
+    }
+}
+
+/// Post the grant to the token endpoint and read the token from its response
+async fn request(
+    client: &reqwest::Client,
+    oauth2: &OAuth2,
+    mut fields: Vec<(&str, &str)>,
+) -> Result<Token, String> {
+    fields.retain(|(_, value)| !value.is_empty());
+
+    let mut builder = client.post(&oauth2.token_url);
+    if oauth2.client_secret.is_empty() || oauth2.credentials_in_body {
+        fields.push(("client_id", &oauth2.client_id));
+        if !oauth2.client_secret.is_empty() {
+            fields.push(("client_secret", &oauth2.client_secret));
+        }
+    } else {
+        // The credentials are form encoded before they're joined, see RFC 6749 section 2.3.1
+        let encode =
+            |text: &str| url::form_urlencoded::byte_serialize(text.as_bytes()).collect::<String>();
+        let credentials = format!(
+            "{}:{}",
+            encode(&oauth2.client_id),
+            encode(&oauth2.client_secret)
+        );
+        builder = builder.header(
+            reqwest::header::AUTHORIZATION,
+            format!("Basic {}", STANDARD.encode(credentials)),
+        );
+    }
+
+    let response = builder
+        .header(reqwest::header::ACCEPT, "application/json")
+        .form(&fields)
+        .send()
+        .await
+        .map_err(|error| format!("The token endpoint couldn't be reached - {error}"))?;
+
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|error| format!("The token response couldn't be read - {error}"))?;
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+
+    if let Some(error) = body["error"].as_str() {
+        return Err(format!(
+            "The token endpoint returned {error} {}",
+            body["error_description"].as_str().unwrap_or_default()
+        )
+        .trim()
+        .to_string());
+    }
+
+    let access_token = body["access_token"]
+        .as_str()
+        .ok_or(if status.is_success() {
+            "The token response has no access_token.".to_string()
+        } else {
+            format!("The token endpoint returned {status}.")
+        })?;
+
+    Ok(Token {
+        access_token: access_token.to_string(),
+        refresh_token: body["refresh_token"].as_str().map(str::to_string),
+        expires_at: body["expires_in"]
+            .as_i64()
+            .or_else(|| body["expires_in"].as_str()?.parse().ok())
+            .map(|expires_in| chrono::Utc::now().timestamp() + expires_in),
+        response: prettify(text),
+    })
+}
+
+/// A random url safe string, used for the PKCE verifier and state
+fn random() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
@@ -1,10 +1,22 @@
 use eframe::egui;
 
 use crate::{
-    ui::widgets::notification::Notification,
-    utils::traits::{ToColour, ToStringForeign},
+    ui::widgets::notification::{Notification, NotificationKind},
+    utils::{
+        auth::Auth,
+        connection::Connection,
+        cookies::{self, Cookie},
+        environments,
+        formats::substitute,
+        proxy::ProxySettings,
+        traits::{ToColour, ToStringForeign},
+        uri,
+    },
 };
 
+/// The format of the timestamp recorded when a request is sent
+pub const TIMESTAMP_FORMAT: &str = "%d/%m/%Y %H:%M";
+
 /// HTTP method mapping
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
 pub enum Method {
@@ -32,6 +44,18 @@ impl ToString for Method {
     }
 }
 
+impl std::str::FromStr for Method {
+    type Err = String;
+
+    /// Parse the method from its name, ignoring case
+    fn from_str(method: &str) -> Result<Self, Self::Err> {
+        Self::values()
+            .into_iter()
+            .find(|value| value.to_string().eq_ignore_ascii_case(method))
+            .ok_or(format!("Unsupported method \"{method}\"."))
+    }
+}
+
 /// Implement the ToColour function
 impl ToColour for Method {
     /// Convert the method type to associated colour
@@ -84,6 +108,8 @@ pub enum ResponseView {
     RESPONSE,
     HEADERS,
     COOKIES,
+    CONNECTION,
+    CODE,
 }
 
 /// Implement the ToString function, using std::fmt::Display causes a stack overflow
@@ -94,6 +120,8 @@ impl ToString for ResponseView {
             Self::RESPONSE => "Response",
             Self::HEADERS => "Headers",
             Self::COOKIES => "Cookies",
+            Self::CONNECTION => "Connection",
+            Self::CODE => "Code",
         };
 
         str.to_string()
@@ -102,7 +130,13 @@ impl ToString for ResponseView {
 
 impl ResponseView {
     /// A list to offer all method types for iteration
-    const OPTIONS: [Self; 3] = [Self::RESPONSE, Self::HEADERS, Self::COOKIES];
+    const OPTIONS: [Self; 5] = [
+        Self::RESPONSE,
+        Self::HEADERS,
+        Self::COOKIES,
+        Self::CONNECTION,
+        Self::CODE,
+    ];
 
     /// Return an iterable of the available methods
     pub fn values() -> Vec<Self> {
@@ -116,9 +150,16 @@ impl ResponseView {
 pub struct Response {
     pub status: StatusCode,
     pub headers: Vec<(String, String)>,
-    pub cookies: Vec<String>,
+    #[serde(deserialize_with = "cookies::records_or_values")]
+    pub cookies: Vec<Cookie>,
     pub body: String,
     pub view: ResponseView,
+
+    /// The time taken to receive the response, in milliseconds
+    pub duration: u64,
+
+    /// The connection the response came over, or why it failed
+    pub connection: Connection,
 }
 
 /// The Content-Type of the request
@@ -161,6 +202,51 @@ impl ContentType {
     pub fn values() -> Vec<Self> {
         Vec::from(Self::OPTIONS)
     }
+
+    /// Map a Content-Type header value onto the closest content type
+    pub fn from_mime(mime: &str) -> Self {
+        let essence = mime
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase();
+
+        match essence.as_str() {
+            "" => Self::EMPTY,
+            "application/x-www-form-urlencoded" => Self::XWWWFORMURLENCODED,
+            "multipart/form-data" => Self::MULTIPART,
+            "application/json" => Self::JSON,
+            json if json.ends_with("+json") => Self::JSON,
+            _ => Self::TEXT,
+        }
+    }
+}
+
+/// A row of the query parameter table
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct QueryParam {
+    pub name: String,
+    pub value: String,
+
+    /// Disabled parameters are kept in the table, but left out of the URI
+    pub enabled: bool,
+}
+
+impl QueryParam {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            enabled: true,
+        }
+    }
+
+    /// Whether the parameter is part of the URI, parameters without a name are still being typed
+    fn is_sent(&self) -> bool {
+        self.enabled && !self.name.is_empty()
+    }
 }
 
 /// The struct containing the request address details
@@ -168,9 +254,144 @@ impl ContentType {
 #[serde(default)]
 pub struct Address {
     pub uri: String,
+
+    /// The query parameters, kept in step with the URI's query string
+    pub query: Vec<QueryParam>,
     pub notification: Notification,
 }
 
+impl Address {
+    /// The URL the request is sent to, see [`uri::normalise`]
+    pub fn url(&self) -> Result<reqwest::Url, String> {
+        uri::normalise(&self.uri).map(|(url, _)| url)
+    }
+
+    /// Check the URI with the environment's variables filled in, explaining any problem in the notification
+    ///
+    /// The request can't be sent while the notification is an error.
+    pub fn validate(&mut self, variables: &[(String, String)]) {
+        // A new request isn't an error until it's sent
+        if self.uri.trim().is_empty() {
+            self.notification.clear();
+            return;
+        }
+
+        let uri = substitute(&self.uri, variables);
+        let missing = environments::placeholders(&uri);
+        if !missing.is_empty() {
+            let names = missing
+                .iter()
+                .map(|placeholder| &uri[placeholder.range.clone()])
+                .collect::<Vec<&str>>()
+                .join(", ");
+            self.notification = Notification::new(
+                format!("No value for {names} in the active environment."),
+                NotificationKind::WARN,
+            );
+            return;
+        }
+
+        self.notification = match uri::normalise(&uri) {
+            Err(error) => Notification::new(error, NotificationKind::ERROR),
+            Ok((_, notes)) if notes.is_empty() => Notification::default(),
+            Ok((_, notes)) => Notification::new(notes.join(" "), NotificationKind::INFO),
+        };
+    }
+
+    /// Update the query parameter table from the URI, after the URI has been edited
+    ///
+    /// Disabled rows, and rows still missing a name, keep their place in the table.
+    pub fn query_from_uri(&mut self) {
+        let (_, query, _) = split_uri(&self.uri);
+        let parameters: Vec<(String, String)> =
+            url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .filter(|(name, _)| !name.is_empty())
+                .collect();
+
+        let sent = self
+            .query
+            .iter()
+            .filter(|param| param.is_sent())
+            .map(|param| (param.name.clone(), param.value.clone()));
+        if sent.eq(parameters.iter().cloned()) {
+            return;
+        }
+
+        let mut parameters = parameters.into_iter();
+        let mut query = Vec::new();
+        for param in self.query.drain(..) {
+            if !param.is_sent() {
+                query.push(param);
+            } else if let Some((name, value)) = parameters.next() {
+                query.push(QueryParam::new(name, value));
+            }
+        }
+        query.extend(parameters.map(|(name, value)| QueryParam::new(name, value)));
+
+        self.query = query;
+    }
+
+    /// Rewrite the URI's query string from the enabled rows, after the table has been edited
+    ///
+    /// Names and values are form encoded as `Url` does, apart from their `{{variable}}` placeholders.
+    pub fn uri_from_query(&mut self) {
+        let (base, _, fragment) = split_uri(&self.uri);
+
+        let query = self
+            .query
+            .iter()
+            .filter(|param| param.is_sent())
+            .map(|param| format!("{}={}", encode(&param.name), encode(&param.value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let mut uri = base.to_string();
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query);
+        }
+        if let Some(fragment) = fragment {
+            uri.push('#');
+            uri.push_str(fragment);
+        }
+
+        self.uri = uri;
+    }
+}
+
+/// Split the URI into the part before the query, the query and the fragment
+fn split_uri(uri: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (uri, fragment) = match uri.split_once('#') {
+        Some((uri, fragment)) => (uri, Some(fragment)),
+        None => (uri, None),
+    };
+
+    match uri.split_once('?') {
+        Some((base, query)) => (base, Some(query), fragment),
+        None => (uri, None, fragment),
+    }
+}
+
+/// Form encode the text, leaving its placeholders to be filled in when the request is sent
+fn encode(text: &str) -> String {
+    let mut encoded = String::new();
+    let mut offset = 0;
+
+    for placeholder in environments::placeholders(text) {
+        encoded.extend(url::form_urlencoded::byte_serialize(
+            &text.as_bytes()[offset..placeholder.range.start],
+        ));
+        encoded.push_str(&text[placeholder.range.clone()]);
+        offset = placeholder.range.end;
+    }
+    encoded.extend(url::form_urlencoded::byte_serialize(
+        &text.as_bytes()[offset..],
+    ));
+
+    encoded
+}
+
 /// The request event types
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone, Eq, PartialEq)]
 pub enum RequestEvent {
@@ -184,12 +405,18 @@ pub enum RequestEvent {
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone)]
 #[serde(default)]
 pub struct Request {
+    /// Contains the request's name, used to label saved requests
+    pub name: String,
+
     /// Contains the request Method
     pub method: Method,
 
     /// Contains the request Headers
     pub headers: Vec<(String, String)>,
 
+    /// The credentials the request authenticates with
+    pub auth: Auth,
+
     /// Contains the request URI
     pub address: Address,
 
@@ -205,6 +432,15 @@ pub struct Request {
     /// Contains the request's formdata/params
     pub params: Vec<(String, String)>,
 
+    /// The number of seconds to wait for a response, the engine's default is used if unset
+    pub timeout: Option<u64>,
+
+    /// The name of the library certificate sent with the request, chosen by host if unset
+    pub certificate: Option<String>,
+
+    /// The proxy the request is sent through, the proxy settings of every request are used if unset
+    pub proxy: Option<ProxySettings>,
+
     /// Contains the request's response
     pub response: Response,
 
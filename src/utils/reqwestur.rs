@@ -1,7 +1,4 @@
-use std::{
-    str::FromStr,
-    sync::{Arc, Mutex},
-};
+use std::sync::{Arc, Mutex, atomic::AtomicU64, mpsc};
 
 use eframe::egui::{self};
 
@@ -12,10 +9,23 @@ use crate::{
     },
     utils::{
         certificates::{Certificate, CertificateStatus},
-        request::{ContentType, Method, Request, RequestEvent, Response},
+        connection::{self, Connection, Handshake},
+        cookies::{Cookie, CookieJar},
+        engine::{self, ClientCache, ClientSettings, EngineError, RequestExecutor},
+        environments::{self, Environment},
+        formats::substitute,
+        oauth2::{self, TokenCache},
+        proxy::ProxySettings,
+        request::{Request, RequestEvent, Response, ResponseView, TIMESTAMP_FORMAT},
+        secrets::{Secret, Vault},
+        snippets::Snippet,
+        tls::TlsSettings,
     },
 };
 
+/// The application name, used as the window title and to locate the persisted state
+pub const APP_ID: &str = "REQWESTUR";
+
 #[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
 pub enum AppView {
     #[default]
@@ -25,6 +35,53 @@ pub enum AppView {
     History,
 }
 
+/// Identifies each sent request, so the results of cancelled requests can be ignored
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The results of the work done on the runtime
+enum Outcome {
+    /// The result of a sent request
+    Response {
+        id: u64,
+        result: Box<Result<Response, Notification>>,
+    },
+
+    /// The TLS handshake read for the connection of a response
+    Handshake {
+        id: u64,
+        handshake: Result<Handshake, String>,
+    },
+}
+
+/// A request that is being sent on the runtime
+#[derive(Clone)]
+pub struct PendingRequest {
+    id: u64,
+    task: tokio::task::AbortHandle,
+}
+
+/// Carries the results of the requests sent on the runtime back to the UI
+#[derive(Clone)]
+pub struct Responses {
+    sender: mpsc::Sender<Outcome>,
+    receiver: Arc<Mutex<mpsc::Receiver<Outcome>>>,
+
+    /// Repaints the UI when a result arrives
+    context: Option<egui::Context>,
+}
+
+impl Default for Responses {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            context: None,
+        }
+    }
+}
+
 /// A struct containing application shortcut keybindings
 pub struct AppShortcuts {
     pub save: egui::KeyboardShortcut,
@@ -57,20 +114,70 @@ pub struct Reqwestur {
     // Request Panel
     pub request: Arc<Mutex<Request>>,
     pub saved_requests: Vec<Request>,
+    pub certificates: Vec<Certificate>,
+    /// The single certificate saved before certificates were kept in a library, moved into it on load
+    #[serde(skip_serializing)]
     pub certificate: Option<Certificate>,
+    #[serde(skip_serializing)]
     pub use_certificate_authentication: bool,
+    #[serde(skip)]
+    pub selected_certificate: Option<usize>,
+    pub tls: TlsSettings,
+    pub proxy: ProxySettings,
+    #[serde(skip)]
+    pub curl_command: String,
+    pub snippet: Snippet,
+
+    #[serde(skip)]
+    pub client: ClientCache,
+    #[serde(skip)]
+    pub pending: Option<PendingRequest>,
+    #[serde(skip)]
+    pub inspection: Option<u64>,
+    #[serde(skip)]
+    pub responses: Responses,
 
     // History Panel
     pub history: Arc<Mutex<Vec<Request>>>,
 
+    // Environments
+    pub environments: Vec<Environment>,
+    pub active_environment: Option<usize>,
+
+    // OAuth 2.0 tokens fetched without an environment, and the result of fetching one
+    #[serde(skip)]
+    pub tokens: TokenCache,
+    #[serde(skip)]
+    pub token_notification: Arc<Mutex<Notification>>,
+
+    // Cookies
+    #[serde(skip)]
+    pub cookies: Vec<Cookie>,
+    #[serde(skip)]
+    pub cookie_domain: String,
+
+    // Secrets
+    #[serde(skip)]
+    pub vault: Vault,
+    #[serde(skip)]
+    pub vault_password: String,
+
     // Editors
     pub header_editor_open: bool,
+    pub query_editor_open: bool,
+    pub auth_editor_open: bool,
     pub payload_editor_open: bool,
     pub certificate_editor_open: bool,
+    pub tls_editor_open: bool,
+    pub proxy_editor_open: bool,
+    pub curl_editor_open: bool,
+    pub environment_editor_open: bool,
+    pub cookie_editor_open: bool,
 
     // Modals
     pub help_modal_open: bool,
     pub about_modal_open: bool,
+    pub vault_modal_open: bool,
 
     // Alerts
     pub notification: Notification,
@@ -87,20 +194,54 @@ impl Default for Reqwestur {
             // Request
             request: Arc::new(Mutex::new(Request::default())),
             saved_requests: Vec::new(),
+            certificates: Vec::new(),
             certificate: None,
             use_certificate_authentication: false,
+            selected_certificate: None,
+            tls: TlsSettings::default(),
+            proxy: ProxySettings::default(),
+            curl_command: String::new(),
+            snippet: Snippet::default(),
+            client: ClientCache::default(),
+            pending: None,
+            inspection: None,
+            responses: Responses::default(),
 
             // History
             history: Arc::new(Mutex::new(Vec::new())),
 
+            // Environments
+            environments: Vec::new(),
+            active_environment: None,
+
+            // OAuth 2.0
+            tokens: TokenCache::default(),
+            token_notification: Arc::new(Mutex::new(Notification::default())),
+
+            // Cookies
+            cookies: Vec::new(),
+            cookie_domain: String::new(),
+
+            // Secrets
+            vault: Vault::default(),
+            vault_password: String::new(),
+
             // Editors
             header_editor_open: false,
+            query_editor_open: false,
+            auth_editor_open: false,
             payload_editor_open: false,
             certificate_editor_open: false,
+            tls_editor_open: false,
+            proxy_editor_open: false,
+            curl_editor_open: false,
+            environment_editor_open: false,
+            cookie_editor_open: false,
 
             // Modals
             help_modal_open: false,
             about_modal_open: false,
+            vault_modal_open: false,
 
             // Alerts
             notification: Notification::default(),
@@ -143,206 +284,709 @@ impl Reqwestur {
             });
 
             // Create new app to generate mutables
-            return Self {
+            let mut app = Self {
                 // Create fresh request
                 request: Arc::new(Mutex::new(Request::default())),
 
                 // Reset window values
                 header_editor_open: false,
+                query_editor_open: false,
+                auth_editor_open: false,
                 payload_editor_open: false,
                 certificate_editor_open: false,
+                tls_editor_open: false,
+                proxy_editor_open: false,
+                curl_editor_open: false,
+                environment_editor_open: false,
+                cookie_editor_open: false,
                 help_modal_open: false,
                 about_modal_open: false,
+                vault_modal_open: false,
+
+                // Secrets are locked until the master password is entered
+                vault: Vault::open(),
+
+                // Repaint when a response arrives
+                responses: Responses {
+                    context: Some(cc.egui_ctx.clone()),
+                    ..Default::default()
+                },
 
                 // Restore old values
                 ..previous_values
             };
+            app.migrate();
+
+            return app;
         }
 
         Default::default()
     }
 
-    /// A function to send the built request
-    pub fn send(&mut self) -> Result<Response, Notification> {
-        self.request.lock().unwrap().event = RequestEvent::PENDING;
-        let mut request = self.request.lock().unwrap().clone();
+    /// Read the state persisted by the application without an eframe context, e.g. from the CLI
+    pub fn from_storage() -> Result<Self, String> {
+        let file_path = eframe::storage_dir(APP_ID)
+            .ok_or("Could not find the application's storage directory.")?
+            .join("app.ron");
 
-        request.response = Response::default();
+        let contents = std::fs::read_to_string(&file_path)
+            .map_err(|error| format!("Could not read {} - {error}", file_path.display()))?;
+
+        let values: std::collections::HashMap<String, String> = ron::from_str(&contents)
+            .map_err(|error| format!("Could not parse {} - {error}", file_path.display()))?;
+
+        let app = values
+            .get(eframe::APP_KEY)
+            .ok_or("No saved application state was found.")?;
+
+        let app: Self = ron::from_str(app)
+            .map_err(|error| format!("Could not parse the saved state - {error}"))?;
+
+        let mut app = Self {
+            vault: Vault::open(),
+            ..app
+        };
+        app.migrate();
+
+        Ok(app)
+    }
+
+    /// Move the state saved by earlier versions into its current place
+    pub fn migrate(&mut self) {
+        // The certificate was sent to every host while certificate authentication was turned on
+        if let Some(certificate) = self.certificate.take() {
+            let name = certificate
+                .file_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or("Certificate".to_string());
+            let hosts = if self.use_certificate_authentication {
+                vec!["*".to_string()]
+            } else {
+                Vec::new()
+            };
+
+            self.certificates.push(Certificate {
+                name,
+                hosts,
+                ..certificate
+            });
+        }
+        self.use_certificate_authentication = false;
+    }
+
+    /// Update the secrets referenced by the environments, the certificates and the proxies
+    ///
+    /// The proxy passwords of the request being edited are left, they're read from the vault when it's sent.
+    fn update_secrets(&mut self, mut update: impl FnMut(&mut Secret)) {
+        let history = self.history.clone();
+        let mut history = history.lock().unwrap();
+
+        self.environments
+            .iter_mut()
+            .flat_map(|environment| environment.secrets.iter_mut())
+            .map(|(_, secret)| secret)
+            .chain(
+                self.certificates
+                    .iter_mut()
+                    .map(|certificate| &mut certificate.passphrase),
+            )
+            .chain(std::iter::once(&mut self.proxy.password))
+            .chain(
+                self.saved_requests
+                    .iter_mut()
+                    .chain(history.iter_mut())
+                    .filter_map(|request| request.proxy.as_mut())
+                    .map(|proxy| &mut proxy.password),
+            )
+            .for_each(&mut update);
+    }
+
+    /// Unlock the vault with the master password, creating it if it doesn't exist yet
+    pub fn unlock_vault(&mut self, password: &str) -> Result<(), String> {
+        if self.vault.exists() {
+            self.vault.unlock(password)?;
+        } else {
+            self.vault.create(password)?;
+        }
 
-        let Request {
-            method,
-            headers,
-            address,
-            timestamp: _,
-            body,
-            content_type,
-            params,
-            response: _,
-            notification: _,
-            event: _,
-        } = request.clone();
-
-        let mut client_builder = reqwest::blocking::ClientBuilder::new();
-
-        if let Some(certificate) = &mut self.certificate {
-            if certificate.file_path.exists() && !certificate.passphrase.is_empty() {
+        // Values not in the vault yet, e.g. proxy passwords imported from a curl command, are kept
+        let vault = self.vault.clone();
+        self.update_secrets(|secret| {
+            if let Some(value) = vault.get(&secret.id) {
+                secret.value = value.to_string();
+            }
+        });
+
+        // Passphrases saved before the vault existed are moved into it
+        for certificate in self.certificates.iter_mut() {
+            if !certificate.legacy_passphrase.is_empty() {
+                certificate.passphrase.value = std::mem::take(&mut certificate.legacy_passphrase);
+                self.vault.set(&certificate.passphrase)?;
+            }
+        }
+
+        self.store_proxy_passwords()
+    }
+
+    /// Move the proxy passwords not in the vault into it, those saved before it existed or imported from a curl command
+    fn store_proxy_passwords(&mut self) -> Result<(), String> {
+        if !self.vault.is_unlocked() {
+            return Ok(());
+        }
+
+        let history = self.history.clone();
+        let mut history = history.lock().unwrap();
+        let proxies = std::iter::once(&mut self.proxy).chain(
+            self.saved_requests
+                .iter_mut()
+                .chain(history.iter_mut())
+                .filter_map(|request| request.proxy.as_mut()),
+        );
+        for proxy in proxies {
+            if !proxy.legacy_password.is_empty() {
+                proxy.password.value = std::mem::take(&mut proxy.legacy_password);
+                self.vault.set(&proxy.password)?;
+            } else if !proxy.password.value.is_empty()
+                && self.vault.get(&proxy.password.id).is_none()
+            {
+                self.vault.set(&proxy.password)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lock the vault, clearing the secret values held in memory
+    pub fn lock_vault(&mut self) {
+        self.vault.lock();
+        self.update_secrets(|secret| secret.value.clear());
+    }
+
+    /// Whether the environments have secrets whose values can't be read until the vault is unlocked
+    pub fn secrets_locked(&self) -> bool {
+        !self.vault.is_unlocked()
+            && self
+                .environments
+                .iter()
+                .any(|environment| !environment.secrets.is_empty())
+    }
+
+    /// The names and values of every unlocked secret variable, used to redact exports
+    pub fn secrets(&self) -> Vec<(String, String)> {
+        self.environments
+            .iter()
+            .flat_map(|environment| environment.secrets.iter())
+            .filter(|(_, secret)| !secret.value.is_empty())
+            .map(|(name, secret)| (name.clone(), secret.value.clone()))
+            .collect()
+    }
+
+    /// The cookie jar of the selected environment, if it keeps cookies
+    pub fn cookie_jar(&self) -> Option<CookieJar> {
+        self.environment()
+            .filter(|environment| environment.keep_cookies)
+            .map(|environment| environment.cookies.clone())
+    }
+
+    /// The OAuth 2.0 tokens of the selected environment
+    pub fn token_cache(&self) -> TokenCache {
+        self.environment()
+            .map(|environment| environment.tokens.clone())
+            .unwrap_or(self.tokens.clone())
+    }
+
+    /// Opens the authorization URL of the OAuth 2.0 authorization code grant in the browser
+    fn browser(&self) -> impl Fn(&str) + Send + Sync + 'static {
+        let context = self.responses.context.clone();
+        move |url: &str| {
+            if let Some(context) = &context {
+                context.open_url(egui::OpenUrl::new_tab(url));
+            }
+        }
+    }
+
+    /// Fetch a new OAuth 2.0 token for the request in the background, replacing any cached token
+    pub fn fetch_token(&mut self, request: &Request) -> Result<(), Notification> {
+        let resolved = environments::resolve(request, &self.variables());
+        let executor = self.executor(&resolved)?;
+        let auth = resolved.auth;
+        let tokens = self.token_cache();
+        let notification = self.token_notification.clone();
+        let browser = self.browser();
+        let context = self.responses.context.clone();
+
+        *notification.lock().unwrap() =
+            Notification::new("Fetching a token...", NotificationKind::INFO);
+
+        engine::runtime().spawn(async move {
+            let fetched = match oauth2::fetch(executor.client(), &auth, &browser).await {
+                Ok(token) => {
+                    tokens.insert(&auth, token);
+                    Notification::new("Token received.", NotificationKind::INFO)
+                }
+                Err(error) => Notification::new(error, NotificationKind::ERROR),
+            };
+
+            *notification.lock().unwrap() = fetched;
+            if let Some(context) = &context {
+                context.request_repaint();
+            }
+        });
+
+        Ok(())
+    }
+
+    /// A fingerprint of the settings the client is built from, the client is rebuilt when it changes
+    fn client_fingerprint(&self, certificate: Option<usize>, proxy: &ProxySettings) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        certificate
+            .and_then(|index| self.certificates.get(index))
+            .map(|certificate| {
+                (
+                    certificate.format.to_string(),
+                    &certificate.file_path,
+                    &certificate.key_path,
+                    &certificate.chain_path,
+                    &certificate.passphrase.value,
+                )
+            })
+            .hash(&mut hasher);
+        self.cookie_jar().map(|jar| jar.id()).hash(&mut hasher);
+        self.tls.hash(&mut hasher);
+        proxy.hash(&mut hasher);
+        self.environment()
+            .map(|environment| {
+                (
+                    environment.accept_invalid_certificates,
+                    environment.accept_invalid_hostnames,
+                )
+            })
+            .hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// The certificate the request is sent with, the one it names or else the first whose hosts match its URL
+    pub fn certificate_for(&self, request: &Request) -> Result<Option<usize>, Notification> {
+        if let Some(name) = &request.certificate {
+            return self
+                .certificates
+                .iter()
+                .position(|certificate| &certificate.name == name)
+                .map(Some)
+                .ok_or(Notification::new(
+                    format!(
+                        "No certificate is called {name}, select another in the request settings."
+                    ),
+                    NotificationKind::ERROR,
+                ));
+        }
+
+        let host = request
+            .address
+            .url()
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string));
+
+        Ok(host.and_then(|host| {
+            self.certificates
+                .iter()
+                .position(|certificate| certificate.matches(&host))
+        }))
+    }
+
+    /// The proxy the request is sent through, its own or else the one for every request, with variables filled in
+    pub fn proxy_for(&self, request: &Request) -> Result<ProxySettings, Notification> {
+        // The password is read from the vault, or the one saved before the vault until it's moved into it
+        let mut proxy = request.proxy.clone().unwrap_or_else(|| self.proxy.clone());
+        if proxy.password.value.is_empty() {
+            proxy.password.value = self
+                .vault
+                .get(&proxy.password.id)
+                .unwrap_or(&proxy.legacy_password)
+                .to_string();
+        }
+
+        let variables = self.variables();
+        for field in proxy.fields_mut() {
+            *field = substitute(field, &variables);
+        }
+
+        let missing = proxy
+            .fields()
+            .into_iter()
+            .flat_map(|field| environments::placeholders(field))
+            .map(|placeholder| format!("{{{{{}}}}}", placeholder.name))
+            .collect::<Vec<String>>();
+        if !missing.is_empty() {
+            return Err(Notification::new(
+                format!(
+                    "No value for {} in the active environment, used by the proxy settings.",
+                    missing.join(", ")
+                ),
+                NotificationKind::ERROR,
+            ));
+        }
+
+        Ok(proxy)
+    }
+
+    /// The executor for the request's settings, reused between requests so connections are kept alive
+    pub fn executor(&mut self, request: &Request) -> Result<Arc<RequestExecutor>, Notification> {
+        let certificate = self.certificate_for(request)?;
+        let proxy = self.proxy_for(request)?;
+        let fingerprint = self.client_fingerprint(certificate, &proxy);
+        if let Some(executor) = self.client.get(fingerprint) {
+            return Ok(executor);
+        }
+
+        let settings = ClientSettings {
+            proxy,
+            ..self.client_settings(certificate)?
+        };
+        let executor = RequestExecutor::new(settings)
+            .map_err(|error| Notification::new(error.to_string(), NotificationKind::ERROR))?;
+
+        Ok(self.client.insert(fingerprint, executor))
+    }
+
+    /// Load the identity of the certificate from the library, updating its status as it's imported
+    pub fn client_settings(
+        &mut self,
+        certificate: Option<usize>,
+    ) -> Result<ClientSettings, Notification> {
+        let environment = self.environment();
+        let mut settings = ClientSettings {
+            cookies: self.cookie_jar(),
+            tls: self.tls.clone(),
+            accept_invalid_certificates: environment
+                .is_some_and(|environment| environment.accept_invalid_certificates),
+            accept_invalid_hostnames: environment
+                .is_some_and(|environment| environment.accept_invalid_hostnames),
+            ..Default::default()
+        };
+
+        if let Some(certificate) = certificate.and_then(|index| self.certificates.get_mut(index)) {
+            if certificate.is_ready() {
                 let (kind, message) = match certificate.import() {
                     Ok(identity) => {
                         certificate.status = CertificateStatus::OK;
                         certificate.identity = Some(identity);
+                        certificate.details = certificate.inspect().unwrap_or_default();
                         (
                             NotificationKind::INFO,
                             "Certificate loaded successfully!".to_string(),
                         )
                     }
                     Err(error) => {
-                        certificate.status = CertificateStatus::OK;
+                        certificate.status = CertificateStatus::ERROR;
                         (NotificationKind::ERROR, error)
                     }
                 };
                 certificate.notification = Notification::new(message, kind);
             }
 
-            if let Some(identity) = certificate.identity.clone() {
-                client_builder = client_builder.identity(identity);
-            } else {
-                let notification = Notification::new(
-                    "Cannot find certificates, have you added them?",
-                    NotificationKind::WARN,
-                );
+            match certificate.identity.clone() {
+                Some(identity) => settings.identity = Some(identity),
+                None if !self.vault.is_unlocked() => {
+                    return Err(Notification::new(
+                        format!(
+                            "Unlock the vault to load the passphrase of {}.",
+                            certificate.name
+                        ),
+                        NotificationKind::WARN,
+                    ));
+                }
+                None => {
+                    return Err(Notification::new(
+                        format!(
+                            "Cannot load the certificate {}, have you added its files?",
+                            certificate.name
+                        ),
+                        NotificationKind::WARN,
+                    ));
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    /// The selected environment, if any
+    pub fn environment(&self) -> Option<&Environment> {
+        self.active_environment
+            .and_then(|index| self.environments.get(index))
+    }
+
+    /// The warning shown while the selected environment turns off server certificate checks
+    pub fn tls_warning(&self) -> Option<String> {
+        self.environment()
+            .and_then(|environment| environment.tls_warning())
+    }
+
+    /// The variables of the selected environment, filled into requests before they're sent
+    pub fn variables(&self) -> Vec<(String, String)> {
+        self.environment()
+            .map(|environment| environment.values())
+            .unwrap_or_default()
+    }
+
+    /// Send the request on the shared runtime, the result is applied by `poll` once it arrives
+    pub fn send(&mut self, request: &mut Request) -> Result<(), Notification> {
+        request.response = Response::default();
+
+        // The request keeps its placeholders, only the sent copy is resolved
+        let variables = self.variables();
+        let missing = environments::unresolved(request, &variables);
+        let resolved = environments::resolve(request, &variables);
+
+        let locked = !self.vault.is_unlocked()
+            && self.environment().is_some_and(|environment| {
+                environment
+                    .secrets
+                    .iter()
+                    .any(|(name, _)| missing.contains(name))
+            });
+
+        let prepared = if missing.is_empty() {
+            match resolved.address.url() {
+                Ok(_) => self.executor(&resolved),
+                Err(error) => Err(Notification::new(error, NotificationKind::ERROR)),
+            }
+        } else {
+            let names = missing
+                .iter()
+                .map(|name| format!("{{{{{name}}}}}"))
+                .collect::<Vec<String>>()
+                .join(", ");
+            Err(Notification::new(
+                if locked {
+                    format!("Unlock the vault to fill in {names}.")
+                } else {
+                    format!("No value for {names} in the active environment.")
+                },
+                NotificationKind::ERROR,
+            ))
+        };
 
+        let executor = match prepared {
+            Ok(executor) => executor,
+            Err(notification) => {
                 request.notification(&notification);
+                request.event = RequestEvent::UNSENT;
                 return Err(notification);
             }
+        };
+
+        request.notification.clear();
+        request.event = RequestEvent::PENDING;
+
+        let id = NEXT_REQUEST_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let responses = self.responses.clone();
+        let tokens = self.token_cache();
+        let browser = self.browser();
+        let task = engine::runtime().spawn(async move {
+            let mut resolved = resolved;
+            let result = match executor.authorize(&mut resolved, &tokens, &browser).await {
+                Ok(()) => executor.execute(&resolved).await.or_else(displayed),
+                Err(error) => displayed(error),
+            };
+
+            // The UI may have been closed, in which case the result isn't needed
+            let _ = responses.sender.send(Outcome::Response {
+                id,
+                result: Box::new(result),
+            });
+            if let Some(context) = &responses.context {
+                context.request_repaint();
+            }
+        });
+
+        // Replacing a pending request stops it, only the latest result is shown
+        self.inspection = None;
+        if let Some(pending) = self.pending.replace(PendingRequest {
+            id,
+            task: task.abort_handle(),
+        }) {
+            pending.task.abort();
         }
 
-        let client = client_builder
-            .default_headers(reqwest::header::HeaderMap::from_iter([(
-                reqwest::header::USER_AGENT,
-                reqwest::header::HeaderValue::from_str("REQWESTUR").unwrap(),
-            )]))
-            .build()
-            .unwrap();
-
-        let mut built_request = match method {
-            Method::GET => client.get(&address.uri),
-            Method::POST => client.post(&address.uri),
-            Method::PUT => client.put(&address.uri),
-            Method::PATCH => client.patch(&address.uri),
-            Method::DELETE => client.delete(&address.uri),
+        Ok(())
+    }
+
+    /// Read the TLS handshake and certificate chain of the request's server in the background, `poll` shows it
+    ///
+    /// A separate connection is made, as the client only reports the server's certificate.
+    pub fn inspect(&mut self, request: &mut Request) {
+        let resolved = environments::resolve(request, &self.variables());
+        let url = match resolved.address.url() {
+            Ok(url) if url.scheme() == "https" => url,
+            _ => return,
         };
 
-        if !headers.is_empty() {
-            let mut header_list = reqwest::header::HeaderMap::new();
-            headers.iter().for_each(|(name, value)| {
-                header_list.append(
-                    reqwest::header::HeaderName::from_str(&name).unwrap(),
-                    reqwest::header::HeaderValue::from_bytes(&value.as_bytes()).unwrap(),
-                );
-            });
+        let connection = &mut request.response.connection;
 
-            built_request = built_request.headers(header_list)
+        // The server may only be reachable through the proxy, which the separate connection doesn't use
+        if self
+            .proxy_for(&resolved)
+            .is_ok_and(|proxy| proxy.is_proxied(url.scheme()))
+        {
+            connection.handshake = Some(Err(
+                "The certificate chain isn't read for requests sent through a proxy.".to_string(),
+            ));
+            return;
         }
 
-        if request.content_type != ContentType::EMPTY {
-            built_request = match content_type {
-                ContentType::MULTIPART => {
-                    let mut form = reqwest::blocking::multipart::Form::new();
-                    for (name, value) in params {
-                        form = form.text(name, value);
-                    }
-                    built_request.multipart(form)
-                }
-                ContentType::XWWWFORMURLENCODED => built_request.form(&params),
-                _ => {
-                    if let Some(body) = body {
-                        built_request.body(body)
-                    } else {
-                        built_request
+        connection.handshake = None;
+        connection.inspecting = true;
+
+        let id = NEXT_REQUEST_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.inspection = Some(id);
+
+        let responses = self.responses.clone();
+        let tls = self.tls.clone();
+        engine::runtime().spawn(async move {
+            let handshake = connection::inspect(&url, &tls).await;
+
+            let _ = responses.sender.send(Outcome::Handshake { id, handshake });
+            if let Some(context) = &responses.context {
+                context.request_repaint();
+            }
+        });
+    }
+
+    /// Stop the request being sent
+    pub fn cancel(&mut self, request: &mut Request) {
+        if let Some(pending) = self.pending.take() {
+            pending.task.abort();
+        }
+
+        request.event = RequestEvent::UNSENT;
+        request.notification(&Notification::new(
+            "The request was cancelled.",
+            NotificationKind::INFO,
+        ));
+    }
+
+    /// Apply the results of the requests sent on the runtime, adding successful requests to the history
+    pub fn poll(&mut self) {
+        let outcomes: Vec<Outcome> = self.responses.receiver.lock().unwrap().try_iter().collect();
+
+        // The certificate chain is read after a TLS failure, to show why the server wasn't trusted
+        let mut inspect = false;
+
+        for outcome in outcomes {
+            let (id, result) = match outcome {
+                Outcome::Response { id, result } => (id, *result),
+                Outcome::Handshake { id, handshake } => {
+                    // Only the handshake read for the latest response is shown
+                    if self.inspection == Some(id) {
+                        self.inspection = None;
+
+                        let connection = &mut self.request.lock().unwrap().response.connection;
+                        if connection.inspecting {
+                            connection.inspecting = false;
+                            connection.handshake = Some(handshake);
+                        }
                     }
+                    continue;
                 }
             };
-        }
 
-        let http_request = built_request.build().unwrap();
+            // Results of cancelled requests are ignored
+            if self.pending.as_ref().is_none_or(|pending| pending.id != id) {
+                continue;
+            }
+            self.pending = None;
 
-        let (status, headers, cookies, text) = match client.execute(http_request) {
-            Ok(response) => (
-                (
-                    response.status().as_u16(),
-                    response
-                        .status()
-                        .canonical_reason()
-                        .unwrap_or("UNKNOWN")
-                        .to_string(),
-                ),
-                response.headers().clone(),
-                response
-                    .cookies()
-                    .map(|cookie| (cookie.value().to_string()))
-                    .collect::<Vec<String>>(),
-                match response.text() {
-                    Ok(str) => str,
-                    Err(_) => todo!(),
-                },
-            ),
-            Err(response) => (
-                (
-                    response
-                        .status()
-                        .unwrap_or(reqwest::StatusCode::BAD_REQUEST)
-                        .as_u16(),
-                    response
-                        .status()
-                        .unwrap_or(reqwest::StatusCode::BAD_REQUEST)
-                        .canonical_reason()
-                        .unwrap_or("UNKNOWN")
-                        .to_string(),
-                ),
-                reqwest::header::HeaderMap::new(),
-                Vec::new(),
-                response.to_string(),
-            ),
-        };
+            // The request may have been replaced, e.g. by a new or saved request
+            let mut request = self.request.lock().unwrap();
+            if request.event != RequestEvent::PENDING {
+                continue;
+            }
 
-        let pretty_string = match serde_json::from_str::<serde_json::Value>(&text) {
-            Ok(json) => serde_json::to_string_pretty(&json),
-            Err(_) => Ok(text),
-        };
+            match result {
+                Ok(response) => {
+                    inspect = response
+                        .connection
+                        .failure
+                        .as_ref()
+                        .is_some_and(|failure| failure.is_tls());
 
-        if let Err(error) = pretty_string {
-            let notification = Notification::new(
-                format!("Could not prettify JSON - {:?}", error),
-                NotificationKind::ERROR,
-            );
-            request.notification(&notification);
-            return Err(notification);
+                    request.notification(&Notification::new(
+                        "Sent successfully.",
+                        NotificationKind::INFO,
+                    ));
+                    request.response = response;
+                    request.timestamp = chrono::Utc::now().format(TIMESTAMP_FORMAT).to_string();
+                    request.event = RequestEvent::SENT;
+
+                    self.history.lock().unwrap().push(request.clone());
+
+                    // Show the cookies the response set in the cookie manager
+                    if self.cookie_editor_open
+                        && let Some(jar) = self.cookie_jar()
+                    {
+                        self.cookies = jar.records();
+                    }
+                }
+                Err(notification) => {
+                    request.notification(&notification);
+                    request.event = RequestEvent::UNSENT;
+                }
+            }
         }
 
-        let simple_headers: Vec<(String, String)> = headers
-            .iter()
-            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
-            .collect();
-
-        let response = Response {
-            status: status,
-            headers: simple_headers,
-            body: pretty_string.unwrap(),
-            cookies,
-            ..Default::default()
-        };
+        if inspect {
+            let shared = self.request.clone();
+            self.inspect(&mut shared.lock().unwrap());
+        }
+    }
 
-        request.notification(&Notification::new(
-            "Sent successfully.",
-            NotificationKind::INFO,
-        ));
-        request.response = response.clone();
-        request.timestamp = chrono::Utc::now().format("%d/%m/%Y %H:%M").to_string();
-        request.event = RequestEvent::SENT;
+    /// Whether a request is being sent
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
 
-        *self.request.lock().unwrap() = request;
+/// Failed requests are still displayed, the transport error is shown as the body
+///
+/// Connection failures open the connection tab, which explains them.
+fn displayed(error: EngineError) -> Result<Response, Notification> {
+    match error {
+        EngineError::Transport {
+            status,
+            message,
+            failure,
+        } => {
+            let status = reqwest::StatusCode::from_u16(status.unwrap_or(400))
+                .unwrap_or(reqwest::StatusCode::BAD_REQUEST);
 
-        Ok(response)
+            Ok(Response {
+                status: (
+                    status.as_u16(),
+                    status.canonical_reason().unwrap_or("UNKNOWN").to_string(),
+                ),
+                body: message,
+                view: if failure.is_some() {
+                    ResponseView::CONNECTION
+                } else {
+                    ResponseView::default()
+                },
+                connection: Connection {
+                    failure,
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        }
+        error => Err(Notification::new(
+            error.to_string(),
+            NotificationKind::ERROR,
+        )),
     }
 }
 
@@ -360,6 +1004,8 @@ impl eframe::App for Reqwestur {
             ..Default::default()
         };
 
+        self.poll();
+
         // Main layout of UI, task_bar top and main_body bottom
         egui::CentralPanel::default()
             .frame(panel_frame)
@@ -369,6 +1015,10 @@ impl eframe::App for Reqwestur {
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        // Only the ids of the secrets are saved, so passwords entered since the vault was unlocked are stored first
+        if let Err(error) = self.store_proxy_passwords() {
+            self.notification = Notification::new(error, NotificationKind::ERROR);
+        }
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 }
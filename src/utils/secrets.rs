@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+use crate::{ui::widgets::notification::Notification, utils::reqwestur::APP_ID};
+
+/// The name of the vault file, stored beside the application's persisted state
+pub const VAULT_FILE: &str = "secrets.vault";
+
+/// The version of the vault file format
+const VAULT_VERSION: u8 = 1;
+
+/// A value kept in the vault, only its id is persisted with the rest of the application state
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct Secret {
+    /// The key the value is stored under in the vault
+    pub id: String,
+
+    /// The value, only available once the vault has been unlocked
+    #[serde(skip)]
+    pub value: String,
+}
+
+impl Default for Secret {
+    fn default() -> Self {
+        let mut bytes = [0u8; 16];
+        OsRng.fill_bytes(&mut bytes);
+
+        Self {
+            id: bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+            value: String::new(),
+        }
+    }
+}
+
+/// The vault as it's written to disk, the secrets are only stored encrypted
+#[derive(serde::Deserialize, serde::Serialize)]
+struct VaultFile {
+    version: u8,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// An encrypted store for secrets, protected by a master password
+///
+/// The key is derived from the password with Argon2id and the secrets are encrypted with AES-256-GCM.
+#[derive(Default, Clone)]
+pub struct Vault {
+    /// The location of the vault file
+    path: std::path::PathBuf,
+
+    /// The salt used to derive the key from the master password
+    salt: Vec<u8>,
+
+    /// The derived key, only set whilst the vault is unlocked
+    key: Option<[u8; 32]>,
+
+    /// The decrypted secrets, keyed by their id
+    secrets: BTreeMap<String, String>,
+
+    /// A notification style message to indicate information.
+    pub notification: Notification,
+}
+
+impl Vault {
+    /// A locked vault stored at the file path
+    pub fn new(path: impl Into<std::path::PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The vault stored in the application's storage directory
+    pub fn open() -> Self {
+        Self::new(
+            eframe::storage_dir(APP_ID)
+                .map(|directory| directory.join(VAULT_FILE))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Whether a vault has been created at the file path
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Create an empty vault protected by the password, replacing any existing vault
+    pub fn create(&mut self, password: &str) -> Result<(), String> {
+        if password.is_empty() {
+            return Err("The master password cannot be empty.".to_string());
+        }
+
+        let mut salt = vec![0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+
+        self.key = Some(derive_key(password, &salt)?);
+        self.salt = salt;
+        self.secrets.clear();
+
+        self.save()
+    }
+
+    /// Decrypt the vault with the password
+    pub fn unlock(&mut self, password: &str) -> Result<(), String> {
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|error| format!("Could not read {} - {error}", self.path.display()))?;
+        let file: VaultFile = serde_json::from_str(&contents)
+            .map_err(|error| format!("Could not parse {} - {error}", self.path.display()))?;
+
+        if file.version != VAULT_VERSION {
+            return Err(format!("Unsupported vault version {}.", file.version));
+        }
+
+        let decode = |value: &str| {
+            STANDARD
+                .decode(value)
+                .map_err(|error| format!("The vault is corrupted - {error}"))
+        };
+        let salt = decode(&file.salt)?;
+        let nonce = decode(&file.nonce)?;
+        let ciphertext = decode(&file.ciphertext)?;
+
+        if nonce.len() != 12 {
+            return Err("The vault is corrupted - invalid nonce.".to_string());
+        }
+
+        let key = derive_key(password, &salt)?;
+        let plaintext = Aes256Gcm::new(&key.into())
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| "The master password is incorrect.".to_string())?;
+
+        self.secrets = serde_json::from_slice(&plaintext)
+            .map_err(|error| format!("The vault is corrupted - {error}"))?;
+        self.salt = salt;
+        self.key = Some(key);
+
+        Ok(())
+    }
+
+    /// Forget the key and the decrypted secrets
+    pub fn lock(&mut self) {
+        self.key = None;
+        self.secrets.clear();
+    }
+
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.secrets.get(id).map(String::as_str)
+    }
+
+    /// Store the secret's value, re-encrypting the vault
+    pub fn set(&mut self, secret: &Secret) -> Result<(), String> {
+        if !self.is_unlocked() {
+            return Err("Unlock the vault to store secrets.".to_string());
+        }
+
+        self.secrets.insert(secret.id.clone(), secret.value.clone());
+        self.save()
+    }
+
+    /// Remove the secret's value, re-encrypting the vault
+    pub fn remove(&mut self, secret: &Secret) -> Result<(), String> {
+        if self.secrets.remove(&secret.id).is_some() {
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Encrypt the secrets with a fresh nonce and write the vault file
+    fn save(&self) -> Result<(), String> {
+        let key = self.key.ok_or("Unlock the vault to store secrets.")?;
+
+        let plaintext = serde_json::to_vec(&self.secrets).map_err(|error| error.to_string())?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&key.into())
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|error| format!("Could not encrypt the vault - {error}"))?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            salt: STANDARD.encode(&self.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+        }
+
+        let contents = serde_json::to_string_pretty(&file).map_err(|error| error.to_string())?;
+        std::fs::write(&self.path, contents)
+            .map_err(|error| format!("Could not write {} - {error}", self.path.display()))
+    }
+}
+
+/// Derive the encryption key from the master password with Argon2id
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|error| format!("Could not derive the key - {error}"))?;
+
+    Ok(key)
+}
@@ -0,0 +1,227 @@
+//! AWS Signature Version 4, signing requests for services such as API Gateway
+//!
+//! See <https://docs.aws.amazon.com/IAM/latest/UserGuide/reference_sigv-create-signed-request.html>
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode};
+use sha2::{Digest, Sha256};
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// The payload hash sent when the body is streamed, and so can't be hashed up front
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+/// Everything but the unreserved characters is encoded, see RFC 3986 section 2.3
+const ENCODED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// The credentials and scope a request is signed with
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct SigV4 {
+    pub access_key: String,
+    pub secret_key: String,
+
+    /// Sent as `X-Amz-Security-Token` when using temporary credentials
+    pub session_token: String,
+
+    /// e.g. eu-west-2
+    pub region: String,
+
+    /// e.g. execute-api
+    pub service: String,
+}
+
+/// Sign the request, adding the `Authorization`, `X-Amz-Date` and any `X-Amz-Security-Token` headers
+///
+/// Every header already on the request is signed, along with the host, path, query string and body.
+pub fn sign(
+    sigv4: &SigV4,
+    request: &mut reqwest::Request,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    if sigv4.access_key.is_empty() || sigv4.secret_key.is_empty() {
+        return Err("AWS signing needs an access key and secret key.".to_string());
+    }
+    if sigv4.region.is_empty() || sigv4.service.is_empty() {
+        return Err("AWS signing needs a region and service.".to_string());
+    }
+
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let payload_hash = match request.body() {
+        None => hex(&Sha256::digest(b"")),
+        Some(body) => match body.as_bytes() {
+            Some(bytes) => hex(&Sha256::digest(bytes)),
+            None => UNSIGNED_PAYLOAD.to_string(),
+        },
+    };
+
+    let headers = request.headers_mut();
+    headers.remove(reqwest::header::AUTHORIZATION);
+    insert(headers, "x-amz-date", &amz_date)?;
+    if !sigv4.session_token.is_empty() {
+        insert(headers, "x-amz-security-token", &sigv4.session_token)?;
+    }
+    // S3 refuses requests without the payload hash
+    if sigv4.service == "s3" {
+        insert(headers, "x-amz-content-sha256", &payload_hash)?;
+    }
+
+    let (canonical_headers, signed_headers) = canonical_headers(request)?;
+    let canonical_request = [
+        request.method().as_str(),
+        &canonical_uri(request.url(), &sigv4.service),
+        &canonical_query(request.url()),
+        &canonical_headers,
+        &signed_headers,
+        &payload_hash,
+    ]
+    .join("\n");
+
+    let scope = format!("{date}/{}/{}/aws4_request", sigv4.region, sigv4.service);
+    let string_to_sign = [
+        ALGORITHM,
+        &amz_date,
+        &scope,
+        &hex(&Sha256::digest(canonical_request.as_bytes())),
+    ]
+    .join("\n");
+
+    let key = signing_key(&sigv4.secret_key, &date, &sigv4.region, &sigv4.service);
+    let signature = hex(&hmac(&key, &string_to_sign));
+
+    let authorization = format!(
+        "{ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        sigv4.access_key
+    );
+    insert(request.headers_mut(), "authorization", &authorization)
+}
+
+/// Derive the key for the day, region and service from the secret key
+pub fn signing_key(secret_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{secret_key}").as_bytes(), date);
+    let key = hmac(&key, region);
+    let key = hmac(&key, service);
+    hmac(&key, "aws4_request")
+}
+
+/// The lowercase `name:value` lines for each header, and the `;` separated names signed
+///
+/// The host isn't one of the request's headers until it's sent, so it's taken from the URL.
+fn canonical_headers(request: &reqwest::Request) -> Result<(String, String), String> {
+    let url = request.url();
+    let mut host = url
+        .host_str()
+        .ok_or("The URI has no host to sign.")?
+        .to_string();
+    if let Some(port) = url.port() {
+        host.push_str(&format!(":{port}"));
+    }
+
+    let mut headers: Vec<(String, String)> = vec![("host".to_string(), host)];
+    for name in request.headers().keys() {
+        if name == reqwest::header::HOST {
+            continue;
+        }
+
+        // Repeated headers are signed as one, with their values comma separated
+        let value = request
+            .headers()
+            .get_all(name)
+            .iter()
+            .map(|value| {
+                String::from_utf8_lossy(value.as_bytes())
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        headers.push((name.as_str().to_string(), value));
+    }
+    headers.sort();
+
+    let canonical = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    Ok((canonical, signed))
+}
+
+/// The path with each segment encoded, S3 is the only service which doesn't encode it twice
+fn canonical_uri(url: &url::Url, service: &str) -> String {
+    url.path()
+        .split('/')
+        .map(|segment| {
+            let decoded = percent_decode_str(segment).decode_utf8_lossy();
+            let encoded = utf8_percent_encode(&decoded, ENCODED).to_string();
+            if service == "s3" {
+                encoded
+            } else {
+                utf8_percent_encode(&encoded, ENCODED).to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The query parameters encoded and sorted by name, then value
+///
+/// The raw query is decoded rather than read as a form, as AWS takes `+` to be a plus rather than a space.
+fn canonical_query(url: &url::Url) -> String {
+    let encode = |text: &str| {
+        utf8_percent_encode(&percent_decode_str(text).decode_utf8_lossy(), ENCODED).to_string()
+    };
+
+    let mut parameters: Vec<(String, String)> = url
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|parameter| !parameter.is_empty())
+        .map(|parameter| {
+            let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            (encode(name), encode(value))
+        })
+        .collect();
+    parameters.sort();
+
+    parameters
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Set the header on the request, replacing any existing value
+fn insert(
+    headers: &mut reqwest::header::HeaderMap,
+    name: &'static str,
+    value: &str,
+) -> Result<(), String> {
+    let value = reqwest::header::HeaderValue::from_str(value)
+        .map_err(|error| format!("{name}: {error}"))?;
+    headers.insert(name, value);
+    Ok(())
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
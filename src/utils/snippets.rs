@@ -0,0 +1,502 @@
+//! Client code generated from a request, to move a working request into a project
+
+use crate::utils::{
+    formats::{content_type_header, request_headers},
+    request::{ContentType, Method, Request},
+};
+
+/// The boundary used when writing multipart bodies as raw HTTP
+const MULTIPART_BOUNDARY: &str = "ReqwesturBoundary";
+
+/// The languages and clients code can be generated for
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, Eq, PartialEq)]
+pub enum Snippet {
+    #[default]
+    REQWEST,
+    REQWESTASYNC,
+    PYTHON,
+    JAVASCRIPT,
+    GO,
+    HTTP,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for Snippet {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::REQWEST => "Rust (reqwest)",
+            Self::REQWESTASYNC => "Rust (reqwest async)",
+            Self::PYTHON => "Python (requests)",
+            Self::JAVASCRIPT => "JavaScript (fetch)",
+            Self::GO => "Go (net/http)",
+            Self::HTTP => "HTTP/1.1",
+        };
+
+        str.to_string()
+    }
+}
+
+impl Snippet {
+    /// A list to offer all snippet types for iteration
+    const OPTIONS: [Self; 6] = [
+        Self::REQWEST,
+        Self::REQWESTASYNC,
+        Self::PYTHON,
+        Self::JAVASCRIPT,
+        Self::GO,
+        Self::HTTP,
+    ];
+
+    /// Return an iterable of the available snippet types
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+
+    /// The extension used to pick the syntax highlighting
+    pub fn syntax(&self) -> &'static str {
+        match self {
+            Self::REQWEST | Self::REQWESTASYNC => "rs",
+            Self::PYTHON => "py",
+            Self::JAVASCRIPT => "js",
+            Self::GO => "go",
+            Self::HTTP => "http",
+        }
+    }
+
+    /// Generate the code that sends the request
+    pub fn generate(&self, request: &Request) -> String {
+        match self {
+            Self::REQWEST => reqwest(request, false),
+            Self::REQWESTASYNC => reqwest(request, true),
+            Self::PYTHON => python(request),
+            Self::JAVASCRIPT => javascript(request),
+            Self::GO => go(request),
+            Self::HTTP => http(request),
+        }
+    }
+}
+
+/// The URI to send the request to, including an API key sent as a query parameter
+fn uri(request: &Request) -> String {
+    request.auth.uri(&request.address.uri)
+}
+
+/// The headers to send, including the credentials and the Content-Type of JSON, text and URL encoded bodies
+fn headers(request: &Request) -> Vec<(String, String)> {
+    let mut headers = request_headers(request);
+
+    // Multipart boundaries are chosen by each client
+    let sets_header = !matches!(
+        request.content_type,
+        ContentType::EMPTY | ContentType::MULTIPART
+    );
+    if sets_header && content_type_header(request).is_none() {
+        headers.push(("Content-Type".to_string(), request.content_type.to_string()));
+    }
+
+    headers
+}
+
+/// The body of a JSON or text request
+fn text_body(request: &Request) -> Option<&str> {
+    match request.content_type {
+        ContentType::JSON | ContentType::TEXT => request.body.as_deref(),
+        _ => None,
+    }
+}
+
+/// A double quoted string literal, JSON escapes are valid in each of the languages
+fn quoted(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+/// A Rust string literal, raw strings are used for text containing quotes or line breaks
+fn rust_string(text: &str) -> String {
+    if !text.contains(['"', '\\', '\n']) {
+        return format!("\"{text}\"");
+    }
+
+    let mut hashes = String::from("#");
+    while text.contains(&format!("\"{hashes}")) {
+        hashes.push('#');
+    }
+
+    format!("r{hashes}\"{text}\"{hashes}")
+}
+
+/// Rust using the reqwest crate
+fn reqwest(request: &Request, is_async: bool) -> String {
+    let (client, multipart, main, wait) = if is_async {
+        (
+            "reqwest::Client",
+            "reqwest::multipart::Form",
+            "#[tokio::main]\nasync fn main()",
+            ".await",
+        )
+    } else {
+        (
+            "reqwest::blocking::Client",
+            "reqwest::blocking::multipart::Form",
+            "fn main()",
+            "",
+        )
+    };
+
+    let mut builder = vec![format!(
+        "        .{}({})",
+        request.method.to_string().to_lowercase(),
+        rust_string(&uri(request))
+    )];
+
+    for (name, value) in headers(request) {
+        builder.push(format!(
+            "        .header({}, {})",
+            rust_string(&name),
+            rust_string(&value)
+        ));
+    }
+
+    match request.content_type {
+        ContentType::EMPTY => {}
+        ContentType::JSON | ContentType::TEXT => {
+            if let Some(body) = text_body(request) {
+                builder.push(format!("        .body({})", rust_string(body)));
+            }
+        }
+        ContentType::XWWWFORMURLENCODED => {
+            let fields: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| {
+                    format!(
+                        "            ({}, {}),",
+                        rust_string(name),
+                        rust_string(value)
+                    )
+                })
+                .collect();
+            builder.push(format!(
+                "        .form(&[\n{}\n        ])",
+                fields.join("\n")
+            ));
+        }
+        ContentType::MULTIPART => {
+            let mut form = vec![format!("            {multipart}::new()")];
+            for (name, value) in &request.params {
+                form.push(format!(
+                    "                .text({}, {})",
+                    rust_string(name),
+                    rust_string(value)
+                ));
+            }
+            builder.push(format!(
+                "        .multipart(\n{},\n        )",
+                form.join("\n")
+            ));
+        }
+    }
+
+    format!(
+        "{main} -> Result<(), Box<dyn std::error::Error>> {{
+    let client = {client}::new();
+    let response = client
+{}
+        .send(){wait}?;
+
+    println!(\"{{}}\", response.status());
+    println!(\"{{}}\", response.text(){wait}?);
+
+    Ok(())
+}}
+",
+        builder.join("\n")
+    )
+}
+
+/// Python using the requests package
+fn python(request: &Request) -> String {
+    let mut arguments = vec![format!("    {},", quoted(&uri(request)))];
+
+    let headers = headers(request);
+    if !headers.is_empty() {
+        let headers: Vec<String> = headers
+            .iter()
+            .map(|(name, value)| format!("        {}: {},", quoted(name), quoted(value)))
+            .collect();
+        arguments.push(format!("    headers={{\n{}\n    }},", headers.join("\n")));
+    }
+
+    match request.content_type {
+        ContentType::EMPTY => {}
+        ContentType::JSON | ContentType::TEXT => {
+            if let Some(body) = text_body(request) {
+                arguments.push(format!("    data={},", quoted(body)));
+            }
+        }
+        ContentType::XWWWFORMURLENCODED => {
+            let fields: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| format!("        ({}, {}),", quoted(name), quoted(value)))
+                .collect();
+            arguments.push(format!("    data=[\n{}\n    ],", fields.join("\n")));
+        }
+        ContentType::MULTIPART => {
+            // A file name of None sends the field as text
+            let fields: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| {
+                    format!("        ({}, (None, {})),", quoted(name), quoted(value))
+                })
+                .collect();
+            arguments.push(format!("    files=[\n{}\n    ],", fields.join("\n")));
+        }
+    }
+
+    format!(
+        "import requests
+
+response = requests.{}(
+{}
+)
+
+print(response.status_code)
+print(response.text)
+",
+        request.method.to_string().to_lowercase(),
+        arguments.join("\n")
+    )
+}
+
+/// JavaScript using the Fetch API
+fn javascript(request: &Request) -> String {
+    let mut setup = String::new();
+    let mut options = vec![format!(
+        "  method: {},",
+        quoted(&request.method.to_string())
+    )];
+
+    let headers = headers(request);
+    if !headers.is_empty() {
+        let headers: Vec<String> = headers
+            .iter()
+            .map(|(name, value)| format!("    {}: {},", quoted(name), quoted(value)))
+            .collect();
+        options.push(format!("  headers: {{\n{}\n  }},", headers.join("\n")));
+    }
+
+    match request.content_type {
+        ContentType::EMPTY => {}
+        ContentType::JSON | ContentType::TEXT => {
+            if let Some(body) = text_body(request) {
+                options.push(format!("  body: {},", quoted(body)));
+            }
+        }
+        ContentType::XWWWFORMURLENCODED => {
+            let fields: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| format!("    [{}, {}],", quoted(name), quoted(value)))
+                .collect();
+            options.push(format!(
+                "  body: new URLSearchParams([\n{}\n  ]),",
+                fields.join("\n")
+            ));
+        }
+        ContentType::MULTIPART => {
+            setup.push_str("const body = new FormData();\n");
+            for (name, value) in &request.params {
+                setup.push_str(&format!(
+                    "body.append({}, {});\n",
+                    quoted(name),
+                    quoted(value)
+                ));
+            }
+            setup.push('\n');
+            options.push("  body,".to_string());
+        }
+    }
+
+    format!(
+        "{setup}const response = await fetch({}, {{
+{}
+}});
+
+console.log(response.status);
+console.log(await response.text());
+",
+        quoted(&uri(request)),
+        options.join("\n")
+    )
+}
+
+/// Go using the standard library's net/http package
+fn go(request: &Request) -> String {
+    let mut imports = vec!["fmt", "io", "net/http"];
+    let mut setup = String::new();
+    let mut headers: Vec<String> = headers(request)
+        .iter()
+        .map(|(name, value)| format!("\trequest.Header.Add({}, {})", quoted(name), quoted(value)))
+        .collect();
+
+    let body = match request.content_type {
+        ContentType::EMPTY => "nil",
+        ContentType::JSON | ContentType::TEXT => match text_body(request) {
+            Some(body) => {
+                imports.push("strings");
+                let literal = if body.contains('`') {
+                    quoted(body)
+                } else {
+                    format!("`{body}`")
+                };
+                setup.push_str(&format!("\tbody := strings.NewReader({literal})\n\n"));
+                "body"
+            }
+            None => "nil",
+        },
+        ContentType::XWWWFORMURLENCODED => {
+            imports.extend(["net/url", "strings"]);
+            setup.push_str("\tform := url.Values{}\n");
+            for (name, value) in &request.params {
+                setup.push_str(&format!(
+                    "\tform.Add({}, {})\n",
+                    quoted(name),
+                    quoted(value)
+                ));
+            }
+            setup.push('\n');
+            "strings.NewReader(form.Encode())"
+        }
+        ContentType::MULTIPART => {
+            imports.extend(["bytes", "mime/multipart"]);
+            setup.push_str("\tbody := &bytes.Buffer{}\n\twriter := multipart.NewWriter(body)\n");
+            for (name, value) in &request.params {
+                setup.push_str(&format!(
+                    "\twriter.WriteField({}, {})\n",
+                    quoted(name),
+                    quoted(value)
+                ));
+            }
+            setup.push_str("\twriter.Close()\n\n");
+            headers.push(
+                "\trequest.Header.Set(\"Content-Type\", writer.FormDataContentType())".to_string(),
+            );
+            "body"
+        }
+    };
+
+    imports.sort();
+    let imports: Vec<String> = imports
+        .iter()
+        .map(|import| format!("\t{}", quoted(import)))
+        .collect();
+
+    let headers = if headers.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n\n", headers.join("\n"))
+    };
+
+    format!(
+        "package main
+
+import (
+{}
+)
+
+func main() {{
+{setup}\trequest, err := http.NewRequest({}, {}, {body})
+\tif err != nil {{
+\t\tpanic(err)
+\t}}
+{headers}\tresponse, err := http.DefaultClient.Do(request)
+\tif err != nil {{
+\t\tpanic(err)
+\t}}
+\tdefer response.Body.Close()
+
+\tcontent, err := io.ReadAll(response.Body)
+\tif err != nil {{
+\t\tpanic(err)
+\t}}
+
+\tfmt.Println(response.Status)
+\tfmt.Println(string(content))
+}}
+",
+        imports.join("\n"),
+        quoted(&request.method.to_string()),
+        quoted(&uri(request)),
+    )
+}
+
+/// The raw HTTP/1.1 message, with CRLF line endings as sent on the wire
+fn http(request: &Request) -> String {
+    let uri = uri(request);
+    let (target, host) = match reqwest::Url::parse(&uri) {
+        Ok(url) => {
+            let mut target = url.path().to_string();
+            if let Some(query) = url.query() {
+                target = format!("{target}?{query}");
+            }
+
+            let host = match url.port() {
+                Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+                None => url.host_str().unwrap_or_default().to_string(),
+            };
+
+            (target, host)
+        }
+        Err(_) => (uri, String::new()),
+    };
+
+    let mut headers = headers(request);
+
+    let body = match request.content_type {
+        ContentType::EMPTY => String::new(),
+        ContentType::JSON | ContentType::TEXT => text_body(request).unwrap_or_default().to_string(),
+        ContentType::XWWWFORMURLENCODED => url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&request.params)
+            .finish(),
+        ContentType::MULTIPART => {
+            headers.push((
+                "Content-Type".to_string(),
+                format!(
+                    "{}; boundary={MULTIPART_BOUNDARY}",
+                    request.content_type.to_string()
+                ),
+            ));
+
+            let mut parts: Vec<String> = request
+                .params
+                .iter()
+                .map(|(name, value)| {
+                    format!(
+                        "--{MULTIPART_BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}"
+                    )
+                })
+                .collect();
+            parts.push(format!("--{MULTIPART_BOUNDARY}--\r\n"));
+            parts.join("\r\n")
+        }
+    };
+
+    let mut lines = vec![
+        format!("{} {target} HTTP/1.1", request.method.to_string()),
+        format!("Host: {host}"),
+    ];
+    lines.extend(
+        headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}")),
+    );
+
+    let has_body = ![Method::GET, Method::DELETE].contains(&request.method) || !body.is_empty();
+    if has_body {
+        lines.push(format!("Content-Length: {}", body.len()));
+    }
+
+    // The start line and headers end with CRLF, and the body's length is counted in bytes
+    format!("{}\r\n\r\n{body}", lines.join("\r\n"))
+}
@@ -0,0 +1,132 @@
+//! The TLS settings the client is built with, e.g. the root certificates of private CAs
+
+/// The oldest TLS version servers may use
+///
+/// TLS 1.3 can't be required as the native TLS backend doesn't support it as a minimum.
+#[derive(Default, serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Hash)]
+pub enum TlsVersion {
+    /// The operating system's minimum
+    #[default]
+    DEFAULT,
+    TLS1_0,
+    TLS1_1,
+    TLS1_2,
+}
+
+/// Implement the ToString function, using std::fmt::Display causes a stack overflow
+impl ToString for TlsVersion {
+    fn to_string(&self) -> String {
+        let str = match self {
+            Self::DEFAULT => "System Default",
+            Self::TLS1_0 => "TLS 1.0",
+            Self::TLS1_1 => "TLS 1.1",
+            Self::TLS1_2 => "TLS 1.2",
+        };
+
+        str.to_string()
+    }
+}
+
+impl TlsVersion {
+    const OPTIONS: [Self; 4] = [Self::DEFAULT, Self::TLS1_0, Self::TLS1_1, Self::TLS1_2];
+
+    pub fn values() -> Vec<Self> {
+        Vec::from(Self::OPTIONS)
+    }
+
+    /// The version passed to the client builder, if one is required
+    pub fn version(&self) -> Option<reqwest::tls::Version> {
+        match self {
+            Self::DEFAULT => None,
+            Self::TLS1_0 => Some(reqwest::tls::Version::TLS_1_0),
+            Self::TLS1_1 => Some(reqwest::tls::Version::TLS_1_1),
+            Self::TLS1_2 => Some(reqwest::tls::Version::TLS_1_2),
+        }
+    }
+}
+
+/// How the client verifies and connects to servers over TLS
+#[derive(serde::Deserialize, serde::Serialize, Clone, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct TlsSettings {
+    /// CA certificates trusted as well as the system's, PEM bundles or DER files
+    pub root_certificates: Vec<std::path::PathBuf>,
+
+    /// The oldest TLS version servers may use
+    pub min_version: TlsVersion,
+
+    /// Whether the host is sent as the server name (SNI) when connecting
+    pub sni: bool,
+
+    /// Hosts connected to at another IP address, e.g. `10.0.0.5`, keeping the host as the server name
+    ///
+    /// A port in the URL always wins, the address's port is only used when the URL has none.
+    #[serde(alias = "server_names")]
+    pub connect_to: Vec<(String, String)>,
+}
+
+impl Default for TlsSettings {
+    fn default() -> Self {
+        Self {
+            root_certificates: Vec::new(),
+            min_version: TlsVersion::default(),
+            sni: true,
+            connect_to: Vec::new(),
+        }
+    }
+}
+
+impl TlsSettings {
+    /// Read the extra root certificates, a PEM file may hold a bundle of certificates
+    pub fn certificates(&self) -> Result<Vec<reqwest::Certificate>, String> {
+        let mut certificates = Vec::new();
+
+        for file_path in &self.root_certificates {
+            let contents = std::fs::read(file_path)
+                .map_err(|error| format!("Could not read {} - {error}", file_path.display()))?;
+
+            let is_pem = contents
+                .windows(b"-----BEGIN".len())
+                .any(|window| window == b"-----BEGIN");
+            let read = if is_pem {
+                reqwest::Certificate::from_pem_bundle(&contents)
+            } else {
+                reqwest::Certificate::from_der(&contents).map(|certificate| vec![certificate])
+            };
+
+            certificates.extend(read.map_err(|error| {
+                format!(
+                    "The CA certificate {} can't be read - {error}",
+                    file_path.display()
+                )
+            })?);
+        }
+
+        Ok(certificates)
+    }
+
+    /// The address each overridden host is connected to, a port of 0 when none is entered uses the scheme's port
+    pub fn addresses(&self) -> Result<Vec<(String, std::net::SocketAddr)>, String> {
+        self.connect_to
+            .iter()
+            .filter(|(host, _)| !host.trim().is_empty())
+            .map(|(host, address)| {
+                let address = address.trim();
+                let parsed = address.parse::<std::net::SocketAddr>().or_else(|_| {
+                    address
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .parse::<std::net::IpAddr>()
+                        .map(|ip| std::net::SocketAddr::new(ip, 0))
+                });
+
+                match parsed {
+                    Ok(parsed) => Ok((host.trim().to_string(), parsed)),
+                    Err(_) => Err(format!(
+                        "{host} can't be connected to at {address}, enter an IP address and optional port."
+                    )),
+                }
+            })
+            .collect()
+    }
+}
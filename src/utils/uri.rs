@@ -0,0 +1,112 @@
+//! Parsing the request URI into the URL that is sent, explaining any changes made to it
+
+/// The scheme assumed when the URI doesn't have one
+pub const DEFAULT_SCHEME: &str = "https";
+
+/// Parse the URI into the URL sent, with a note for each way the URL differs from what was typed
+///
+/// A missing scheme defaults to `https://`, international hosts are converted to punycode and
+/// the path and query are percent encoded where they need to be.
+pub fn normalise(uri: &str) -> Result<(reqwest::Url, Vec<String>), String> {
+    let mut notes = Vec::new();
+
+    let trimmed = uri.trim();
+    if trimmed.is_empty() {
+        return Err("Enter the URL to send the request to.".to_string());
+    }
+    if trimmed != uri {
+        notes.push("The spaces around the URL are removed.".to_string());
+    }
+
+    // e.g. `localhost:8080/users` would otherwise be read as the scheme `localhost`
+    let with_scheme = if has_scheme(trimmed) {
+        trimmed.to_string()
+    } else {
+        notes.push(format!(
+            "No scheme was given, so {DEFAULT_SCHEME}:// is used."
+        ));
+        format!("{DEFAULT_SCHEME}://{trimmed}")
+    };
+
+    let url = reqwest::Url::parse(&with_scheme).map_err(describe)?;
+
+    if !["http", "https"].contains(&url.scheme()) {
+        return Err(format!(
+            "Only http:// and https:// URLs can be sent, not {}://.",
+            url.scheme()
+        ));
+    }
+    let Some(host) = url.host_str() else {
+        return Err("The URL needs a host.".to_string());
+    };
+    if url.port() == Some(0) {
+        return Err("The port must be a number from 1 to 65535.".to_string());
+    }
+
+    let (typed_host, typed_rest) = split_authority(&with_scheme);
+    if !typed_host.is_ascii() {
+        notes.push(format!("The host {typed_host} is sent as {host}."));
+    }
+
+    let rest = &url[url::Position::BeforePath..];
+    let typed_rest = if typed_rest.is_empty() || typed_rest.starts_with(['?', '#']) {
+        format!("/{typed_rest}")
+    } else {
+        typed_rest.to_string()
+    };
+    if rest != typed_rest {
+        notes.push(format!("The path and query are sent encoded as {rest}"));
+    }
+
+    Ok((url, notes))
+}
+
+/// Whether the URI starts with a scheme followed by `://`
+fn has_scheme(uri: &str) -> bool {
+    uri.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.starts_with(|character: char| character.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || "+-.".contains(character))
+    })
+}
+
+/// The host as it was typed, and everything after the authority
+fn split_authority(uri: &str) -> (&str, &str) {
+    let after_scheme = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    let end = after_scheme
+        .find(['/', '?', '#'])
+        .unwrap_or(after_scheme.len());
+    let (authority, rest) = after_scheme.split_at(end);
+
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = if host_and_port.starts_with('[') {
+        // An IPv6 address, e.g. [::1]:8080
+        host_and_port
+            .find(']')
+            .map_or(host_and_port, |end| &host_and_port[..=end])
+    } else {
+        host_and_port
+            .rsplit_once(':')
+            .map_or(host_and_port, |(host, _)| host)
+    };
+
+    (host, rest)
+}
+
+/// Explain why the URL couldn't be parsed
+fn describe(error: url::ParseError) -> String {
+    match error {
+        url::ParseError::EmptyHost => "The URL needs a host.".to_string(),
+        url::ParseError::IdnaError => "The host isn't a valid domain name.".to_string(),
+        url::ParseError::InvalidPort => "The port must be a number from 1 to 65535.".to_string(),
+        url::ParseError::InvalidIpv4Address => "The host isn't a valid IPv4 address.".to_string(),
+        url::ParseError::InvalidIpv6Address => "The host isn't a valid IPv6 address.".to_string(),
+        url::ParseError::InvalidDomainCharacter => {
+            "The host contains a character which isn't allowed.".to_string()
+        }
+        error => format!("The URL can't be parsed - {error}."),
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+};
+
+use reqwestur::utils::{
+    auth::{self, ApiKeyLocation, Auth, AuthKind},
+    engine::{self, ClientSettings, RequestExecutor},
+    environments::{self, Environment},
+    formats,
+    request::{Address, Method, Request},
+};
+
+fn request(uri: &str, auth: Auth) -> Request {
+    Request {
+        method: Method::GET,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        headers: vec![("Authorization".to_string(), "replaced".to_string())],
+        auth,
+        ..Default::default()
+    }
+}
+
+fn executor() -> RequestExecutor {
+    RequestExecutor::new(ClientSettings::default()).expect("the client should build")
+}
+
+fn header(request: &reqwest::Request, name: &str) -> Vec<String> {
+    request
+        .headers()
+        .get_all(name)
+        .iter()
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn applies_basic_bearer_and_api_key_credentials() {
+    let executor = executor();
+
+    let basic = Auth {
+        kind: AuthKind::BASIC,
+        username: "user".to_string(),
+        password: "pass".to_string(),
+        ..Default::default()
+    };
+    let built = executor
+        .build(&request("https://example.com", basic))
+        .unwrap();
+    assert_eq!(vec!["Basic dXNlcjpwYXNz"], header(&built, "authorization"));
+
+    let bearer = Auth {
+        kind: AuthKind::BEARER,
+        token: "abc".to_string(),
+        ..Default::default()
+    };
+    let built = executor
+        .build(&request("https://example.com", bearer))
+        .unwrap();
+    assert_eq!(vec!["Bearer abc"], header(&built, "authorization"));
+
+    let api_key = Auth {
+        kind: AuthKind::APIKEY,
+        key_name: "X-API-Key".to_string(),
+        key_value: "k3y".to_string(),
+        ..Default::default()
+    };
+    let built = executor
+        .build(&request("https://example.com", api_key.clone()))
+        .unwrap();
+    assert_eq!(vec!["k3y"], header(&built, "x-api-key"));
+
+    let query = Auth {
+        key_location: ApiKeyLocation::QUERY,
+        key_name: "api key".to_string(),
+        ..api_key
+    };
+    let built = executor
+        .build(&request("https://example.com/users?page=2", query))
+        .unwrap();
+    assert_eq!(
+        "https://example.com/users?page=2&api+key=k3y",
+        built.url().as_str()
+    );
+    assert_eq!(vec!["replaced"], header(&built, "authorization"));
+}
+
+#[test]
+fn answers_digest_challenges() {
+    // The examples from RFC 7616 section 3.9.1
+    let challenge = auth::digest_challenge(
+        "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", algorithm=SHA-256, \
+         nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+         opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"",
+    )
+    .unwrap();
+    let credentials = Auth {
+        kind: AuthKind::DIGEST,
+        username: "Mufasa".to_string(),
+        password: "Circle of Life".to_string(),
+        ..Default::default()
+    };
+    let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    let authorization =
+        auth::digest(&credentials, &challenge, "GET", "/dir/index.html", cnonce).unwrap();
+    assert!(
+        authorization.contains(
+            "response=\"753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1\""
+        ),
+        "{authorization}"
+    );
+    assert!(authorization.contains("qop=auth, nc=00000001"));
+    assert!(authorization.contains("opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\""));
+
+    let md5: Vec<(String, String)> = challenge
+        .into_iter()
+        .filter(|(name, _)| name != "algorithm")
+        .collect();
+    let authorization = auth::digest(&credentials, &md5, "GET", "/dir/index.html", cnonce).unwrap();
+    assert!(
+        authorization.contains("response=\"8ca523f5e9506fed4657c9700eebdbec\""),
+        "{authorization}"
+    );
+
+    let auth_int = auth::digest_challenge("Digest nonce=\"abc\", qop=\"auth-int\"").unwrap();
+    assert_eq!(
+        Err("The Digest protection auth-int isn't supported.".to_string()),
+        auth::digest(&credentials, &auth_int, "GET", "/", cnonce)
+    );
+}
+
+#[test]
+fn completes_the_digest_handshake() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    // Challenge the first request, then echo the credentials of the second
+    std::thread::spawn(move || {
+        for attempt in 0..2 {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0u8; 4096];
+            let read = stream.read(&mut buffer).unwrap_or_default();
+            let received = String::from_utf8_lossy(&buffer[..read]).to_string();
+
+            let response = if attempt == 0 {
+                "HTTP/1.1 401 Unauthorized\r\n\
+                 WWW-Authenticate: Basic realm=\"other\"\r\n\
+                 WWW-Authenticate: Digest realm=\"test\", qop=\"auth\", nonce=\"n0nce\"\r\n\
+                 Content-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string()
+            } else {
+                let authorization = received
+                    .lines()
+                    .find_map(|line| line.strip_prefix("authorization: "))
+                    .unwrap_or_default()
+                    .to_string();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{authorization}",
+                    authorization.len()
+                )
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    let credentials = Auth {
+        kind: AuthKind::DIGEST,
+        username: "{{user}}".to_string(),
+        password: "secret".to_string(),
+        ..Default::default()
+    };
+    let template = request(&format!("http://{address}/private?id=1"), credentials);
+    let sent = Environment {
+        variables: vec![("user".to_string(), "Mufasa".to_string())],
+        ..Environment::new("Test")
+    }
+    .apply(&template);
+
+    let response = engine::runtime()
+        .block_on(executor().execute(&sent))
+        .expect("the request should be answered");
+    assert_eq!(200, response.status.0);
+
+    // The answer can be checked once the client's nonce is known
+    let cnonce = response
+        .body
+        .split("cnonce=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+    let challenge =
+        auth::digest_challenge("Digest realm=\"test\", qop=\"auth\", nonce=\"n0nce\"").unwrap();
+    assert_eq!(
+        auth::digest(&sent.auth, &challenge, "GET", "/private?id=1", cnonce).unwrap(),
+        response.body
+    );
+}
+
+#[test]
+fn fills_credentials_from_the_environment() {
+    let template = request(
+        "https://example.com",
+        Auth {
+            kind: AuthKind::BEARER,
+            token: "{{token}}".to_string(),
+            password: "{{unused}}".to_string(),
+            ..Default::default()
+        },
+    );
+
+    // Only the credentials sent by the kind of authentication need a value
+    assert_eq!(vec!["token"], environments::unresolved(&template, &[]));
+
+    let variables = vec![("token".to_string(), "s3cr3t".to_string())];
+    let sent = environments::resolve(&template, &variables);
+    assert_eq!("s3cr3t", sent.auth.token);
+    assert_eq!(
+        "{{token}}",
+        environments::redact(&sent, &variables).auth.token
+    );
+}
+
+#[test]
+fn exports_the_credentials() {
+    let bearer = Request {
+        name: "Users".to_string(),
+        auth: Auth {
+            kind: AuthKind::BEARER,
+            token: "abc".to_string(),
+            ..Default::default()
+        },
+        ..request("https://example.com/users", Auth::default())
+    };
+
+    let http = formats::http::serialize(std::slice::from_ref(&bearer));
+    assert!(http.contains("Authorization: Bearer abc\n"), "{http}");
+    assert!(!http.contains("replaced"), "{http}");
+
+    let postman = formats::postman::serialize("Collection", std::slice::from_ref(&bearer));
+    assert!(postman.contains("\"value\": \"Bearer abc\""), "{postman}");
+
+    let har = formats::har::serialize(std::slice::from_ref(&bearer));
+    assert!(har.contains("\"value\": \"Bearer abc\""), "{har}");
+
+    let api_key = Auth {
+        kind: AuthKind::APIKEY,
+        key_name: "api key".to_string(),
+        key_value: "k3y/1".to_string(),
+        key_location: ApiKeyLocation::QUERY,
+        ..Default::default()
+    };
+    assert!(api_key.headers().is_empty());
+    assert_eq!(
+        "https://example.com/users?page=2&api%20key=k3y%2F1",
+        api_key.uri("https://example.com/users?page=2")
+    );
+    let curl = formats::curl::serialize(&request("https://example.com/users", api_key));
+    assert!(
+        curl.starts_with("curl 'https://example.com/users?api%20key=k3y%2F1'"),
+        "{curl}"
+    );
+}
+
+#[test]
+fn masks_typed_credentials() {
+    let auth = Auth {
+        kind: AuthKind::BASIC,
+        username: "user".to_string(),
+        password: "pass".to_string(),
+        token: "{{token}}".to_string(),
+        key_value: "Bearer {{key}}".to_string(),
+        ..Default::default()
+    };
+
+    let masked = auth.masked();
+    assert_eq!("user", masked.username);
+    assert_eq!("{{password}}", masked.password);
+    assert_eq!("{{token}}", masked.token);
+    assert_eq!("Bearer {{key}}", masked.key_value);
+    assert!(masked.oauth2.client_secret.is_empty());
+
+    // OAuth 2.0 tokens are fetched again when the request is sent
+    let oauth2 = Auth {
+        kind: AuthKind::OAUTH2,
+        token: "fetched".to_string(),
+        ..Default::default()
+    };
+    assert!(oauth2.masked().token.is_empty());
+}
+
+#[test]
+fn fills_in_masked_basic_credentials_after_an_import() {
+    let typed = request(
+        "https://example.com/login",
+        Auth {
+            kind: AuthKind::BASIC,
+            username: "user".to_string(),
+            password: "pass".to_string(),
+            ..Default::default()
+        },
+    );
+    let masked = [Request {
+        name: "Login".to_string(),
+        auth: typed.auth.masked(),
+        ..typed
+    }];
+    let variables = vec![("password".to_string(), "pass".to_string())];
+
+    // Sent with the password from the environment once the exported file is imported again
+    let sent = |imported: &Request| {
+        let built = executor()
+            .build(&environments::resolve(imported, &variables))
+            .unwrap();
+        header(&built, "authorization")
+    };
+
+    let http = formats::http::serialize(&masked);
+    assert!(
+        http.contains("Authorization: Basic user {{password}}\n"),
+        "{http}"
+    );
+    let imported = formats::http::parse(&http);
+    assert_eq!(vec!["Basic dXNlcjpwYXNz"], sent(&imported.requests[0]));
+
+    let postman = formats::postman::serialize("Collection", &masked);
+    assert!(postman.contains("\"value\": \"{{password}}\""), "{postman}");
+    let imported = formats::postman::parse(&postman).unwrap();
+    assert_eq!(vec!["Basic dXNlcjpwYXNz"], sent(&imported.requests[0]));
+
+    // Formats without their own auth are given a placeholder for the encoded credentials
+    let har = formats::har::serialize(&masked);
+    assert!(har.contains("\"value\": \"Basic {{basicAuth}}\""), "{har}");
+}
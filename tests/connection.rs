@@ -0,0 +1,264 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use reqwestur::utils::{
+    connection::{self, ConnectionFailure},
+    engine,
+    request::{Address, Request, RequestEvent, Response, ResponseView},
+    reqwestur::Reqwestur,
+    tls::TlsSettings,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/tls")
+        .join(name)
+}
+
+/// Serve HTTPS on a local port with a certificate for `tls.test` issued by the test CA
+fn serve_tls() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let identity = native_tls::Identity::from_pkcs8(
+        &std::fs::read(fixture("server.pem")).unwrap(),
+        &std::fs::read(fixture("server.key")).unwrap(),
+    )
+    .unwrap();
+    let acceptor = native_tls::TlsAcceptor::new(identity).unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // Handshakes rejected by the client are expected
+            let Ok(mut stream) = acceptor.accept(stream) else {
+                continue;
+            };
+
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        }
+    });
+
+    address.to_string()
+}
+
+/// Serve plain HTTP on a local port
+fn serve_plain() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        }
+    });
+
+    address.to_string()
+}
+
+/// TLS settings connecting `tls.test` to the local address
+fn tls(address: &str) -> TlsSettings {
+    TlsSettings {
+        connect_to: vec![("tls.test".to_string(), address.to_string())],
+        ..Default::default()
+    }
+}
+
+/// Send a GET request and poll the app until it has finished
+fn send_and_wait(app: &mut Reqwestur, uri: &str) -> Response {
+    *app.request.lock().unwrap() = Request {
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let shared = app.request.clone();
+    assert!(app.send(&mut shared.lock().unwrap()).is_ok());
+
+    let started = Instant::now();
+    while app.request.lock().unwrap().event == RequestEvent::PENDING {
+        assert!(started.elapsed() < Duration::from_secs(20), "no response");
+        std::thread::sleep(Duration::from_millis(20));
+        app.poll();
+    }
+
+    app.request.lock().unwrap().response.clone()
+}
+
+/// Poll the app until the certificate chain of the response has been read
+fn wait_for_handshake(app: &mut Reqwestur) -> Response {
+    let started = Instant::now();
+    while app.request.lock().unwrap().response.connection.inspecting {
+        assert!(started.elapsed() < Duration::from_secs(20), "no handshake");
+        std::thread::sleep(Duration::from_millis(20));
+        app.poll();
+    }
+
+    app.request.lock().unwrap().response.clone()
+}
+
+#[test]
+fn shows_the_handshake_of_trusted_servers() {
+    let address = serve_tls();
+    let port = address.rsplit_once(':').unwrap().1;
+    let mut app = Reqwestur {
+        tls: TlsSettings {
+            root_certificates: vec![fixture("ca.pem")],
+            ..tls(&address)
+        },
+        ..Default::default()
+    };
+
+    let response = send_and_wait(&mut app, &format!("https://tls.test:{port}/"));
+    assert_eq!("ok", response.body);
+    assert!(response.view == ResponseView::RESPONSE);
+
+    // The certificate is read from the response, the handshake only when it's asked for
+    let connection = response.connection;
+    assert_eq!(None, connection.failure);
+    assert_eq!(Some(address.clone()), connection.remote_address);
+    assert_eq!(Some("HTTP/1.1".to_string()), connection.http_version);
+    assert_eq!(None, connection.handshake);
+    let certificate = connection.certificate.unwrap();
+    assert!(certificate.subject.contains("CN=tls.test"));
+    assert_eq!(
+        "FC:AD:7A:F6:C7:58:2A:9D:83:70:3D:CF:A6:7D:8C:78:F1:0A:C9:B9:61:EC:2B:99:9F:33:81:75:2A:9B:88:BD",
+        certificate.fingerprint
+    );
+
+    let shared = app.request.clone();
+    app.inspect(&mut shared.lock().unwrap());
+    let connection = wait_for_handshake(&mut app).connection;
+    let handshake = connection.handshake.unwrap().unwrap();
+    assert!(["TLS 1.2", "TLS 1.3"].contains(&handshake.version.as_str()));
+    assert_eq!(address, handshake.remote_address);
+    assert_eq!(1, handshake.chain.len());
+    assert!(handshake.chain[0].subject.contains("CN=tls.test"));
+    assert!(
+        handshake.chain[0]
+            .issuer
+            .contains("CN=Reqwestur TLS Test CA")
+    );
+    assert_eq!(
+        "FC:AD:7A:F6:C7:58:2A:9D:83:70:3D:CF:A6:7D:8C:78:F1:0A:C9:B9:61:EC:2B:99:9F:33:81:75:2A:9B:88:BD",
+        handshake.chain[0].fingerprint
+    );
+}
+
+#[test]
+fn explains_untrusted_certificates() {
+    let address = serve_tls();
+    let port = address.rsplit_once(':').unwrap().1;
+    let mut app = Reqwestur {
+        tls: tls(&address),
+        ..Default::default()
+    };
+
+    let response = send_and_wait(&mut app, &format!("https://tls.test:{port}/"));
+    assert!(response.view == ResponseView::CONNECTION);
+    assert_eq!(None, response.connection.certificate);
+
+    // The chain is read after the failure so the certificate can be checked
+    let connection = wait_for_handshake(&mut app).connection;
+    let failure = connection.failure.unwrap();
+    assert_eq!(ConnectionFailure::UNTRUSTEDCERTIFICATE, failure);
+    assert!(failure.is_tls());
+    assert!(failure.explanation().contains("TLS settings"));
+    assert!(
+        connection
+            .handshake
+            .unwrap()
+            .is_ok_and(|handshake| handshake.chain[0].subject.contains("CN=tls.test"))
+    );
+}
+
+#[test]
+fn explains_failed_connections() {
+    // Nothing is listening once the listener is dropped
+    let refused = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let mut app = Reqwestur::default();
+    let response = send_and_wait(&mut app, &format!("http://{refused}/"));
+    assert_eq!(
+        Some(ConnectionFailure::REFUSED),
+        response.connection.failure
+    );
+    assert_eq!(None, response.connection.handshake);
+
+    // A plain HTTP server on an HTTPS URL
+    let address = serve_plain();
+    send_and_wait(&mut app, &format!("https://{address}/"));
+    let response = wait_for_handshake(&mut app);
+    assert_eq!(Some(ConnectionFailure::NOTTLS), response.connection.failure);
+    assert!(
+        response
+            .connection
+            .handshake
+            .is_some_and(|handshake| handshake.is_err())
+    );
+
+    // Plain HTTP isn't inspected
+    let response = send_and_wait(&mut app, &format!("http://{address}/"));
+    assert_eq!("ok", response.body);
+    assert_eq!(None, response.connection.failure);
+    assert_eq!(None, response.connection.certificate);
+
+    let shared = app.request.clone();
+    app.inspect(&mut shared.lock().unwrap());
+    assert!(!app.request.lock().unwrap().response.connection.inspecting);
+}
+
+#[test]
+fn inspects_the_handshake_directly() {
+    let address = serve_tls();
+    let port = address.rsplit_once(':').unwrap().1;
+    let url = reqwest::Url::parse(&format!("https://tls.test:{port}/")).unwrap();
+
+    let handshake = engine::runtime()
+        .block_on(connection::inspect(&url, &tls(&address)))
+        .unwrap();
+    assert_eq!(None, handshake.alpn);
+    assert!(!handshake.cipher_suite.is_empty());
+
+    let error = engine::runtime()
+        .block_on(connection::inspect(&url, &TlsSettings::default()))
+        .unwrap_err();
+    assert!(error.starts_with("Could not connect to tls.test"));
+}
+
+#[test]
+fn explains_servers_the_inspector_cannot_read() {
+    // A server refusing TLS 1.2 and 1.3 with a protocol_version alert, as TLS 1.0 only servers do
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = stream.write_all(&[0x15, 0x03, 0x01, 0x00, 0x02, 0x02, 0x46]);
+        }
+    });
+
+    let port = address.rsplit_once(':').unwrap().1;
+    let url = reqwest::Url::parse(&format!("https://tls.test:{port}/")).unwrap();
+    let error = engine::runtime()
+        .block_on(connection::inspect(&url, &tls(&address)))
+        .unwrap_err();
+    assert!(
+        error.starts_with("The server only offers TLS 1.1 or older"),
+        "{error}"
+    );
+}
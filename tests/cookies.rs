@@ -0,0 +1,247 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use reqwestur::utils::{
+    cookies::{self, Cookie, CookieJar},
+    environments::Environment,
+    request::{Address, Method, Request, RequestEvent},
+    reqwestur::Reqwestur,
+};
+
+/// Serve two requests on a local port, setting a cookie on the first and reporting each request's headers
+fn serve() -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let responses = [
+            "HTTP/1.1 200 OK\r\nSet-Cookie: session=abc; Path=/; Max-Age=3600; Secure; HttpOnly\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ];
+
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0u8; 4096];
+            let read = stream.read(&mut buffer).unwrap_or_default();
+            let _ = sender.send(String::from_utf8_lossy(&buffer[..read]).to_lowercase());
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    (format!("http://{address}/login"), receiver)
+}
+
+/// Send the request and poll the app until it has finished
+fn send_and_wait(app: &mut Reqwestur, uri: &str) {
+    *app.request.lock().unwrap() = Request {
+        method: Method::GET,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let shared = app.request.clone();
+    assert!(app.send(&mut shared.lock().unwrap()).is_ok());
+
+    let started = Instant::now();
+    while app.request.lock().unwrap().event == RequestEvent::PENDING {
+        assert!(started.elapsed() < Duration::from_secs(10), "no response");
+        std::thread::sleep(Duration::from_millis(20));
+        app.poll();
+    }
+}
+
+fn app(keep_cookies: bool) -> Reqwestur {
+    Reqwestur {
+        environments: vec![Environment {
+            keep_cookies,
+            ..Environment::new("Staging")
+        }],
+        active_environment: Some(0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn keeps_cookies_within_an_environment() {
+    let mut app = app(true);
+    let (uri, requests) = serve();
+
+    send_and_wait(&mut app, &uri);
+    assert!(!requests.recv().unwrap().contains("cookie:"));
+    assert_eq!(1, app.environments[0].cookies.len());
+
+    send_and_wait(&mut app, &uri);
+    assert!(requests.recv().unwrap().contains("cookie: session=abc"));
+}
+
+#[test]
+fn forgets_cookies_when_not_kept() {
+    let mut app = app(false);
+    let (uri, requests) = serve();
+
+    send_and_wait(&mut app, &uri);
+    send_and_wait(&mut app, &uri);
+
+    assert!(!requests.recv().unwrap().contains("cookie:"));
+    assert!(!requests.recv().unwrap().contains("cookie:"));
+    assert!(app.environments[0].cookies.is_empty());
+}
+
+fn executor(app: &mut Reqwestur) -> std::sync::Arc<reqwestur::utils::engine::RequestExecutor> {
+    app.executor(&Request::default())
+        .ok()
+        .expect("the client should build")
+}
+
+#[test]
+fn reuses_the_client_until_its_settings_change() {
+    let mut app = app(false);
+
+    let first = executor(&mut app);
+    assert!(std::sync::Arc::ptr_eq(&first, &executor(&mut app)));
+
+    app.environments[0].keep_cookies = true;
+    let second = executor(&mut app);
+    assert!(!std::sync::Arc::ptr_eq(&first, &second));
+    assert!(std::sync::Arc::ptr_eq(&second, &executor(&mut app)));
+
+    // The jar is persisted with the environment
+    let jar = CookieJar::default();
+    let restored: CookieJar = ron::from_str(&ron::to_string(&jar).unwrap()).unwrap();
+    assert!(restored.is_empty());
+}
+
+fn cookie(name: &str, domain: &str) -> Cookie {
+    Cookie {
+        name: name.to_string(),
+        value: "abc".to_string(),
+        domain: domain.to_string(),
+        path: "/".to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn records_the_attributes_of_response_cookies() {
+    let mut app = app(false);
+    let (uri, _requests) = serve();
+
+    send_and_wait(&mut app, &uri);
+
+    let request = app.request.lock().unwrap();
+    let [cookie] = &request.response.cookies[..] else {
+        panic!("expected one cookie");
+    };
+    assert_eq!("session", cookie.name);
+    assert_eq!("abc", cookie.value);
+    assert_eq!("127.0.0.1", cookie.domain);
+    assert_eq!("/", cookie.path);
+    assert!(cookie.secure && cookie.http_only && !cookie.include_subdomains);
+    assert!(cookie.expires.unwrap() > chrono::Utc::now().timestamp());
+
+    // Responses stored by earlier versions only kept the values
+    let legacy = r#"(cookies: ["abc"])"#;
+    let response: reqwestur::utils::request::Response = ron::from_str(legacy).unwrap();
+    assert_eq!("abc", response.cookies[0].value);
+    assert!(response.cookies[0].name.is_empty());
+}
+
+#[test]
+fn edits_the_cookies_in_the_jar() {
+    let jar = CookieJar::default();
+
+    jar.insert(&cookie("session", "example.com")).unwrap();
+    jar.insert(&Cookie {
+        include_subdomains: true,
+        ..cookie("theme", "example.com")
+    })
+    .unwrap();
+    jar.insert(&cookie("id", "api.example.org")).unwrap();
+    assert_eq!(
+        Err("Cookies need a name.".to_string()),
+        jar.insert(&cookie("", "example.com"))
+    );
+
+    let records = jar.records();
+    assert_eq!(
+        vec!["api.example.org", "example.com", "example.com"],
+        records
+            .iter()
+            .map(|cookie| cookie.domain.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(records[2].include_subdomains);
+
+    // Replacing a cookie with the same domain, path and name
+    jar.insert(&Cookie {
+        value: "xyz".to_string(),
+        ..cookie("session", "example.com")
+    })
+    .unwrap();
+    assert_eq!(3, jar.len());
+
+    jar.remove(&cookie("session", "example.com"));
+    assert_eq!(2, jar.len());
+}
+
+#[test]
+fn imports_and_exports_netscape_cookie_files() {
+    let contents = "# Netscape HTTP Cookie File\n\
+        \n\
+        .example.com\tTRUE\t/\tTRUE\t4102444800\ttheme\tdark\n\
+        #HttpOnly_example.com\tFALSE\t/account\tFALSE\t0\tsession\tabc\n";
+
+    let cookies = cookies::from_netscape(contents).unwrap();
+    assert_eq!(
+        vec![
+            Cookie {
+                name: "theme".to_string(),
+                value: "dark".to_string(),
+                domain: "example.com".to_string(),
+                include_subdomains: true,
+                path: "/".to_string(),
+                expires: Some(4102444800),
+                secure: true,
+                http_only: false,
+            },
+            Cookie {
+                name: "session".to_string(),
+                value: "abc".to_string(),
+                domain: "example.com".to_string(),
+                include_subdomains: false,
+                path: "/account".to_string(),
+                expires: None,
+                secure: false,
+                http_only: true,
+            },
+        ],
+        cookies
+    );
+
+    let jar = CookieJar::default();
+    for cookie in &cookies {
+        jar.insert(cookie).unwrap();
+    }
+
+    let exported = cookies::to_netscape(&jar.records());
+    assert!(exported.starts_with("# Netscape HTTP Cookie File"));
+    assert!(exported.contains(".example.com\tTRUE\t/\tTRUE\t4102444800\ttheme\tdark"));
+    assert!(exported.contains("#HttpOnly_example.com\tFALSE\t/account\tFALSE\t0\tsession\tabc"));
+    assert_eq!(
+        cookies.len(),
+        cookies::from_netscape(&exported).unwrap().len()
+    );
+
+    assert_eq!(
+        Err("Line 1 is not a cookies.txt entry.".to_string()),
+        cookies::from_netscape("example.com\tFALSE\t/")
+    );
+}
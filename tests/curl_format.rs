@@ -0,0 +1,244 @@
+use reqwestur::utils::{
+    auth::AuthKind,
+    formats::curl,
+    proxy::ProxyKind,
+    request::{Address, ContentType, Method, Request},
+};
+
+fn request(method: Method, uri: &str) -> Request {
+    Request {
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn parse_one(command: &str) -> (Request, Vec<String>) {
+    let parsed = curl::parse(command);
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    (parsed.requests[0].clone(), parsed.warnings)
+}
+
+#[test]
+fn round_trips_every_content_type() {
+    let requests = vec![
+        Request {
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            ..request(Method::GET, "https://example.com/users?page=2&sort=name")
+        },
+        Request {
+            content_type: ContentType::JSON,
+            body: Some("{\n  \"name\": \"Reqwestur's\"\n}".to_string()),
+            ..request(Method::POST, "https://example.com/users")
+        },
+        Request {
+            headers: vec![("Content-Type".to_string(), "text/xml".to_string())],
+            content_type: ContentType::TEXT,
+            body: Some("<user/>".to_string()),
+            ..request(Method::PUT, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::XWWWFORMURLENCODED,
+            params: vec![
+                ("name".to_string(), "Req Westur".to_string()),
+                ("query".to_string(), "a=b&c".to_string()),
+            ],
+            ..request(Method::PATCH, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::MULTIPART,
+            params: vec![
+                ("first".to_string(), "one".to_string()),
+                ("handle".to_string(), "@reqwestur".to_string()),
+            ],
+            ..request(Method::POST, "https://example.com/upload")
+        },
+        request(Method::DELETE, "https://example.com/users/1"),
+    ];
+
+    for expected in &requests {
+        let command = curl::serialize(expected);
+        let (actual, warnings) = parse_one(&command);
+
+        assert!(warnings.is_empty(), "{command}: {warnings:?}");
+        assert!(expected.method == actual.method, "{command}");
+        assert_eq!(expected.address.uri, actual.address.uri);
+        assert_eq!(expected.headers, actual.headers, "{command}");
+        assert!(expected.content_type == actual.content_type, "{command}");
+        assert_eq!(expected.body, actual.body);
+        assert_eq!(expected.params, actual.params);
+    }
+}
+
+#[test]
+fn serializes_a_copyable_command() {
+    let request = Request {
+        headers: vec![("Authorization".to_string(), "Bearer token".to_string())],
+        content_type: ContentType::JSON,
+        body: Some("{\"name\": \"O'Brien\"}".to_string()),
+        ..request(Method::PUT, "https://example.com/users?id=1")
+    };
+
+    assert_eq!(
+        curl::serialize(&request),
+        "curl 'https://example.com/users?id=1' \\\n  \
+         -X PUT \\\n  \
+         -H 'Authorization: Bearer token' \\\n  \
+         -H 'Content-Type: application/json' \\\n  \
+         --data-raw '{\"name\": \"O'\\''Brien\"}'"
+    );
+}
+
+#[test]
+fn parses_browser_commands() {
+    let (request, warnings) = parse_one(
+        "curl 'https://example.com/api' \\
+  -H 'accept: */*' \\
+  -H 'content-type: application/json' \\
+  -b 'session=abc' \\
+  --data-raw $'{\"name\":\"Req\\'s\"}' \\
+  --compressed",
+    );
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert!(request.method == Method::POST);
+    assert!(request.content_type == ContentType::JSON);
+    assert_eq!(Some("{\"name\":\"Req's\"}".to_string()), request.body);
+    assert_eq!(
+        vec![
+            ("accept".to_string(), "*/*".to_string()),
+            ("Cookie".to_string(), "session=abc".to_string()),
+        ],
+        request.headers
+    );
+}
+
+#[test]
+fn parses_forms_credentials_and_combined_flags() {
+    let (request, warnings) = parse_one(
+        "curl -sSL -XPATCH -u user:pass https://example.com \
+         -d name=one --data-urlencode 'note=a b&c'",
+    );
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert!(request.method == Method::PATCH);
+    assert!(request.content_type == ContentType::XWWWFORMURLENCODED);
+    assert_eq!(
+        vec![
+            ("name".to_string(), "one".to_string()),
+            ("note".to_string(), "a b&c".to_string()),
+        ],
+        request.params
+    );
+    assert!(request.auth.kind == AuthKind::BASIC);
+    assert_eq!(
+        ("user", "pass"),
+        (
+            request.auth.username.as_str(),
+            request.auth.password.as_str()
+        )
+    );
+    assert!(request.headers.is_empty());
+
+    let (request, _) = parse_one("curl --digest -u user:pass https://example.com");
+    assert!(request.auth.kind == AuthKind::DIGEST);
+
+    let (request, _) = parse_one("curl https://example.com -d '{\"raw\": true}'");
+    assert!(request.content_type == ContentType::TEXT);
+    assert_eq!(Some("{\"raw\": true}".to_string()), request.body);
+
+    let (request, _) = parse_one("curl -G https://example.com?a=1 -d b=2");
+    assert!(request.method == Method::GET);
+    assert_eq!("https://example.com?a=1&b=2", request.address.uri);
+}
+
+#[test]
+fn parses_and_serializes_proxies() {
+    let (request, warnings) = parse_one(
+        "curl -x http://proxy.example.com:8080 -U user:pass --noproxy localhost,.internal \
+         https://example.com",
+    );
+
+    assert!(warnings.is_empty(), "{warnings:?}");
+    let proxy = request.proxy.clone().unwrap();
+    assert!(proxy.kind == ProxyKind::HTTP);
+    assert_eq!(
+        ("proxy.example.com:8080", "user", "pass"),
+        (
+            proxy.address.as_str(),
+            proxy.username.as_str(),
+            proxy.password.value.as_str()
+        )
+    );
+    assert_eq!(vec!["localhost", ".internal"], proxy.bypass);
+
+    // The password is only kept in the vault, so it's copied as a placeholder
+    let command = curl::serialize(&request);
+    assert!(
+        command.contains("-x http://proxy.example.com:8080"),
+        "{command}"
+    );
+    assert!(command.contains("-U 'user:{{proxyPassword}}'"), "{command}");
+    let copied = parse_one(&command).0.proxy.unwrap();
+    assert_eq!(
+        ("user", "{{proxyPassword}}"),
+        (copied.username.as_str(), copied.password.value.as_str())
+    );
+    assert_eq!(proxy.bypass, copied.bypass);
+
+    let (request, _) =
+        parse_one("curl --socks5-hostname proxy.example.com:1080 https://example.com");
+    assert!(
+        request
+            .proxy
+            .is_some_and(|proxy| proxy.kind == ProxyKind::SOCKS5)
+    );
+
+    // Without a proxy, `--noproxy '*'` connects directly
+    let (request, _) = parse_one("curl --noproxy '*' https://example.com");
+    assert!(
+        request
+            .proxy
+            .as_ref()
+            .is_some_and(|proxy| proxy.kind == ProxyKind::NONE)
+    );
+    assert!(
+        parse_one(&curl::serialize(&request))
+            .0
+            .proxy
+            .is_some_and(|proxy| proxy.kind == ProxyKind::NONE)
+    );
+
+    let (request, warnings) = parse_one("curl -x ftp://proxy.example.com https://example.com");
+    assert!(request.proxy.is_none());
+    assert_eq!(
+        vec!["the -x option, the proxy ftp://proxy.example.com isn't supported".to_string()],
+        warnings
+    );
+}
+
+#[test]
+fn reports_unsupported_options() {
+    let (request, warnings) = parse_one(
+        "curl -k --cert client.pem:secret -F title=Reqwestur -F file=@logo.png \
+         --frobnicate https://example.com/upload",
+    );
+
+    assert!(request.content_type == ContentType::MULTIPART);
+    assert_eq!(
+        vec![("title".to_string(), "Reqwestur".to_string())],
+        request.params
+    );
+    assert_eq!(4, warnings.len(), "{warnings:?}");
+    assert_eq!(
+        "the -k option, invalid certificates are accepted in the environment settings",
+        warnings[0]
+    );
+
+    assert_eq!(1, curl::parse("curl -I https://example.com").errors.len());
+    assert_eq!(1, curl::parse("curl -X GET").errors.len());
+    assert_eq!(1, curl::parse("curl 'https://example.com").errors.len());
+}
@@ -0,0 +1,161 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    time::{Duration, Instant},
+};
+
+use reqwestur::utils::{
+    engine::{ClientSettings, RequestExecutor},
+    request::{Address, ContentType, Method, Request, RequestEvent},
+    reqwestur::Reqwestur,
+};
+
+/// Serve a single connection on a local port, replying with the response if there is one
+fn serve(response: Option<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buffer = [0u8; 4096];
+        let _ = stream.read(&mut buffer);
+
+        match response {
+            Some(response) => {
+                let _ = stream.write_all(response.as_bytes());
+            }
+            // Hold the connection open without replying
+            None => std::thread::sleep(Duration::from_secs(10)),
+        }
+    });
+
+    format!("http://{address}/users")
+}
+
+/// Send the request as the Send button does, the app's request is the one being edited
+fn send(app: &mut Reqwestur, uri: String, timeout: Option<u64>) {
+    *app.request.lock().unwrap() = Request {
+        method: Method::GET,
+        address: Address {
+            uri,
+            ..Default::default()
+        },
+        timeout,
+        ..Default::default()
+    };
+
+    let shared = app.request.clone();
+    let mut request = shared.lock().unwrap();
+    assert!(app.send(&mut request).is_ok());
+    assert!(request.event == RequestEvent::PENDING);
+}
+
+/// Poll the app until the request has finished
+fn wait(app: &mut Reqwestur) {
+    let started = Instant::now();
+    while app.request.lock().unwrap().event == RequestEvent::PENDING {
+        assert!(started.elapsed() < Duration::from_secs(10), "no response");
+        std::thread::sleep(Duration::from_millis(20));
+        app.poll();
+    }
+}
+
+#[test]
+fn sends_on_the_runtime() {
+    let mut app = Reqwestur::default();
+    let uri = serve(Some(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"ok\":true}",
+    ));
+
+    send(&mut app, uri, None);
+    wait(&mut app);
+
+    let request = app.request.lock().unwrap();
+    assert!(request.event == RequestEvent::SENT);
+    assert_eq!(200, request.response.status.0);
+    assert_eq!("{\n  \"ok\": true\n}", request.response.body);
+    assert!(!app.is_pending());
+    assert_eq!(1, app.history.lock().unwrap().len());
+}
+
+#[test]
+fn times_out_per_request() {
+    let mut app = Reqwestur::default();
+
+    send(&mut app, serve(None), Some(1));
+    wait(&mut app);
+
+    let request = app.request.lock().unwrap();
+    assert!(request.event == RequestEvent::UNSENT);
+    assert_eq!(
+        Some("Request timed out after 1 seconds."),
+        request.notification.message()
+    );
+    assert!(app.history.lock().unwrap().is_empty());
+}
+
+#[test]
+fn cancels_pending_requests() {
+    let mut app = Reqwestur::default();
+
+    send(&mut app, serve(None), None);
+    assert!(app.is_pending());
+
+    let shared = app.request.clone();
+    app.cancel(&mut shared.lock().unwrap());
+    assert!(!app.is_pending());
+
+    std::thread::sleep(Duration::from_millis(100));
+    app.poll();
+
+    let request = app.request.lock().unwrap();
+    assert!(request.event == RequestEvent::UNSENT);
+    assert_eq!(
+        Some("The request was cancelled."),
+        request.notification.message()
+    );
+    assert!(app.history.lock().unwrap().is_empty());
+}
+
+#[test]
+fn sets_the_content_type_of_text_bodies() {
+    let executor = RequestExecutor::new(ClientSettings::default()).unwrap();
+    let request = Request {
+        method: Method::POST,
+        address: Address {
+            uri: "https://example.com".to_string(),
+            ..Default::default()
+        },
+        content_type: ContentType::JSON,
+        body: Some("{}".to_string()),
+        ..Default::default()
+    };
+
+    let built = executor.build(&request).unwrap();
+    assert_eq!(
+        Some("application/json"),
+        built
+            .headers()
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+    );
+
+    // A Content-Type header entered with the request is kept
+    let request = Request {
+        headers: vec![(
+            "Content-Type".to_string(),
+            "application/vnd.api+json".to_string(),
+        )],
+        ..request
+    };
+    let built = executor.build(&request).unwrap();
+    assert_eq!(
+        vec!["application/vnd.api+json"],
+        built
+            .headers()
+            .get_all("content-type")
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect::<Vec<&str>>()
+    );
+}
@@ -0,0 +1,108 @@
+use reqwestur::utils::{
+    environments::{self, Environment},
+    request::{Address, ContentType, Method, Request, RequestEvent},
+    reqwestur::Reqwestur,
+};
+
+fn request(method: Method, uri: &str) -> Request {
+    Request {
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn staging() -> Environment {
+    Environment {
+        variables: vec![
+            (
+                "baseUrl".to_string(),
+                "https://staging.example.com".to_string(),
+            ),
+            ("token".to_string(), "abc123".to_string()),
+        ],
+        ..Environment::new("Staging")
+    }
+}
+
+#[test]
+fn fills_in_every_field() {
+    let template = Request {
+        headers: vec![(
+            "Authorization".to_string(),
+            "Bearer {{ token }}".to_string(),
+        )],
+        content_type: ContentType::JSON,
+        body: Some("{\"token\": \"{{token}}\"}".to_string()),
+        params: vec![("{{token}}".to_string(), "{{baseUrl}}".to_string())],
+        ..request(Method::POST, "{{baseUrl}}/users?token={{token}}")
+    };
+
+    let resolved = staging().apply(&template);
+
+    assert_eq!(
+        "https://staging.example.com/users?token=abc123",
+        resolved.address.uri
+    );
+    assert_eq!(
+        vec![("Authorization".to_string(), "Bearer abc123".to_string())],
+        resolved.headers
+    );
+    assert_eq!(Some("{\"token\": \"abc123\"}".to_string()), resolved.body);
+    assert_eq!(
+        vec![(
+            "abc123".to_string(),
+            "https://staging.example.com".to_string()
+        )],
+        resolved.params
+    );
+    assert!(environments::unresolved(&template, &staging().variables).is_empty());
+}
+
+#[test]
+fn reports_unresolved_variables() {
+    let template = Request {
+        headers: vec![("X-Tenant".to_string(), "{{tenant}}".to_string())],
+        body: Some("{{ tenant }} {{missing".to_string()),
+        ..request(Method::GET, "{{baseUrl}}/{{version}}/users")
+    };
+
+    assert_eq!(
+        vec!["version".to_string(), "tenant".to_string()],
+        environments::unresolved(&template, &staging().variables)
+    );
+
+    let found = environments::placeholders("a {{ b }} c {{d}}");
+    assert_eq!(
+        vec![(2..9, "b".to_string()), (12..17, "d".to_string())],
+        found
+            .into_iter()
+            .map(|placeholder| (placeholder.range, placeholder.name))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn refuses_to_send_unresolved_requests() {
+    let mut app = Reqwestur {
+        environments: vec![staging()],
+        active_environment: Some(0),
+        ..Default::default()
+    };
+    let mut request = request(Method::GET, "{{host}}/users");
+
+    let notification = app
+        .send(&mut request)
+        .expect_err("the request should not be sent");
+    assert_eq!(
+        Some("No value for {{host}} in the active environment."),
+        notification.message()
+    );
+
+    // The request keeps its placeholders so it can be sent to another environment
+    assert_eq!("{{host}}/users", request.address.uri);
+    assert!(request.event == RequestEvent::UNSENT);
+}
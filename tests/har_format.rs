@@ -0,0 +1,195 @@
+use reqwestur::utils::{
+    formats::har,
+    request::{Address, ContentType, Method, Request, RequestEvent, Response},
+};
+
+fn request(method: Method, uri: &str) -> Request {
+    Request {
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        timestamp: "18/10/2025 09:30".to_string(),
+        ..Default::default()
+    }
+}
+
+fn assert_same(expected: &Request, actual: &Request) {
+    assert_eq!(expected.name, actual.name);
+    assert!(expected.method == actual.method, "methods differ");
+    assert_eq!(expected.address.uri, actual.address.uri);
+    assert_eq!(expected.headers, actual.headers);
+    assert!(
+        expected.content_type == actual.content_type,
+        "content types differ"
+    );
+    assert_eq!(expected.body, actual.body);
+    assert_eq!(expected.params, actual.params);
+    assert_eq!(expected.timestamp, actual.timestamp);
+    assert_eq!(expected.response.status, actual.response.status);
+    assert_eq!(expected.response.headers, actual.response.headers);
+    assert_eq!(expected.response.body, actual.response.body);
+    assert_eq!(expected.response.duration, actual.response.duration);
+}
+
+#[test]
+fn round_trips_history_with_responses() {
+    let requests = vec![
+        Request {
+            name: "List Users".to_string(),
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            response: Response {
+                status: (200, "OK".to_string()),
+                headers: vec![
+                    ("content-type".to_string(), "application/json".to_string()),
+                    (
+                        "set-cookie".to_string(),
+                        "session=abc; Path=/; HttpOnly".to_string(),
+                    ),
+                ],
+                body: "[\n  1,\n  2\n]".to_string(),
+                duration: 42,
+                ..Default::default()
+            },
+            event: RequestEvent::SENT,
+            ..request(Method::GET, "https://example.com/users?page=2")
+        },
+        Request {
+            content_type: ContentType::JSON,
+            body: Some("{\n  \"name\": \"Reqwestur\"\n}".to_string()),
+            response: Response {
+                status: (201, "Created".to_string()),
+                body: "Created".to_string(),
+                duration: 7,
+                ..Default::default()
+            },
+            ..request(Method::POST, "https://example.com/users")
+        },
+        Request {
+            content_type: ContentType::XWWWFORMURLENCODED,
+            params: vec![("name".to_string(), "Req Westur".to_string())],
+            ..request(Method::PATCH, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::MULTIPART,
+            params: vec![("first".to_string(), "one".to_string())],
+            ..request(Method::POST, "https://example.com/upload")
+        },
+    ];
+
+    let parsed = har::parse(&har::serialize(&requests)).expect("the archive should parse");
+
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+    assert_eq!(requests.len(), parsed.requests.len());
+    for (expected, actual) in requests.iter().zip(parsed.requests.iter()) {
+        assert_same(expected, actual);
+    }
+
+    assert!(parsed.requests[0].event == RequestEvent::SENT);
+    assert!(parsed.requests[2].event == RequestEvent::UNSENT);
+}
+
+#[test]
+fn writes_cookies_and_timings() {
+    let requests = vec![Request {
+        headers: vec![("Cookie".to_string(), "a=1; b=2".to_string())],
+        response: Response {
+            status: (302, "Found".to_string()),
+            headers: vec![
+                ("Location".to_string(), "/login".to_string()),
+                (
+                    "Set-Cookie".to_string(),
+                    "session=abc; Path=/; Secure".to_string(),
+                ),
+            ],
+            duration: 120,
+            ..Default::default()
+        },
+        ..request(Method::GET, "https://example.com")
+    }];
+
+    let archive: serde_json::Value = serde_json::from_str(&har::serialize(&requests)).unwrap();
+    let entry = &archive["log"]["entries"][0];
+
+    assert_eq!("1.2", archive["log"]["version"]);
+    assert_eq!("2025-10-18T09:30:00.000Z", entry["startedDateTime"]);
+    assert_eq!(120, entry["time"]);
+    assert_eq!(120, entry["timings"]["wait"]);
+    assert_eq!(2, entry["request"]["cookies"].as_array().unwrap().len());
+    assert_eq!("/login", entry["response"]["redirectURL"]);
+    assert_eq!(
+        serde_json::json!([{ "name": "session", "value": "abc", "path": "/", "secure": true }]),
+        entry["response"]["cookies"]
+    );
+}
+
+#[test]
+fn imports_devtools_captures() {
+    let contents = r#"{
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "WebInspector", "version": "537.36" },
+            "entries": [
+                {
+                    "startedDateTime": "2025-10-18T09:30:12.345Z",
+                    "time": 12.7,
+                    "request": {
+                        "method": "POST",
+                        "url": "https://example.com/upload",
+                        "httpVersion": "h2",
+                        "headers": [
+                            { "name": ":authority", "value": "example.com" },
+                            { "name": "content-length", "value": "120" },
+                            { "name": "content-type", "value": "multipart/form-data; boundary=----Boundary" }
+                        ],
+                        "postData": {
+                            "mimeType": "multipart/form-data; boundary=----Boundary",
+                            "text": "",
+                            "params": [
+                                { "name": "title", "value": "Reqwestur" },
+                                { "name": "file", "fileName": "logo.png", "contentType": "image/png" }
+                            ]
+                        }
+                    },
+                    "response": {
+                        "status": 200,
+                        "statusText": "",
+                        "headers": [{ "name": "content-type", "value": "application/json" }],
+                        "cookies": [{ "name": "session", "value": "abc" }],
+                        "content": { "size": 11, "mimeType": "application/json", "text": "eyJvayI6dHJ1ZX0=", "encoding": "base64" }
+                    }
+                },
+                {
+                    "startedDateTime": "2025-10-18T09:30:13.000Z",
+                    "time": -1,
+                    "request": { "method": "OPTIONS", "url": "https://example.com" },
+                    "response": { "status": 0 }
+                }
+            ]
+        }
+    }"#;
+
+    let parsed = har::parse(contents).unwrap();
+    assert_eq!(1, parsed.requests.len());
+    assert_eq!(1, parsed.errors.len());
+    assert_eq!(1, parsed.warnings.len());
+
+    let upload = &parsed.requests[0];
+    assert!(upload.headers.is_empty(), "{:?}", upload.headers);
+    assert!(upload.content_type == ContentType::MULTIPART);
+    assert_eq!(
+        vec![("title".to_string(), "Reqwestur".to_string())],
+        upload.params
+    );
+    assert_eq!("18/10/2025 09:30", upload.timestamp);
+    assert_eq!((200, "OK".to_string()), upload.response.status);
+    let cookie = &upload.response.cookies[0];
+    assert_eq!(
+        ("session", "abc"),
+        (cookie.name.as_str(), cookie.value.as_str())
+    );
+    assert_eq!("{\n  \"ok\": true\n}", upload.response.body);
+    assert_eq!(12, upload.response.duration);
+}
@@ -0,0 +1,204 @@
+use reqwestur::utils::{
+    exports::{self, ExportType},
+    formats::http,
+    request::{Address, ContentType, Method, Request},
+};
+
+fn request(method: Method, uri: &str) -> Request {
+    Request {
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn assert_same(expected: &Request, actual: &Request) {
+    assert_eq!(expected.name, actual.name);
+    assert!(expected.method == actual.method, "methods differ");
+    assert_eq!(expected.address.uri, actual.address.uri);
+    assert_eq!(expected.headers, actual.headers);
+    assert!(
+        expected.content_type == actual.content_type,
+        "content types differ"
+    );
+    assert_eq!(expected.body, actual.body);
+    assert_eq!(expected.params, actual.params);
+}
+
+#[test]
+fn round_trips_every_content_type() {
+    let requests = vec![
+        Request {
+            name: "List Users".to_string(),
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            ..request(Method::GET, "https://example.com/users?page=2")
+        },
+        Request {
+            content_type: ContentType::JSON,
+            body: Some("{\n  \"name\": \"Reqwestur\"\n}".to_string()),
+            ..request(Method::POST, "https://example.com/users")
+        },
+        Request {
+            headers: vec![("Content-Type".to_string(), "text/xml".to_string())],
+            content_type: ContentType::TEXT,
+            body: Some("<user>\n\n  <name>Reqwestur</name>\n</user>".to_string()),
+            ..request(Method::PUT, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::XWWWFORMURLENCODED,
+            params: vec![
+                ("name".to_string(), "Req Westur".to_string()),
+                ("query".to_string(), "a=b&c".to_string()),
+            ],
+            ..request(Method::PATCH, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::MULTIPART,
+            params: vec![
+                ("first".to_string(), "one".to_string()),
+                ("second".to_string(), "two\nlines".to_string()),
+            ],
+            ..request(Method::POST, "https://example.com/upload")
+        },
+        request(Method::DELETE, "https://example.com/users/1"),
+    ];
+
+    let parsed = http::parse(&http::serialize(&requests));
+
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert_eq!(requests.len(), parsed.requests.len());
+    for (expected, actual) in requests.iter().zip(parsed.requests.iter()) {
+        assert_same(expected, actual);
+    }
+}
+
+#[test]
+fn serializes_rest_client_syntax() {
+    let requests = vec![
+        Request {
+            name: "Create".to_string(),
+            headers: vec![("Authorization".to_string(), "Bearer token".to_string())],
+            content_type: ContentType::JSON,
+            body: Some("{}".to_string()),
+            ..request(Method::POST, "https://example.com/users")
+        },
+        request(Method::GET, "https://example.com/users"),
+    ];
+
+    assert_eq!(
+        http::serialize(&requests),
+        "# @name Create\n\
+         POST https://example.com/users\n\
+         Authorization: Bearer token\n\
+         Content-Type: application/json\n\
+         \n\
+         {}\n\
+         \n\
+         ###\n\
+         \n\
+         GET https://example.com/users\n"
+    );
+}
+
+#[test]
+fn parses_variables_names_and_comments() {
+    let contents = "@host = https://example.com
+@users = {{host}}/users
+
+### Get Users
+// A comment before the request
+GET {{users}}
+    ?page=1
+    &limit=10 HTTP/1.1
+Accept: application/json
+
+###
+
+# @name Create User
+POST {{users}}
+Content-Type: application/json
+X-Trace: {{unknown}}
+
+{\"host\": \"{{host}}\"}
+";
+
+    let parsed = http::parse(contents);
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert_eq!(2, parsed.requests.len());
+
+    let get = &parsed.requests[0];
+    assert_eq!("Get Users", get.name);
+    assert_eq!("https://example.com/users?page=1&limit=10", get.address.uri);
+    assert!(get.content_type == ContentType::EMPTY);
+
+    let post = &parsed.requests[1];
+    assert_eq!("Create User", post.name);
+    assert!(post.method == Method::POST);
+    assert_eq!(
+        vec![("X-Trace".to_string(), "{{unknown}}".to_string())],
+        post.headers
+    );
+    assert!(post.content_type == ContentType::JSON);
+    assert_eq!(
+        Some("{\"host\": \"https://example.com\"}".to_string()),
+        post.body
+    );
+}
+
+#[test]
+fn reports_invalid_entries() {
+    let contents = "GET https://example.com
+
+###
+FETCH https://example.com
+
+###
+POST not-a-uri
+
+###
+GET https://example.com
+Not A Header
+";
+
+    let parsed = http::parse(contents);
+    assert_eq!(1, parsed.requests.len());
+    assert_eq!(3, parsed.errors.len());
+    assert!(parsed.errors[0].starts_with("Entry 2"));
+}
+
+#[test]
+fn imports_http_files() {
+    let contents = "@host = https://example.com
+
+### Users
+GET {{host}}/users
+
+###
+FETCH {{host}}
+";
+
+    let parsed = exports::parse(&ExportType::HTTP, contents).unwrap();
+    assert_eq!(1, parsed.requests.len());
+    assert_eq!("Users", parsed.requests[0].name);
+    assert_eq!(
+        vec!["Entry 2: Unsupported method \"FETCH\".".to_string()],
+        parsed.errors
+    );
+    assert!(parsed.warnings.is_empty());
+    assert_eq!(
+        Some("Imported 1 request(s). Skipped 1 - Entry 2: Unsupported method \"FETCH\".."),
+        parsed.notification().message()
+    );
+
+    assert!(
+        ExportType::from_extension(std::path::Path::new("requests.HTTP"))
+            .is_some_and(|export_type| export_type == ExportType::HTTP)
+    );
+    assert!(
+        ExportType::from_extension(std::path::Path::new("requests.json"))
+            .is_some_and(|export_type| export_type == ExportType::JSON)
+    );
+}
@@ -0,0 +1,159 @@
+use reqwestur::utils::{
+    auth::{Auth, AuthKind},
+    exports::{self, ExportType},
+    formats::{ParsedRequests, json},
+    request::{Address, ContentType, Method, Request},
+};
+
+#[test]
+fn imports_json_requests() {
+    let contents = r#"[
+        {
+            "name": "Create User",
+            "method": "POST",
+            "contentType": "JSON",
+            "uri": "https://example.com/users",
+            "headers": [["Accept", "application/json"]],
+            "body": "{\"name\": \"Reqwestur\"}"
+        },
+        { "uri": "https://example.com/{{path}}" }
+    ]"#;
+
+    let parsed = exports::parse(&ExportType::JSON, contents).unwrap();
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+    assert_eq!(2, parsed.requests.len());
+
+    let create = &parsed.requests[0];
+    assert_eq!("Create User", create.name);
+    assert!(create.method == Method::POST);
+    assert!(create.content_type == ContentType::JSON);
+    assert_eq!("https://example.com/users", create.address.uri);
+    assert_eq!(
+        vec![("Accept".to_string(), "application/json".to_string())],
+        create.headers
+    );
+    assert_eq!(Some("{\"name\": \"Reqwestur\"}".to_string()), create.body);
+
+    // Missing fields take their defaults, placeholders are kept for the environment
+    let get = &parsed.requests[1];
+    assert!(get.method == Method::GET);
+    assert!(get.content_type == ContentType::EMPTY);
+    assert_eq!("https://example.com/{{path}}", get.address.uri);
+
+    // The exported file is read back as it was written
+    let exported = json::serialize(&parsed.requests);
+    let reimported = json::parse(&exported).unwrap();
+    assert_eq!(2, reimported.requests.len());
+    assert_eq!(parsed.requests[0].body, reimported.requests[0].body);
+}
+
+#[test]
+fn reports_invalid_json_entries() {
+    let contents = r#"[
+        { "uri": "https://example.com" },
+        { "method": "POST" },
+        { "uri": "not a uri" },
+        { "uri": "https://example.com", "headers": [["Not A Header", "1"]] },
+        { "uri": "https://example.com", "method": "FETCH" },
+        "https://example.com"
+    ]"#;
+
+    let parsed = json::parse(contents).unwrap();
+    assert_eq!(1, parsed.requests.len());
+    assert_eq!(5, parsed.errors.len());
+    assert_eq!("Entry 2: The request has no URI.", parsed.errors[0]);
+    assert!(
+        parsed.errors[1].starts_with("Entry 3: Invalid URI not a uri"),
+        "{}",
+        parsed.errors[1]
+    );
+    assert_eq!(
+        "Entry 4: Invalid header name \"Not A Header\".",
+        parsed.errors[2]
+    );
+    assert!(parsed.errors[3].starts_with("Entry 5: unknown variant `FETCH`"));
+    assert!(parsed.errors[4].starts_with("Entry 6: invalid type"));
+
+    assert!(
+        json::parse(r#"{ "uri": "https://example.com" }"#)
+            .err()
+            .is_some_and(|error| error.starts_with("The file is not a JSON array of requests"))
+    );
+}
+
+#[test]
+fn summarises_imports() {
+    let request = Request {
+        address: Address {
+            uri: "https://example.com".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut parsed = ParsedRequests::default();
+    parsed.push(1, Ok(request.clone()));
+    assert_eq!(
+        Some("Imported 1 request(s)."),
+        parsed.notification().message()
+    );
+
+    parsed.warnings.push("scripts on \"Users\"".to_string());
+    assert_eq!(
+        Some("Imported 1 request(s). Ignored - scripts on \"Users\"."),
+        parsed.notification().message()
+    );
+
+    parsed.push(2, Err("Unsupported method \"FETCH\".".to_string()));
+    parsed.push(3, Ok(Request::default()));
+    let notification = parsed.notification();
+    assert_eq!(
+        Some(
+            "Imported 1 request(s). Skipped 2 - Entry 2: Unsupported method \"FETCH\".; \
+             Entry 3: The request has no URI.. Ignored - scripts on \"Users\"."
+        ),
+        notification.message()
+    );
+    assert!(!notification.is_error());
+
+    // Nothing imported is an error
+    let failed = ParsedRequests {
+        errors: vec!["Entry 1: The request has no URI.".to_string()],
+        ..Default::default()
+    };
+    assert!(failed.notification().is_error());
+}
+
+#[test]
+fn keeps_credentials_with_typed_secrets_masked() {
+    let request = Request {
+        address: Address {
+            uri: "https://example.com/users".to_string(),
+            ..Default::default()
+        },
+        auth: Auth {
+            kind: AuthKind::BASIC,
+            username: "{{username}}".to_string(),
+            password: "hunter2".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let exported = json::serialize(&[request]);
+    assert!(!exported.contains("hunter2"), "{exported}");
+
+    let reimported = json::parse(&exported).unwrap();
+    assert!(reimported.errors.is_empty(), "{:?}", reimported.errors);
+    let auth = &reimported.requests[0].auth;
+    assert!(auth.kind == AuthKind::BASIC);
+    assert_eq!(
+        ("{{username}}", "{{password}}"),
+        (auth.username.as_str(), auth.password.as_str())
+    );
+
+    // Files written before credentials were exported have none
+    let parsed = json::parse(r#"[{ "uri": "https://example.com/users" }]"#).unwrap();
+    assert!(parsed.requests[0].auth.kind == AuthKind::NONE);
+}
@@ -0,0 +1,303 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::Digest;
+
+use reqwestur::utils::{
+    auth::{Auth, AuthKind},
+    engine,
+    oauth2::{self, GrantType, OAuth2, Token, TokenCache},
+    request::{Address, Method, Request, RequestEvent},
+    reqwestur::Reqwestur,
+};
+
+/// Answer each request on a local port with the next response, reporting the requests received
+fn serve(responses: Vec<String>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = sender.send(receive(&mut stream));
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    (format!("http://{address}"), receiver)
+}
+
+/// Read a whole request, including the form body
+fn receive(stream: &mut TcpStream) -> String {
+    let mut received = Vec::new();
+    let mut buffer = [0u8; 4096];
+
+    loop {
+        let read = stream.read(&mut buffer).unwrap_or_default();
+        received.extend_from_slice(&buffer[..read]);
+
+        let text = String::from_utf8_lossy(&received).to_string();
+        let Some((head, body)) = text.split_once("\r\n\r\n") else {
+            if read == 0 {
+                return text;
+            }
+            continue;
+        };
+
+        let length = head
+            .lines()
+            .find_map(|line| {
+                line.to_lowercase()
+                    .strip_prefix("content-length: ")
+                    .map(str::to_string)
+            })
+            .and_then(|length| length.trim().parse().ok())
+            .unwrap_or(0);
+        if body.len() >= length || read == 0 {
+            return text;
+        }
+    }
+}
+
+fn json(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+fn credentials(token_url: &str, grant: GrantType) -> Auth {
+    Auth {
+        kind: AuthKind::OAUTH2,
+        oauth2: OAuth2 {
+            grant,
+            token_url: format!("{token_url}/token"),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            scope: "read".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn token(auth: &Auth, cache: &TokenCache) -> Result<Token, String> {
+    engine::runtime().block_on(oauth2::token(
+        &reqwest::Client::new(),
+        auth,
+        cache,
+        &|_: &str| {},
+    ))
+}
+
+#[test]
+fn fetches_and_caches_client_credentials_tokens() {
+    let (uri, requests) = serve(vec![json(
+        "200 OK",
+        r#"{"access_token":"t0k3n","token_type":"Bearer","expires_in":3600}"#,
+    )]);
+    let auth = credentials(&uri, GrantType::CLIENTCREDENTIALS);
+    let cache = TokenCache::default();
+
+    let fetched = token(&auth, &cache).unwrap();
+    assert_eq!("t0k3n", fetched.access_token);
+    assert!(!fetched.is_expired());
+    assert!(fetched.response.contains("\"token_type\": \"Bearer\""));
+
+    let received = requests.recv().unwrap();
+    assert!(received.starts_with("POST /token"));
+    assert!(received.contains("Basic Y2xpZW50OnNlY3JldA=="));
+    assert!(received.ends_with("grant_type=client_credentials&scope=read"));
+
+    // The cached token is used until it expires
+    assert_eq!(fetched, token(&auth, &cache).unwrap());
+    assert!(requests.try_recv().is_err());
+    assert_eq!(Some(fetched), cache.get(&auth));
+}
+
+#[test]
+fn sends_the_password_grant_with_credentials_in_the_body() {
+    let (uri, requests) = serve(vec![json("200 OK", r#"{"access_token":"t0k3n"}"#)]);
+    let mut auth = Auth {
+        username: "user".to_string(),
+        password: "p@ss".to_string(),
+        ..credentials(&uri, GrantType::PASSWORD)
+    };
+    auth.oauth2.credentials_in_body = true;
+
+    let fetched = token(&auth, &TokenCache::default()).unwrap();
+    assert_eq!(None, fetched.expires_at);
+
+    let received = requests.recv().unwrap();
+    assert!(!received.to_lowercase().contains("authorization:"));
+    assert!(received.ends_with(
+        "grant_type=password&username=user&password=p%40ss&scope=read&client_id=client&client_secret=secret"
+    ));
+}
+
+#[test]
+fn refreshes_expired_tokens() {
+    let (uri, requests) = serve(vec![json(
+        "200 OK",
+        r#"{"access_token":"new","expires_in":"3600"}"#,
+    )]);
+    let auth = credentials(&uri, GrantType::CLIENTCREDENTIALS);
+    let cache = TokenCache::default();
+    cache.insert(
+        &auth,
+        Token {
+            access_token: "old".to_string(),
+            refresh_token: Some("r3fr3sh".to_string()),
+            // Within the margin, so it's refreshed before it can expire in flight
+            expires_at: Some(chrono::Utc::now().timestamp() + 10),
+            ..Default::default()
+        },
+    );
+
+    let refreshed = token(&auth, &cache).unwrap();
+    assert_eq!("new", refreshed.access_token);
+    assert_eq!(Some("r3fr3sh".to_string()), refreshed.refresh_token);
+    assert!(!refreshed.is_expired());
+    assert_eq!(Some(refreshed), cache.get(&auth));
+
+    assert!(
+        requests
+            .recv()
+            .unwrap()
+            .ends_with("grant_type=refresh_token&refresh_token=r3fr3sh&scope=read")
+    );
+}
+
+#[test]
+fn reports_token_endpoint_errors() {
+    let (uri, _requests) = serve(vec![
+        json(
+            "401 Unauthorized",
+            r#"{"error":"invalid_client","error_description":"Unknown client"}"#,
+        ),
+        json("500 Internal Server Error", "{}"),
+    ]);
+    let auth = credentials(&uri, GrantType::CLIENTCREDENTIALS);
+    let cache = TokenCache::default();
+
+    assert_eq!(
+        Err("The token endpoint returned invalid_client Unknown client".to_string()),
+        token(&auth, &cache)
+    );
+    assert_eq!(
+        Err("The token endpoint returned 500 Internal Server Error.".to_string()),
+        token(&auth, &cache)
+    );
+    assert_eq!(None, cache.get(&auth));
+}
+
+#[test]
+fn receives_the_authorization_code_on_the_loopback_redirect() {
+    let (uri, requests) = serve(vec![json("200 OK", r#"{"access_token":"c0d3"}"#)]);
+    let mut auth = credentials(&uri, GrantType::AUTHORIZATIONCODE);
+    auth.oauth2.authorization_url = format!("{uri}/authorize?audience=api");
+
+    let (challenges, challenge) = mpsc::channel();
+
+    // Stands in for the user approving the request in their browser
+    let browser = move |url: &str| {
+        let url = url::Url::parse(url).unwrap();
+        let parameter = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default()
+        };
+        assert_eq!("code", parameter("response_type"));
+        assert_eq!("api", parameter("audience"));
+        assert_eq!("S256", parameter("code_challenge_method"));
+        let _ = challenges.send(parameter("code_challenge"));
+
+        let redirect = url::Url::parse(&parameter("redirect_uri")).unwrap();
+        let target = format!("{}?code=abc&state={}", redirect.path(), parameter("state"));
+        let address = format!("127.0.0.1:{}", redirect.port().unwrap());
+
+        std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream
+                .write_all(format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+                .unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+        });
+    };
+
+    let fetched = engine::runtime()
+        .block_on(oauth2::token(
+            &reqwest::Client::new(),
+            &auth,
+            &TokenCache::default(),
+            &browser,
+        ))
+        .unwrap();
+    assert_eq!("c0d3", fetched.access_token);
+
+    // The verifier sent to the token endpoint proves the request came from us
+    let received = requests.recv().unwrap();
+    let form: Vec<(String, String)> =
+        url::form_urlencoded::parse(received.split("\r\n\r\n").nth(1).unwrap().as_bytes())
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+    let field = |name: &str| {
+        form.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap()
+    };
+    assert_eq!("authorization_code", field("grant_type"));
+    assert_eq!("abc", field("code"));
+    assert_eq!(
+        challenge.recv().unwrap(),
+        URL_SAFE_NO_PAD.encode(sha2::Sha256::digest(field("code_verifier")))
+    );
+}
+
+#[test]
+fn sends_requests_with_the_access_token() {
+    let (uri, requests) = serve(vec![
+        json("200 OK", r#"{"access_token":"t0k3n","expires_in":3600}"#),
+        json("200 OK", "{}"),
+    ]);
+    let mut app = Reqwestur::default();
+    *app.request.lock().unwrap() = Request {
+        method: Method::GET,
+        address: Address {
+            uri: format!("{uri}/users"),
+            ..Default::default()
+        },
+        auth: credentials(&uri, GrantType::CLIENTCREDENTIALS),
+        ..Default::default()
+    };
+
+    let shared = app.request.clone();
+    assert!(app.send(&mut shared.lock().unwrap()).is_ok());
+
+    let started = Instant::now();
+    while app.request.lock().unwrap().event == RequestEvent::PENDING {
+        assert!(started.elapsed() < Duration::from_secs(10), "no response");
+        std::thread::sleep(Duration::from_millis(20));
+        app.poll();
+    }
+
+    assert!(requests.recv().unwrap().starts_with("POST /token"));
+    let sent = requests.recv().unwrap();
+    assert!(sent.starts_with("GET /users"));
+    assert!(sent.to_lowercase().contains("authorization: bearer t0k3n"));
+
+    // The token is kept for the next request, but not saved with it
+    let request = app.request.lock().unwrap();
+    assert!(request.auth.token.is_empty());
+    assert!(app.token_cache().get(&request.auth).is_some());
+}
@@ -0,0 +1,224 @@
+use reqwestur::utils::{
+    exports::{self, ExportType},
+    formats::openapi,
+    request::{ContentType, Method},
+};
+
+const PETSTORE: &str = r#"
+openapi: 3.0.3
+info:
+  title: Petstore
+  version: 1.0.0
+servers:
+  - url: https://{region}.example.com/v1/
+    variables:
+      region:
+        default: eu
+security:
+  - bearer: []
+components:
+  securitySchemes:
+    bearer:
+      type: http
+      scheme: bearer
+    key:
+      type: apiKey
+      in: header
+      name: X-Api-Key
+  parameters:
+    PetId:
+      name: petId
+      in: path
+      required: true
+      schema:
+        type: integer
+  schemas:
+    Named:
+      type: object
+      properties:
+        name:
+          type: string
+          example: Rex
+    Pet:
+      allOf:
+        - $ref: '#/components/schemas/Named'
+        - type: object
+          properties:
+            id:
+              type: integer
+              format: int64
+            born:
+              type: string
+              format: date
+            tags:
+              type: array
+              items:
+                type: string
+            status:
+              type: string
+              enum: [available, sold]
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      summary: List pets
+      parameters:
+        - name: limit
+          in: query
+          required: true
+          schema:
+            type: integer
+            default: 20
+        - name: page
+          in: query
+          schema:
+            type: integer
+        - name: X-Request-Id
+          in: header
+          required: true
+          schema:
+            type: string
+    post:
+      tags: [pets]
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/Pet'
+    head:
+      summary: Check pets
+  /pets/{petId}:
+    parameters:
+      - $ref: '#/components/parameters/PetId'
+    delete:
+      security:
+        - key: []
+    put:
+      summary: Upload a photo
+      requestBody:
+        content:
+          multipart/form-data:
+            schema:
+              type: object
+              properties:
+                caption:
+                  type: string
+                photo:
+                  type: string
+                  format: binary
+"#;
+
+#[test]
+fn imports_openapi_3_yaml() {
+    let parsed = openapi::parse(PETSTORE).expect("the document should parse");
+
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert_eq!(2, parsed.warnings.len(), "{:?}", parsed.warnings);
+    assert_eq!(4, parsed.requests.len());
+
+    let list = &parsed.requests[0];
+    assert_eq!("pets/List pets", list.name);
+    assert!(list.method == Method::GET);
+    assert_eq!("https://eu.example.com/v1/pets?limit=20", list.address.uri);
+    assert_eq!(
+        vec![
+            ("X-Request-Id".to_string(), "{{X-Request-Id}}".to_string()),
+            ("Authorization".to_string(), "Bearer {{token}}".to_string()),
+        ],
+        list.headers
+    );
+
+    let create = &parsed.requests[1];
+    assert_eq!("pets/createPet", create.name);
+    assert!(create.content_type == ContentType::JSON);
+    let body: serde_json::Value =
+        serde_json::from_str(create.body.as_deref().unwrap_or_default()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "name": "Rex",
+            "id": 0,
+            "born": "2025-01-01",
+            "tags": ["string"],
+            "status": "available",
+        }),
+        body
+    );
+
+    let upload = &parsed.requests[2];
+    assert!(upload.method == Method::PUT);
+    assert_eq!(
+        "https://eu.example.com/v1/pets/{{petId}}",
+        upload.address.uri
+    );
+    assert!(upload.content_type == ContentType::MULTIPART);
+    assert_eq!(
+        vec![("caption".to_string(), "string".to_string())],
+        upload.params
+    );
+
+    let delete = &parsed.requests[3];
+    assert_eq!("DELETE /pets/{petId}", delete.name);
+    assert_eq!(
+        vec![("X-Api-Key".to_string(), "{{X-Api-Key}}".to_string())],
+        delete.headers
+    );
+}
+
+#[test]
+fn imports_swagger_2_json() {
+    let contents = r##"{
+        "swagger": "2.0",
+        "basePath": "/api",
+        "consumes": ["application/json"],
+        "definitions": {
+            "User": {
+                "type": "object",
+                "properties": { "email": { "type": "string", "format": "email" } }
+            }
+        },
+        "paths": {
+            "/users": {
+                "post": {
+                    "parameters": [
+                        { "name": "user", "in": "body", "schema": { "$ref": "#/definitions/User" } }
+                    ]
+                }
+            },
+            "/avatars": {
+                "post": {
+                    "consumes": ["multipart/form-data"],
+                    "parameters": [
+                        { "name": "user", "in": "formData", "type": "string", "x-example": "rex" },
+                        { "name": "avatar", "in": "formData", "type": "file" }
+                    ]
+                }
+            }
+        }
+    }"##;
+
+    // Documents are detected when imported as JSON
+    let parsed = exports::parse(&ExportType::JSON, contents).unwrap();
+
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert_eq!(1, parsed.warnings.len(), "{:?}", parsed.warnings);
+
+    let user = &parsed.requests[1];
+    assert_eq!("{{baseUrl}}/api/users", user.address.uri);
+    assert!(user.content_type == ContentType::JSON);
+    assert_eq!(
+        Some("{\n  \"email\": \"user@example.com\"\n}".to_string()),
+        user.body
+    );
+
+    let avatar = &parsed.requests[0];
+    assert!(avatar.content_type == ContentType::MULTIPART);
+    assert_eq!(vec![("user".to_string(), "rex".to_string())], avatar.params);
+}
+
+#[test]
+fn rejects_other_documents() {
+    assert!(openapi::parse("swagger: '1.2'\npaths: {}").is_err());
+    assert!(openapi::parse("- not\n- a document").is_err());
+    assert!(!openapi::is_document("[]"));
+}
@@ -0,0 +1,299 @@
+use reqwestur::utils::{
+    auth::AuthKind,
+    formats::postman,
+    request::{Address, ContentType, Method, Request},
+};
+
+fn request(name: &str, method: Method, uri: &str) -> Request {
+    Request {
+        name: name.to_string(),
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn assert_same(expected: &Request, actual: &Request) {
+    assert_eq!(expected.name, actual.name);
+    assert!(expected.method == actual.method, "methods differ");
+    assert_eq!(expected.address.uri, actual.address.uri);
+    assert_eq!(expected.headers, actual.headers);
+    assert!(
+        expected.content_type == actual.content_type,
+        "content types differ"
+    );
+    assert_eq!(expected.body, actual.body);
+    assert_eq!(expected.params, actual.params);
+}
+
+#[test]
+fn round_trips_requests_and_folders() {
+    let requests = vec![
+        Request {
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            ..request(
+                "Users/List",
+                Method::GET,
+                "https://example.com:8443/users?page=2&sort=name",
+            )
+        },
+        Request {
+            content_type: ContentType::JSON,
+            body: Some("{\n  \"name\": \"Reqwestur\"\n}".to_string()),
+            ..request("Users/Create", Method::POST, "https://example.com/users")
+        },
+        Request {
+            headers: vec![("Content-Type".to_string(), "text/xml".to_string())],
+            content_type: ContentType::TEXT,
+            body: Some("<user/>".to_string()),
+            ..request(
+                "Users/Admin/Replace",
+                Method::PUT,
+                "https://example.com/users/1",
+            )
+        },
+        Request {
+            content_type: ContentType::XWWWFORMURLENCODED,
+            params: vec![
+                ("name".to_string(), "Req Westur".to_string()),
+                ("query".to_string(), "a=b&c".to_string()),
+            ],
+            ..request("Update", Method::PATCH, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::MULTIPART,
+            params: vec![("file".to_string(), "contents".to_string())],
+            ..request("Upload", Method::POST, "https://example.com/upload")
+        },
+        request(
+            "Users/Delete",
+            Method::DELETE,
+            "https://example.com/users/1",
+        ),
+    ];
+
+    let collection = postman::serialize("reqwestur_saved_requests", &requests);
+    let parsed = postman::parse(&collection).expect("the collection should parse");
+
+    assert!(parsed.errors.is_empty(), "{:?}", parsed.errors);
+    assert!(parsed.warnings.is_empty(), "{:?}", parsed.warnings);
+
+    // Requests are grouped by folder, so compare them by name
+    assert_eq!(requests.len(), parsed.requests.len());
+    for expected in &requests {
+        let actual = parsed
+            .requests
+            .iter()
+            .find(|actual| actual.name == expected.name)
+            .expect("the request should be imported");
+        assert_same(expected, actual);
+    }
+}
+
+#[test]
+fn writes_folders_and_the_v2_1_schema() {
+    let requests = vec![
+        request("Users/List", Method::GET, "https://example.com/users"),
+        request("Users/Get", Method::GET, "https://example.com/users/1"),
+        request("", Method::GET, "https://example.com"),
+    ];
+
+    let collection: serde_json::Value =
+        serde_json::from_str(&postman::serialize("Example", &requests)).unwrap();
+
+    assert_eq!(postman::SCHEMA, collection["info"]["schema"]);
+    assert_eq!("Example", collection["info"]["name"]);
+    assert_eq!("Users", collection["item"][0]["name"]);
+    assert_eq!(2, collection["item"][0]["item"].as_array().unwrap().len());
+    assert_eq!("GET https://example.com", collection["item"][1]["name"]);
+    assert_eq!(
+        serde_json::json!(["example", "com"]),
+        collection["item"][0]["item"][0]["request"]["url"]["host"]
+    );
+}
+
+#[test]
+fn imports_variables_auth_and_reports_unsupported_features() {
+    let collection = r#"{
+        "info": { "name": "Demo", "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json" },
+        "variable": [{ "key": "base", "value": "https://example.com" }],
+        "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "secret", "type": "string" }] },
+        "item": [
+            {
+                "name": "Users",
+                "item": [
+                    {
+                        "name": "List",
+                        "request": {
+                            "method": "GET",
+                            "header": [
+                                { "key": "Accept", "value": "*/*" },
+                                { "key": "X-Disabled", "value": "1", "disabled": true }
+                            ],
+                            "url": { "raw": "{{base}}/users", "host": ["{{base}}"], "path": ["users"] }
+                        }
+                    },
+                    {
+                        "name": "Upload",
+                        "event": [{ "listen": "test", "script": { "exec": [] } }],
+                        "request": {
+                            "method": "POST",
+                            "auth": { "type": "basic", "basic": [
+                                { "key": "username", "value": "user" },
+                                { "key": "password", "value": "pass" }
+                            ] },
+                            "url": "{{base}}/upload",
+                            "body": { "mode": "formdata", "formdata": [
+                                { "key": "name", "value": "reqwestur", "type": "text" },
+                                { "key": "file", "src": "/tmp/file", "type": "file" }
+                            ] }
+                        }
+                    },
+                    { "name": "Options", "request": { "method": "OPTIONS", "url": "{{base}}" } }
+                ]
+            }
+        ]
+    }"#;
+
+    assert!(postman::is_collection(collection));
+
+    let parsed = postman::parse(collection).unwrap();
+    assert_eq!(2, parsed.requests.len());
+    assert_eq!(1, parsed.errors.len());
+    assert_eq!(2, parsed.warnings.len());
+
+    let list = &parsed.requests[0];
+    assert_eq!("Users/List", list.name);
+    assert_eq!("https://example.com/users", list.address.uri);
+    assert_eq!(
+        vec![
+            ("Accept".to_string(), "*/*".to_string()),
+            ("Authorization".to_string(), "Bearer secret".to_string()),
+        ],
+        list.headers
+    );
+
+    let upload = &parsed.requests[1];
+    assert!(upload.content_type == ContentType::MULTIPART);
+    assert_eq!(
+        vec![("name".to_string(), "reqwestur".to_string())],
+        upload.params
+    );
+    assert!(upload.headers.is_empty());
+    assert!(upload.auth.kind == AuthKind::BASIC);
+    assert_eq!(
+        ("user", "pass"),
+        (upload.auth.username.as_str(), upload.auth.password.as_str())
+    );
+}
+
+#[test]
+fn imports_bodies_urls_and_v2_0_collections() {
+    let collection = r#"{
+        "info": { "name": "Legacy", "schema": "https://schema.getpostman.com/json/collection/v2.0.0/collection.json" },
+        "event": [{ "listen": "prerequest", "script": { "exec": [] } }],
+        "item": [
+            {
+                "name": "Create",
+                "request": {
+                    "method": "post",
+                    "auth": { "type": "basic", "basic": { "username": "user", "password": "pass" } },
+                    "header": [{ "key": "Content-Type", "value": "application/json" }],
+                    "url": {
+                        "protocol": "https",
+                        "host": ["api", "example", "com"],
+                        "port": "8443",
+                        "path": ["v1", "users"],
+                        "query": [
+                            { "key": "page", "value": "2" },
+                            { "key": "debug", "value": "1", "disabled": true }
+                        ]
+                    },
+                    "body": { "mode": "raw", "raw": "{\"name\": \"Reqwestur\"}" }
+                }
+            },
+            {
+                "name": "Login",
+                "request": {
+                    "method": "POST",
+                    "auth": { "type": "apikey", "apikey": [] },
+                    "header": [{ "key": "Content-Type", "value": "text/plain" }],
+                    "url": "https://example.com/login",
+                    "body": { "mode": "urlencoded", "urlencoded": [
+                        { "key": "user", "value": "admin" },
+                        { "key": "remember", "value": "1", "disabled": true }
+                    ] }
+                }
+            },
+            { "name": "Home", "request": "https://example.com" },
+            { "name": "Purge", "request": { "method": "PURGE", "url": "https://example.com" } },
+            { "name": "Empty", "request": { "method": "GET" } },
+            {
+                "name": "Graph",
+                "request": { "method": "POST", "url": "https://example.com/graphql", "body": { "mode": "graphql" } }
+            }
+        ]
+    }"#;
+
+    let parsed = postman::parse(collection).unwrap();
+    assert_eq!(
+        vec![
+            "Entry \"Purge\": Unsupported method \"PURGE\".".to_string(),
+            "Entry \"Empty\": The request has no URI.".to_string(),
+        ],
+        parsed.errors
+    );
+    assert_eq!(
+        vec![
+            "the collection schema \"https://schema.getpostman.com/json/collection/v2.0.0/collection.json\" is not v2.1, it was read as v2.1".to_string(),
+            "scripts on \"Legacy\"".to_string(),
+            "apikey auth on \"Login\"".to_string(),
+            "graphql body on \"Graph\"".to_string(),
+        ],
+        parsed.warnings
+    );
+    assert_eq!(4, parsed.requests.len());
+
+    // v2.0 auth attributes are an object rather than a list
+    assert_eq!(
+        ("user", "pass"),
+        (
+            parsed.requests[0].auth.username.as_str(),
+            parsed.requests[0].auth.password.as_str()
+        )
+    );
+    let create = Request {
+        headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+        content_type: ContentType::JSON,
+        body: Some("{\"name\": \"Reqwestur\"}".to_string()),
+        ..request(
+            "Create",
+            Method::POST,
+            "https://api.example.com:8443/v1/users?page=2",
+        )
+    };
+    assert_same(&create, &parsed.requests[0]);
+
+    // Form bodies set their own Content-Type
+    let login = Request {
+        content_type: ContentType::XWWWFORMURLENCODED,
+        params: vec![("user".to_string(), "admin".to_string())],
+        ..request("Login", Method::POST, "https://example.com/login")
+    };
+    assert_same(&login, &parsed.requests[1]);
+
+    assert_same(
+        &request("Home", Method::GET, "https://example.com"),
+        &parsed.requests[2],
+    );
+
+    assert!(!postman::is_collection("[]"));
+    assert!(
+        postman::parse("{")
+            .err()
+            .is_some_and(|error| error.starts_with("The file is not a Postman collection"))
+    );
+}
@@ -0,0 +1,104 @@
+use reqwestur::utils::{
+    engine::{ClientSettings, RequestExecutor},
+    request::{Address, QueryParam, Request},
+};
+
+fn address(uri: &str) -> Address {
+    let mut address = Address {
+        uri: uri.to_string(),
+        ..Default::default()
+    };
+    address.query_from_uri();
+    address
+}
+
+fn disabled(name: &str, value: &str) -> QueryParam {
+    QueryParam {
+        enabled: false,
+        ..QueryParam::new(name, value)
+    }
+}
+
+#[test]
+fn reads_the_parameters_from_the_uri() {
+    let address = address("https://example.com/search?q=hello+world&tag=a%26b&flag#results");
+
+    assert_eq!(
+        vec![
+            QueryParam::new("q", "hello world"),
+            QueryParam::new("tag", "a&b"),
+            QueryParam::new("flag", ""),
+        ],
+        address.query
+    );
+    assert!(self::address("{{host}}/users").query.is_empty());
+}
+
+#[test]
+fn writes_the_parameters_to_the_uri() {
+    let mut address = address("https://example.com/search?q=old#results");
+
+    address.query[0].value = "a b&c=d/é".to_string();
+    address.query.push(QueryParam::new("token", "{{token}} 2"));
+    address.query.push(disabled("debug", "true"));
+    address.uri_from_query();
+
+    assert_eq!(
+        "https://example.com/search?q=a+b%26c%3Dd%2F%C3%A9&token={{token}}+2#results",
+        address.uri
+    );
+
+    // The engine sends the values as they were typed
+    let built = RequestExecutor::new(ClientSettings::default())
+        .expect("the client should build")
+        .build(&Request {
+            address: Address {
+                uri: address.uri.replace("{{token}}", "s3cr3t"),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(
+        vec![
+            ("q".to_string(), "a b&c=d/é".to_string()),
+            ("token".to_string(), "s3cr3t 2".to_string()),
+        ],
+        built.url().query_pairs().into_owned().collect::<Vec<_>>()
+    );
+
+    // Without any enabled parameters the query is removed
+    address.query[0].enabled = false;
+    address.query.remove(1);
+    address.uri_from_query();
+    assert_eq!("https://example.com/search#results", address.uri);
+}
+
+#[test]
+fn keeps_disabled_and_unnamed_rows_when_the_uri_changes() {
+    let mut address = address("https://example.com/?a=1&c=3");
+    address.query.insert(1, disabled("b", "2"));
+    address.query.push(QueryParam::new("", ""));
+    address.uri_from_query();
+    assert_eq!("https://example.com/?a=1&c=3", address.uri);
+
+    address.uri = "https://example.com/?a=one&c=3&d=4".to_string();
+    address.query_from_uri();
+    assert_eq!(
+        vec![
+            QueryParam::new("a", "one"),
+            disabled("b", "2"),
+            QueryParam::new("c", "3"),
+            QueryParam::new("", ""),
+            QueryParam::new("d", "4"),
+        ],
+        address.query
+    );
+
+    address.uri = "https://example.com/".to_string();
+    address.query_from_uri();
+    assert_eq!(
+        vec![disabled("b", "2"), QueryParam::new("", "")],
+        address.query
+    );
+}
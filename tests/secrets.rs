@@ -0,0 +1,275 @@
+use reqwestur::utils::{
+    environments::{self, Environment},
+    formats::curl,
+    request::{Address, Method, Request},
+    reqwestur::Reqwestur,
+    secrets::{Secret, Vault},
+};
+
+/// A vault file in a fresh temporary directory
+fn vault_path(name: &str) -> std::path::PathBuf {
+    let directory = std::env::temp_dir().join(format!("reqwestur-{name}-{}", Secret::default().id));
+    directory.join("secrets.vault")
+}
+
+fn secret(value: &str) -> Secret {
+    Secret {
+        value: value.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn encrypts_secrets_with_the_master_password() {
+    let path = vault_path("vault");
+    let token = secret("s3cr3t-token");
+
+    let mut vault = Vault::new(&path);
+    assert!(!vault.exists());
+    assert!(vault.set(&token).is_err());
+
+    vault.create("correct horse").unwrap();
+    vault.set(&token).unwrap();
+    assert!(vault.exists());
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("s3cr3t-token"), "{contents}");
+    assert!(!contents.contains(&token.id), "{contents}");
+
+    let mut reopened = Vault::new(&path);
+    assert_eq!(
+        Err("The master password is incorrect.".to_string()),
+        reopened.unlock("battery staple")
+    );
+    assert!(!reopened.is_unlocked());
+
+    reopened.unlock("correct horse").unwrap();
+    assert_eq!(Some("s3cr3t-token"), reopened.get(&token.id));
+
+    reopened.remove(&token).unwrap();
+    reopened.lock();
+    assert_eq!(None, reopened.get(&token.id));
+
+    reopened.unlock("correct horse").unwrap();
+    assert_eq!(None, reopened.get(&token.id));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn persists_only_secret_references() {
+    let environment = Environment {
+        variables: vec![("baseUrl".to_string(), "https://example.com".to_string())],
+        secrets: vec![("token".to_string(), secret("s3cr3t-token"))],
+        ..Environment::new("Production")
+    };
+
+    let persisted = ron::to_string(&environment).unwrap();
+    assert!(!persisted.contains("s3cr3t-token"), "{persisted}");
+
+    let restored: Environment = ron::from_str(&persisted).unwrap();
+    assert_eq!(environment.secrets[0].1.id, restored.secrets[0].1.id);
+    assert!(restored.secrets[0].1.value.is_empty());
+
+    // Locked secrets aren't filled in
+    assert_eq!(
+        vec![("baseUrl".to_string(), "https://example.com".to_string())],
+        restored.values()
+    );
+    assert_eq!(2, environment.values().len());
+}
+
+#[test]
+fn unlocks_environment_secrets_and_redacts_exports() {
+    let path = vault_path("app");
+    let token = secret("s3cr3t-token");
+
+    let mut vault = Vault::new(&path);
+    vault.create("correct horse").unwrap();
+    vault.set(&token).unwrap();
+
+    let mut app = Reqwestur {
+        vault: Vault::new(&path),
+        environments: vec![Environment {
+            secrets: vec![(
+                "token".to_string(),
+                Secret {
+                    value: String::new(),
+                    ..token
+                },
+            )],
+            ..Environment::new("Production")
+        }],
+        active_environment: Some(0),
+        ..Default::default()
+    };
+
+    let mut request = Request {
+        method: Method::GET,
+        address: Address {
+            uri: "https://example.com/{{token}}".to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let notification = app.send(&mut request).unwrap_err();
+    assert_eq!(
+        Some("Unlock the vault to fill in {{token}}."),
+        notification.message()
+    );
+
+    app.unlock_vault("correct horse").unwrap();
+    assert_eq!(
+        vec![("token".to_string(), "s3cr3t-token".to_string())],
+        app.variables()
+    );
+
+    let sent = environments::resolve(&request, &app.variables());
+    assert_eq!("https://example.com/s3cr3t-token", sent.address.uri);
+    assert_eq!(
+        request.address.uri,
+        environments::redact(&sent, &app.secrets()).address.uri
+    );
+
+    app.lock_vault();
+    assert!(app.variables().is_empty());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn moves_saved_passphrases_into_the_vault() {
+    let path = vault_path("passphrase");
+    let saved =
+        r#"(certificates: [(name: "Client", passphrase_secret: "abc", passphrase: "hunter2")])"#;
+    let mut app = Reqwestur {
+        vault: Vault::new(&path),
+        ..ron::from_str(saved).unwrap()
+    };
+    assert_eq!("hunter2", app.certificates[0].legacy_passphrase);
+
+    // Saving before the vault is unlocked keeps the passphrase
+    assert!(ron::to_string(&app).unwrap().contains("hunter2"));
+
+    app.unlock_vault("correct horse").unwrap();
+    assert_eq!("hunter2", app.certificates[0].passphrase.value);
+    assert!(!ron::to_string(&app).unwrap().contains("hunter2"));
+
+    let mut vault = Vault::new(&path);
+    vault.unlock("correct horse").unwrap();
+    assert_eq!(Some("hunter2"), vault.get("abc"));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn locked_secrets_stop_exports() {
+    let path = vault_path("locked");
+    let mut app = Reqwestur {
+        vault: Vault::new(&path),
+        environments: vec![Environment::new("Production")],
+        ..Default::default()
+    };
+    assert!(!app.secrets_locked());
+
+    app.environments[0].secrets = vec![("token".to_string(), secret(""))];
+    assert!(app.secrets_locked());
+
+    app.unlock_vault("correct horse").unwrap();
+    assert!(!app.secrets_locked());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn keeps_the_proxy_password_in_the_vault() {
+    let path = vault_path("proxy");
+    let saved = r#"(proxy: (kind: HTTP, address: "proxy.example.com:8080", username: "user", password: "hunter2"))"#;
+    let mut app = Reqwestur {
+        vault: Vault::new(&path),
+        ..ron::from_str(saved).unwrap()
+    };
+
+    let request = Request::default();
+    assert_eq!(
+        "hunter2",
+        app.proxy_for(&request).ok().unwrap().password.value
+    );
+
+    app.unlock_vault("correct horse").unwrap();
+    assert!(app.proxy.legacy_password.is_empty());
+    assert_eq!(
+        "hunter2",
+        app.proxy_for(&request).ok().unwrap().password.value
+    );
+
+    let persisted = ron::to_string(&app).unwrap();
+    assert!(!persisted.contains("hunter2"), "{persisted}");
+
+    let mut restored: Reqwestur = ron::from_str(&persisted).unwrap();
+    restored.vault = Vault::new(&path);
+    assert!(
+        restored
+            .proxy_for(&request)
+            .ok()
+            .unwrap()
+            .password
+            .value
+            .is_empty()
+    );
+
+    restored.unlock_vault("correct horse").unwrap();
+    assert_eq!(
+        "hunter2",
+        restored.proxy_for(&request).ok().unwrap().password.value
+    );
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn keeps_the_proxy_passwords_of_requests_in_the_vault() {
+    let path = vault_path("request_proxy");
+    let saved = r#"(saved_requests: [(name: "Users", proxy: Some((kind: HTTP, address: "proxy.example.com:8080", username: "user", password: "hunter2")))])"#;
+    let mut app = Reqwestur {
+        vault: Vault::new(&path),
+        ..ron::from_str(saved).unwrap()
+    };
+
+    let request = app.saved_requests[0].clone();
+    assert_eq!(
+        "hunter2",
+        app.proxy_for(&request).ok().unwrap().password.value
+    );
+
+    app.unlock_vault("correct horse").unwrap();
+    let persisted = ron::to_string(&app).unwrap();
+    assert!(!persisted.contains("hunter2"), "{persisted}");
+
+    // Requests opened before the vault is unlocked read the password from it when they're sent
+    let mut restored: Reqwestur = ron::from_str(&persisted).unwrap();
+    restored.vault = Vault::new(&path);
+    let request = restored.saved_requests[0].clone();
+    restored.unlock_vault("correct horse").unwrap();
+    assert_eq!(
+        "hunter2",
+        restored.saved_requests[0]
+            .proxy
+            .as_ref()
+            .unwrap()
+            .password
+            .value
+    );
+    assert_eq!(
+        "hunter2",
+        restored.proxy_for(&request).ok().unwrap().password.value
+    );
+
+    // Exports and copied curl commands have a placeholder instead
+    let proxy = restored.saved_requests[0].proxy.as_ref().unwrap();
+    assert_eq!("{{proxyPassword}}", proxy.masked().password.value);
+    assert!(curl::serialize(&restored.saved_requests[0]).contains("-U 'user:{{proxyPassword}}'"));
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
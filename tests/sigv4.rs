@@ -0,0 +1,233 @@
+use chrono::{DateTime, Utc};
+
+use reqwestur::utils::{
+    auth::{Auth, AuthKind},
+    engine::{ClientSettings, RequestExecutor},
+    formats::curl,
+    request::{Address, ContentType, Method, Request},
+    sigv4::{self, SigV4},
+};
+
+/// The credentials and time used throughout the AWS SigV4 test suite
+fn credentials() -> SigV4 {
+    SigV4 {
+        access_key: "AKIDEXAMPLE".to_string(),
+        secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+        region: "us-east-1".to_string(),
+        service: "service".to_string(),
+        ..Default::default()
+    }
+}
+
+fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2015-08-30T12:36:00Z")
+        .unwrap()
+        .to_utc()
+}
+
+fn signed(sigv4: &SigV4, method: &str, uri: &str, headers: &[(&str, &str)], body: &str) -> String {
+    let client = reqwest::Client::new();
+    let mut builder = client.request(method.parse().unwrap(), uri);
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+    if !body.is_empty() {
+        builder = builder.body(body.to_string());
+    }
+    let mut request = builder.build().unwrap();
+
+    sigv4::sign(sigv4, &mut request, now()).unwrap();
+    assert_eq!("20150830T123600Z", request.headers()["x-amz-date"]);
+
+    request.headers()["authorization"]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+fn authorization(signed_headers: &str, signature: &str) -> String {
+    format!(
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+         SignedHeaders={signed_headers}, Signature={signature}"
+    )
+}
+
+#[test]
+fn derives_the_signing_key() {
+    // The example from the AWS documentation
+    let key = sigv4::signing_key(
+        "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+        "20150830",
+        "us-east-1",
+        "iam",
+    );
+    assert_eq!(
+        "c4afb1cc5771d871763a393e44b703571b55cc28424d1a5e86da6ed3c154a4b9",
+        key.iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>()
+    );
+}
+
+#[test]
+fn signs_the_test_suite_requests() {
+    let sigv4 = credentials();
+
+    // get-vanilla
+    assert_eq!(
+        authorization(
+            "host;x-amz-date",
+            "5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        ),
+        signed(&sigv4, "GET", "https://example.amazonaws.com/", &[], "")
+    );
+
+    // get-vanilla-query-order-key-case
+    assert_eq!(
+        authorization(
+            "host;x-amz-date",
+            "b97d918cfa904a5beff61c982a1b6f458b799221646efd99d3219ec94cdf2500"
+        ),
+        signed(
+            &sigv4,
+            "GET",
+            "https://example.amazonaws.com/?Param2=value2&Param1=value1",
+            &[],
+            ""
+        )
+    );
+
+    // post-vanilla
+    assert_eq!(
+        authorization(
+            "host;x-amz-date",
+            "5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        ),
+        signed(&sigv4, "POST", "https://example.amazonaws.com/", &[], "")
+    );
+
+    // post-x-www-form-urlencoded
+    assert_eq!(
+        authorization(
+            "content-type;host;x-amz-date",
+            "ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        ),
+        signed(
+            &sigv4,
+            "POST",
+            "https://example.amazonaws.com/",
+            &[("Content-Type", "application/x-www-form-urlencoded")],
+            "Param1=value1"
+        )
+    );
+}
+
+#[test]
+fn signs_a_plus_in_the_query_as_a_plus() {
+    let sigv4 = credentials();
+    let sign = |uri: &str| signed(&sigv4, "GET", uri, &[], "");
+
+    // AWS decodes `+` as itself, so it's signed as `%2B` rather than as a space
+    assert_eq!(
+        sign("https://example.amazonaws.com/?q=a%2Bb"),
+        sign("https://example.amazonaws.com/?q=a+b")
+    );
+    assert_ne!(
+        sign("https://example.amazonaws.com/?q=a%20b"),
+        sign("https://example.amazonaws.com/?q=a+b")
+    );
+}
+
+#[test]
+fn signs_the_session_token() {
+    let sigv4 = SigV4 {
+        session_token: "AQoDYXdzEPT//////////wEXAMPLEtc764bNrC9SAPBSM22wDOk4x4HIZ8j4FZTwdQWLWsKWHGBuFqwAeMicRXmxfpSPfIeoIYRqTflfKD8YUuwthAx7mSEI/qkPpKPi/kMcGdQrmGdeehM4IC1NtBmUpp2wUE8phUZampKsburEDy0KPkyQDYwT7WZ0wq5VSXDvp75YU9HFvlRd8Tx6q6fE8YQcHNVXAkiY9q6d+xo0rKwT38xVqr7ZD0u0iPPkUL64lIZbqBAz+scqKmlzm8FDrypNC9Yjc8fPOLn9FX9KSYvKTr4rvx3iSIlTJabIQwj2ICCR/oLxBA==".to_string(),
+        ..credentials()
+    };
+
+    // post-sts-header-before
+    assert_eq!(
+        authorization(
+            "host;x-amz-date;x-amz-security-token",
+            "85d96828115b5dc0cfc3bd16ad9e210dd772bbebba041836c64533a82be05ead"
+        ),
+        signed(&sigv4, "POST", "https://example.amazonaws.com/", &[], "")
+    );
+}
+
+#[test]
+fn signs_requests_as_they_are_built() {
+    let request = Request {
+        method: Method::POST,
+        address: Address {
+            uri: "https://abc123.execute-api.eu-west-2.amazonaws.com/prod/orders?page=2"
+                .to_string(),
+            ..Default::default()
+        },
+        headers: vec![("Authorization".to_string(), "replaced".to_string())],
+        content_type: ContentType::JSON,
+        body: Some("{\"id\": 1}".to_string()),
+        auth: Auth {
+            kind: AuthKind::AWSSIGV4,
+            sigv4: SigV4 {
+                region: "eu-west-2".to_string(),
+                service: "execute-api".to_string(),
+                ..credentials()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let built = RequestExecutor::new(ClientSettings::default())
+        .expect("the client should build")
+        .build(&request)
+        .unwrap();
+    let authorization = built
+        .headers()
+        .get_all("authorization")
+        .iter()
+        .collect::<Vec<_>>();
+    assert_eq!(1, authorization.len());
+
+    let authorization = authorization[0].to_str().unwrap();
+    assert!(authorization.starts_with("AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/"));
+    assert!(authorization.contains("/eu-west-2/execute-api/aws4_request"));
+    assert!(authorization.contains("SignedHeaders=content-type;host;x-amz-date,"));
+
+    let missing = Request {
+        auth: Auth {
+            kind: AuthKind::AWSSIGV4,
+            ..Default::default()
+        },
+        ..request
+    };
+    assert!(
+        RequestExecutor::new(ClientSettings::default())
+            .unwrap()
+            .build(&missing)
+            .is_err()
+    );
+}
+
+#[test]
+fn converts_curl_aws_signatures() {
+    let parsed = curl::parse(
+        "curl https://example.amazonaws.com/ --aws-sigv4 aws:amz:us-east-1:service \
+         -u 'AKIDEXAMPLE:secret' -H 'X-Amz-Security-Token: t0k3n'",
+    );
+    let request = &parsed.requests[0];
+
+    assert!(request.auth.kind == AuthKind::AWSSIGV4);
+    assert_eq!("AKIDEXAMPLE", request.auth.sigv4.access_key);
+    assert_eq!("secret", request.auth.sigv4.secret_key);
+    assert_eq!("t0k3n", request.auth.sigv4.session_token);
+    assert_eq!("us-east-1", request.auth.sigv4.region);
+    assert_eq!("service", request.auth.sigv4.service);
+    assert!(request.headers.is_empty());
+
+    let command = curl::serialize(request);
+    assert!(command.contains("--aws-sigv4 aws:amz:us-east-1:service"));
+    assert!(command.contains("-u AKIDEXAMPLE:secret"));
+    assert!(command.contains("-H 'X-Amz-Security-Token: t0k3n'"));
+}
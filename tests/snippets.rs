@@ -0,0 +1,187 @@
+use reqwestur::utils::{
+    auth::{ApiKeyLocation, Auth, AuthKind},
+    request::{Address, ContentType, Method, Request},
+    snippets::Snippet,
+};
+
+fn request(method: Method, uri: &str) -> Request {
+    Request {
+        method,
+        address: Address {
+            uri: uri.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn json_request() -> Request {
+    Request {
+        headers: vec![("Authorization".to_string(), "Bearer token".to_string())],
+        content_type: ContentType::JSON,
+        body: Some("{\"name\": \"Reqwestur\"}".to_string()),
+        ..request(Method::POST, "https://example.com:8443/users?page=1")
+    }
+}
+
+#[test]
+fn generates_reqwest_blocking() {
+    assert_eq!(
+        Snippet::REQWEST.generate(&json_request()),
+        r###"fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = reqwest::blocking::Client::new();
+    let response = client
+        .post("https://example.com:8443/users?page=1")
+        .header("Authorization", "Bearer token")
+        .header("Content-Type", "application/json")
+        .body(r#"{"name": "Reqwestur"}"#)
+        .send()?;
+
+    println!("{}", response.status());
+    println!("{}", response.text()?);
+
+    Ok(())
+}
+"###
+    );
+}
+
+#[test]
+fn generates_raw_http() {
+    assert_eq!(
+        Snippet::HTTP.generate(&json_request()),
+        "POST /users?page=1 HTTP/1.1\r\n\
+         Host: example.com:8443\r\n\
+         Authorization: Bearer token\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: 21\r\n\
+         \r\n\
+         {\"name\": \"Reqwestur\"}"
+    );
+
+    assert_eq!(
+        Snippet::HTTP.generate(&request(Method::GET, "https://example.com")),
+        "GET / HTTP/1.1\r\nHost: example.com\r\n\r\n"
+    );
+
+    // Multipart delimiters use CRLF too, and are counted in the length
+    let multipart = Snippet::HTTP.generate(&Request {
+        content_type: ContentType::MULTIPART,
+        params: vec![("name".to_string(), "Reqwestur".to_string())],
+        ..request(Method::POST, "https://example.com/upload")
+    });
+    let (head, body) = multipart.split_once("\r\n\r\n").unwrap();
+    assert_eq!(
+        "--ReqwesturBoundary\r\n\
+         Content-Disposition: form-data; name=\"name\"\r\n\
+         \r\n\
+         Reqwestur\r\n\
+         --ReqwesturBoundary--\r\n",
+        body
+    );
+    assert!(
+        head.ends_with(&format!("\r\nContent-Length: {}", body.len())),
+        "{head}"
+    );
+    assert!(!head.replace("\r\n", "").contains('\n'), "{head}");
+}
+
+#[test]
+fn generates_every_language_for_every_content_type() {
+    let requests = vec![
+        request(Method::GET, "https://example.com"),
+        json_request(),
+        Request {
+            content_type: ContentType::XWWWFORMURLENCODED,
+            params: vec![("name".to_string(), "Req \"Westur\"".to_string())],
+            ..request(Method::PATCH, "https://example.com/users/1")
+        },
+        Request {
+            content_type: ContentType::MULTIPART,
+            params: vec![("file".to_string(), "contents".to_string())],
+            ..request(Method::PUT, "https://example.com/upload")
+        },
+    ];
+
+    for snippet in Snippet::values() {
+        for request in &requests {
+            let code = snippet.generate(request);
+            assert!(
+                code.contains(&request.address.uri) || snippet == Snippet::HTTP,
+                "{}: {code}",
+                snippet.to_string()
+            );
+            assert!(
+                code.to_uppercase()
+                    .contains(&request.method.to_string().to_uppercase()),
+                "{}: {code}",
+                snippet.to_string()
+            );
+
+            for (name, _) in &request.params {
+                assert!(code.contains(name), "{}: {code}", snippet.to_string());
+            }
+        }
+    }
+
+    let python = Snippet::PYTHON.generate(&requests[2]);
+    assert!(
+        python.contains("(\"name\", \"Req \\\"Westur\\\"\"),"),
+        "{python}"
+    );
+
+    let go = Snippet::GO.generate(&requests[3]);
+    assert!(go.contains("\t\"mime/multipart\""), "{go}");
+    assert!(go.contains("writer.FormDataContentType()"), "{go}");
+
+    let javascript = Snippet::JAVASCRIPT.generate(&requests[3]);
+    assert!(
+        javascript.starts_with("const body = new FormData();\n"),
+        "{javascript}"
+    );
+}
+
+#[test]
+fn sends_the_credentials() {
+    let basic = Request {
+        auth: Auth {
+            kind: AuthKind::BASIC,
+            username: "user".to_string(),
+            password: "pass".to_string(),
+            ..Default::default()
+        },
+        ..json_request()
+    };
+    let api_key = Request {
+        auth: Auth {
+            kind: AuthKind::APIKEY,
+            key_name: "api_key".to_string(),
+            key_value: "{{key}}".to_string(),
+            key_location: ApiKeyLocation::QUERY,
+            ..Default::default()
+        },
+        ..request(Method::GET, "https://example.com/users")
+    };
+
+    for snippet in Snippet::values() {
+        let code = snippet.generate(&basic);
+        assert!(
+            code.contains("Basic dXNlcjpwYXNz"),
+            "{}: {code}",
+            snippet.to_string()
+        );
+        // The credentials replace the Authorization header
+        assert!(
+            !code.contains("Bearer token"),
+            "{}: {code}",
+            snippet.to_string()
+        );
+
+        let code = snippet.generate(&api_key);
+        assert!(
+            code.contains("/users?api_key={{key}}"),
+            "{}: {code}",
+            snippet.to_string()
+        );
+    }
+}
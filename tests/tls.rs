@@ -0,0 +1,231 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+};
+
+use reqwestur::utils::{
+    engine,
+    environments::Environment,
+    request::{Address, Request},
+    reqwestur::Reqwestur,
+    tls::{TlsSettings, TlsVersion},
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/tls")
+        .join(name)
+}
+
+/// Serve HTTPS on a local port with a certificate for `tls.test` issued by the test CA
+fn serve() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let identity = native_tls::Identity::from_pkcs8(
+        &std::fs::read(fixture("server.pem")).unwrap(),
+        &std::fs::read(fixture("server.key")).unwrap(),
+    )
+    .unwrap();
+    let acceptor = native_tls::TlsAcceptor::new(identity).unwrap();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // Handshakes rejected by the client are expected
+            let Ok(mut stream) = acceptor.accept(stream) else {
+                continue;
+            };
+
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        }
+    });
+
+    address.to_string()
+}
+
+/// An app connecting to the hosts at the local server's address
+fn app(address: &str, hosts: &[&str]) -> Reqwestur {
+    Reqwestur {
+        tls: TlsSettings {
+            connect_to: hosts
+                .iter()
+                .map(|host| (host.to_string(), address.to_string()))
+                .collect(),
+            ..Default::default()
+        },
+        environments: vec![Environment::new("Local")],
+        active_environment: Some(0),
+        ..Default::default()
+    }
+}
+
+/// Send a GET request to the host, returning the response body or the error
+fn get(app: &mut Reqwestur, host: &str, address: &str) -> Result<String, String> {
+    let port = address.rsplit_once(':').unwrap().1;
+    let request = Request {
+        address: Address {
+            uri: format!("https://{host}:{port}/"),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let executor = app
+        .executor(&request)
+        .map_err(|notification| notification.message().unwrap_or_default().to_string())?;
+    engine::runtime()
+        .block_on(executor.execute(&request))
+        .map(|response| response.body)
+        .map_err(|error| error.to_string())
+}
+
+#[test]
+fn trusts_extra_ca_certificates() {
+    let address = serve();
+    let mut app = app(&address, &["tls.test"]);
+
+    assert!(get(&mut app, "tls.test", &address).is_err());
+
+    app.tls.root_certificates = vec![fixture("ca.pem")];
+    assert_eq!(Ok("ok".to_string()), get(&mut app, "tls.test", &address));
+
+    // DER files are read too
+    app.tls.root_certificates = vec![fixture("ca.der")];
+    assert_eq!(Ok("ok".to_string()), get(&mut app, "tls.test", &address));
+}
+
+#[test]
+fn accepts_invalid_certificates_per_environment() {
+    let address = serve();
+    let mut app = app(&address, &["tls.test", "other.test"]);
+    assert_eq!(None, app.tls_warning());
+
+    app.environments[0].accept_invalid_certificates = true;
+    assert_eq!(Ok("ok".to_string()), get(&mut app, "other.test", &address));
+    assert_eq!(
+        Some(
+            "Invalid certificates are accepted in the Local environment, responses may not come from the server they claim to."
+                .to_string()
+        ),
+        app.tls_warning()
+    );
+
+    // Without an environment certificates are checked
+    app.active_environment = None;
+    assert!(get(&mut app, "tls.test", &address).is_err());
+}
+
+#[test]
+fn accepts_invalid_hostnames_per_environment() {
+    let address = serve();
+    let mut app = app(&address, &["tls.test", "other.test"]);
+    app.tls.root_certificates = vec![fixture("ca.pem")];
+
+    assert!(get(&mut app, "other.test", &address).is_err());
+
+    app.environments[0].accept_invalid_hostnames = true;
+    assert_eq!(Ok("ok".to_string()), get(&mut app, "other.test", &address));
+    assert!(
+        app.tls_warning()
+            .is_some_and(|warning| warning.starts_with("Certificates for other hostnames are"))
+    );
+}
+
+#[test]
+fn explains_invalid_settings() {
+    let settings = TlsSettings {
+        connect_to: vec![
+            ("api.example.com".to_string(), "10.0.0.5".to_string()),
+            ("ipv6.example.com".to_string(), "[::1]:8443".to_string()),
+            (String::new(), String::new()),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        Ok(vec![
+            ("api.example.com".to_string(), "10.0.0.5:0".parse().unwrap()),
+            (
+                "ipv6.example.com".to_string(),
+                "[::1]:8443".parse().unwrap()
+            ),
+        ]),
+        settings.addresses()
+    );
+
+    let settings = TlsSettings {
+        connect_to: vec![("api.example.com".to_string(), "staging".to_string())],
+        root_certificates: vec![fixture("missing.pem")],
+        ..Default::default()
+    };
+    assert_eq!(
+        Err(
+            "api.example.com can't be connected to at staging, enter an IP address and optional port."
+                .to_string()
+        ),
+        settings.addresses()
+    );
+    assert!(
+        settings
+            .certificates()
+            .is_err_and(|error| error.starts_with("Could not read"))
+    );
+
+    assert_eq!(None, TlsVersion::DEFAULT.version());
+    assert_eq!(
+        Some(reqwest::tls::Version::TLS_1_2),
+        TlsVersion::TLS1_2.version()
+    );
+}
+
+#[test]
+fn connects_overridden_hosts_on_the_url_port() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut buffer = [0u8; 4096];
+            let _ = stream.read(&mut buffer);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok");
+        }
+    });
+
+    let get = |connect_to: &str, uri: &str| {
+        let mut app = Reqwestur {
+            tls: TlsSettings {
+                connect_to: vec![("api.test".to_string(), connect_to.to_string())],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let request = Request {
+            address: Address {
+                uri: uri.to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let executor = app.executor(&request).ok().unwrap();
+        engine::runtime()
+            .block_on(executor.execute(&request))
+            .map(|response| response.body)
+    };
+
+    // The URL's port is used when it has one, otherwise the address's
+    let port = address.port();
+    assert!(get("127.0.0.1", &format!("http://api.test:{port}/")).is_ok_and(|body| body == "ok"));
+    assert!(get("127.0.0.1:1", &format!("http://api.test:{port}/")).is_ok());
+    assert!(get(&address.to_string(), "http://api.test/").is_ok_and(|body| body == "ok"));
+
+    // Settings saved before the rename are read
+    let settings: TlsSettings =
+        ron::from_str(r#"(server_names: [("api.test", "10.0.0.5")])"#).unwrap();
+    assert_eq!(
+        vec![("api.test".to_string(), "10.0.0.5".to_string())],
+        settings.connect_to
+    );
+}
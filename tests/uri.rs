@@ -0,0 +1,128 @@
+use reqwestur::utils::{
+    engine::{ClientSettings, RequestExecutor},
+    request::{Address, Request, RequestEvent},
+    reqwestur::Reqwestur,
+    uri,
+};
+
+fn normalised(text: &str) -> (String, Vec<String>) {
+    let (url, notes) = uri::normalise(text).unwrap();
+    (url.to_string(), notes)
+}
+
+#[test]
+fn defaults_to_https() {
+    assert_eq!(
+        (
+            "https://example.com/users".to_string(),
+            vec!["No scheme was given, so https:// is used.".to_string()]
+        ),
+        normalised("example.com/users")
+    );
+
+    // A host and port isn't mistaken for a scheme
+    assert_eq!(
+        "https://localhost:8080/health",
+        normalised("localhost:8080/health").0
+    );
+
+    assert_eq!(
+        ("http://localhost:8080/".to_string(), vec![]),
+        normalised("http://localhost:8080")
+    );
+    assert_eq!(
+        ("https://example.com/?page=2".to_string(), vec![]),
+        normalised("https://example.com?page=2")
+    );
+}
+
+#[test]
+fn encodes_international_hosts_and_paths() {
+    let (url, notes) = normalised(" https://bücher.example/straße?q=a b");
+
+    assert_eq!("https://xn--bcher-kva.example/stra%C3%9Fe?q=a%20b", url);
+    assert_eq!(
+        vec![
+            "The spaces around the URL are removed.",
+            "The host bücher.example is sent as xn--bcher-kva.example.",
+            "The path and query are sent encoded as /stra%C3%9Fe?q=a%20b",
+        ],
+        notes
+    );
+
+    // The engine sends the same URL
+    let built = RequestExecutor::new(ClientSettings::default())
+        .expect("the client should build")
+        .build(&Request {
+            address: Address {
+                uri: "bücher.example/a b".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!("https://xn--bcher-kva.example/a%20b", built.url().as_str());
+}
+
+#[test]
+fn explains_invalid_uris() {
+    let error = |text: &str| uri::normalise(text).unwrap_err();
+
+    assert_eq!("Enter the URL to send the request to.", error("  "));
+    assert_eq!(
+        "Only http:// and https:// URLs can be sent, not ftp://.",
+        error("ftp://example.com/file")
+    );
+    assert_eq!("The URL needs a host.", error("https://"));
+    assert_eq!(
+        "The port must be a number from 1 to 65535.",
+        error("https://example.com:99999/")
+    );
+    assert_eq!(
+        "The port must be a number from 1 to 65535.",
+        error("example.com:0")
+    );
+    assert_eq!(
+        "The host isn't a valid domain name.",
+        error("https://exa mple.com")
+    );
+    assert_eq!(
+        "The host isn't a valid IPv6 address.",
+        error("http://[::1/")
+    );
+}
+
+#[test]
+fn validates_the_address_before_sending() {
+    let variables = vec![("host".to_string(), "api.example.com".to_string())];
+    let mut address = Address::default();
+
+    address.validate(&variables);
+    assert_eq!(None, address.notification.message());
+
+    address.uri = "https://{{host}}/users".to_string();
+    address.validate(&variables);
+    assert_eq!(None, address.notification.message());
+
+    address.uri = "https://{{domain}}/users".to_string();
+    address.validate(&variables);
+    assert_eq!(
+        Some("No value for {{domain}} in the active environment."),
+        address.notification.message()
+    );
+    assert!(!address.notification.is_error());
+
+    address.uri = "https://{{host}}:70000/".to_string();
+    address.validate(&variables);
+    assert!(address.notification.is_error());
+
+    // Invalid requests aren't sent
+    let mut app = Reqwestur::default();
+    let mut request = Request {
+        address,
+        ..Default::default()
+    };
+    assert!(app.send(&mut request).is_err());
+    assert!(request.event == RequestEvent::UNSENT);
+    assert!(request.notification.is_error());
+}